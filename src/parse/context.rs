@@ -4,8 +4,11 @@
 
 use thiserror::Error;
 
-use crate::parse::proto::extensions::SimpleExtensionUrn;
+use crate::parse::proto::extensions::{
+    ExtensionFunction, ExtensionType, ExtensionTypeVariation, SimpleExtensionUrn,
+};
 use crate::parse::{Anchor, Parse};
+use crate::urn::Urn;
 
 /// A parse context.
 ///
@@ -34,6 +37,72 @@ pub enum ContextError {
     #[error("duplicate anchor `{0}` for simple extension")]
     DuplicateSimpleExtension(Anchor<SimpleExtensionUrn>),
 
+    /// Undefined reference to extension function.
+    #[error("undefined reference to extension function with anchor `{0}`")]
+    UndefinedExtensionFunction(Anchor<ExtensionFunction>),
+
+    /// Duplicate anchor for extension function.
+    #[error("duplicate anchor `{0}` for extension function")]
+    DuplicateExtensionFunction(Anchor<ExtensionFunction>),
+
+    /// Undefined reference to extension type.
+    #[error("undefined reference to extension type with anchor `{0}`")]
+    UndefinedExtensionType(Anchor<ExtensionType>),
+
+    /// Duplicate anchor for extension type.
+    #[error("duplicate anchor `{0}` for extension type")]
+    DuplicateExtensionType(Anchor<ExtensionType>),
+
+    /// Undefined reference to extension type variation.
+    #[error("undefined reference to extension type variation with anchor `{0}`")]
+    UndefinedExtensionTypeVariation(Anchor<ExtensionTypeVariation>),
+
+    /// Duplicate anchor for extension type variation.
+    #[error("duplicate anchor `{0}` for extension type variation")]
+    DuplicateExtensionTypeVariation(Anchor<ExtensionTypeVariation>),
+
+    /// No extension type is declared with a name.
+    #[error("no extension type is declared with name `{0}`")]
+    UndeclaredExtensionType(String),
+
+    /// More than one extension type is declared with a name.
+    #[error("more than one extension type is declared with name `{0}`")]
+    AmbiguousExtensionType(String),
+
+    /// No extension type variation is declared with a name.
+    #[error("no extension type variation is declared with name `{0}`")]
+    UndeclaredExtensionTypeVariation(String),
+
+    /// More than one extension type variation is declared with a name.
+    #[error("more than one extension type variation is declared with name `{0}`")]
+    AmbiguousExtensionTypeVariation(String),
+
+    /// No registry is set to resolve declarations against.
+    #[error("no registry is set to resolve declarations against")]
+    NoRegistry,
+
+    /// Simple extension not loaded in the registry.
+    #[error("simple extension `{0}` is not loaded in the registry")]
+    UnknownSimpleExtension(Urn),
+
+    /// Function not defined by a simple extension.
+    #[error("simple extension `{urn}` does not define function `{name}`")]
+    UnknownFunction {
+        /// The urn of the simple extension.
+        urn: Urn,
+        /// The (compound) name of the function.
+        name: String,
+    },
+
+    /// Function signature not defined by a simple extension.
+    #[error("simple extension `{urn}` does not define an implementation of function `{name}`")]
+    UnknownFunctionSignature {
+        /// The urn of the simple extension.
+        urn: Urn,
+        /// The compound name of the function.
+        name: String,
+    },
+
     /// Unsupported simple extension urn.
     #[error("unsupported simple extension urn: {0}")]
     UnsupportedUrn(String),
@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing of [proto::expression::Cast].
+
+use crate::{
+    parse::{
+        Context, Parse,
+        proto::{
+            ExtensionAnchors,
+            expression::{Expression, ExpressionError, required},
+        },
+    },
+    proto::{self, expression::cast::FailureBehavior},
+};
+
+/// A parsed [proto::expression::Cast].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cast<'a> {
+    /// The type to cast to.
+    type_: proto::Type,
+
+    /// The value to cast.
+    input: Expression<'a>,
+
+    /// The behavior when the cast fails.
+    failure_behavior: FailureBehavior,
+}
+
+impl<'a> Cast<'a> {
+    /// Returns the type to cast to.
+    ///
+    /// See the `type` field of [proto::expression::Cast].
+    pub fn type_(&self) -> &proto::Type {
+        &self.type_
+    }
+
+    /// Returns the value to cast.
+    ///
+    /// See [proto::expression::Cast::input].
+    pub fn input(&self) -> &Expression<'a> {
+        &self.input
+    }
+
+    /// Returns the behavior when the cast fails, which may be
+    /// [FailureBehavior::Unspecified].
+    ///
+    /// See [proto::expression::Cast::failure_behavior].
+    pub fn failure_behavior(&self) -> FailureBehavior {
+        self.failure_behavior
+    }
+}
+
+impl<'a> Parse<ExtensionAnchors<'a>> for proto::expression::Cast {
+    type Parsed = Cast<'a>;
+    type Error = ExpressionError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'a>) -> Result<Self::Parsed, Self::Error> {
+        let proto::expression::Cast {
+            r#type,
+            input,
+            failure_behavior,
+        } = self;
+
+        // The type to cast to is required, and must be valid.
+        let type_ = r#type.ok_or(ExpressionError::MissingField {
+            expression: "cast",
+            field: "type",
+        })?;
+        ctx.parse(type_.clone())?;
+
+        Ok(Cast {
+            type_,
+            input: required(input.map(|input| *input), "cast", "input", ctx)?,
+            failure_behavior: FailureBehavior::try_from(failure_behavior)
+                .map_err(|_| ExpressionError::FailureBehavior(failure_behavior))?,
+        })
+    }
+}
+
+impl From<Cast<'_>> for proto::expression::Cast {
+    fn from(cast: Cast<'_>) -> Self {
+        let Cast {
+            type_,
+            input,
+            failure_behavior,
+        } = cast;
+
+        proto::expression::Cast {
+            r#type: Some(type_),
+            input: Some(Box::new(input.into())),
+            failure_behavior: failure_behavior.into(),
+        }
+    }
+}
@@ -0,0 +1,173 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing of [proto::expression::IfThen] and
+//! [proto::expression::SwitchExpression].
+
+use crate::{
+    parse::{
+        Context, Parse,
+        proto::{
+            ExtensionAnchors,
+            expression::{Expression, ExpressionError, parse_literal, required},
+        },
+    },
+    proto::{
+        self,
+        expression::{Literal, if_then::IfClause, switch_expression::IfValue},
+    },
+};
+
+/// A parsed [proto::expression::IfThen].
+#[derive(Clone, Debug, PartialEq)]
+pub struct IfThen<'a> {
+    /// The if clauses, as conditions and results, at least one.
+    ifs: Vec<(Expression<'a>, Expression<'a>)>,
+
+    /// The result if no condition holds.
+    else_: Option<Expression<'a>>,
+}
+
+impl<'a> IfThen<'a> {
+    /// Returns the if clauses, as conditions and results. There is at least
+    /// one clause.
+    ///
+    /// See [proto::expression::IfThen::ifs].
+    pub fn ifs(&self) -> &[(Expression<'a>, Expression<'a>)] {
+        &self.ifs
+    }
+
+    /// Returns the result if no condition holds, if any.
+    ///
+    /// See the `else` field of [proto::expression::IfThen].
+    pub fn else_(&self) -> Option<&Expression<'a>> {
+        self.else_.as_ref()
+    }
+}
+
+impl<'a> Parse<ExtensionAnchors<'a>> for proto::expression::IfThen {
+    type Parsed = IfThen<'a>;
+    type Error = ExpressionError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'a>) -> Result<Self::Parsed, Self::Error> {
+        let proto::expression::IfThen { ifs, r#else } = self;
+
+        if ifs.is_empty() {
+            return Err(ExpressionError::EmptyIfThen);
+        }
+
+        Ok(IfThen {
+            ifs: ifs
+                .into_iter()
+                .map(|IfClause { r#if, then }| {
+                    Ok((
+                        required(r#if, "if clause", "condition", ctx)?,
+                        required(then, "if clause", "result", ctx)?,
+                    ))
+                })
+                .collect::<Result<_, ExpressionError>>()?,
+            else_: r#else.map(|else_| ctx.parse(*else_)).transpose()?,
+        })
+    }
+}
+
+impl From<IfThen<'_>> for proto::expression::IfThen {
+    fn from(if_then: IfThen<'_>) -> Self {
+        let IfThen { ifs, else_ } = if_then;
+
+        proto::expression::IfThen {
+            ifs: ifs
+                .into_iter()
+                .map(|(condition, result)| IfClause {
+                    r#if: Some(condition.into()),
+                    then: Some(result.into()),
+                })
+                .collect(),
+            r#else: else_.map(|else_| Box::new(else_.into())),
+        }
+    }
+}
+
+/// A parsed [proto::expression::SwitchExpression].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Switch<'a> {
+    /// The value to match.
+    match_: Expression<'a>,
+
+    /// The cases, as literals to match and results.
+    ifs: Vec<(Literal, Expression<'a>)>,
+
+    /// The result if no literal matches.
+    else_: Option<Expression<'a>>,
+}
+
+impl<'a> Switch<'a> {
+    /// Returns the value to match.
+    ///
+    /// See the `match` field of [proto::expression::SwitchExpression].
+    pub fn match_(&self) -> &Expression<'a> {
+        &self.match_
+    }
+
+    /// Returns the cases, as literals to match and results.
+    ///
+    /// See [proto::expression::SwitchExpression::ifs].
+    pub fn ifs(&self) -> &[(Literal, Expression<'a>)] {
+        &self.ifs
+    }
+
+    /// Returns the result if no literal matches, if any.
+    ///
+    /// See the `else` field of [proto::expression::SwitchExpression].
+    pub fn else_(&self) -> Option<&Expression<'a>> {
+        self.else_.as_ref()
+    }
+}
+
+impl<'a> Parse<ExtensionAnchors<'a>> for proto::expression::SwitchExpression {
+    type Parsed = Switch<'a>;
+    type Error = ExpressionError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'a>) -> Result<Self::Parsed, Self::Error> {
+        let proto::expression::SwitchExpression {
+            r#match,
+            ifs,
+            r#else,
+        } = self;
+
+        Ok(Switch {
+            match_: required(r#match.map(|m| *m), "switch expression", "match", ctx)?,
+            ifs: ifs
+                .into_iter()
+                .map(|IfValue { r#if, then }| {
+                    let literal = r#if.ok_or(ExpressionError::MissingField {
+                        expression: "switch case",
+                        field: "literal",
+                    })?;
+                    Ok((
+                        parse_literal(literal, ctx)?,
+                        required(then, "switch case", "result", ctx)?,
+                    ))
+                })
+                .collect::<Result<_, ExpressionError>>()?,
+            else_: r#else.map(|else_| ctx.parse(*else_)).transpose()?,
+        })
+    }
+}
+
+impl From<Switch<'_>> for proto::expression::SwitchExpression {
+    fn from(switch: Switch<'_>) -> Self {
+        let Switch { match_, ifs, else_ } = switch;
+
+        proto::expression::SwitchExpression {
+            r#match: Some(Box::new(match_.into())),
+            ifs: ifs
+                .into_iter()
+                .map(|(literal, result)| IfValue {
+                    r#if: Some(literal),
+                    then: Some(result.into()),
+                })
+                .collect(),
+            r#else: else_.map(|else_| Box::new(else_.into())),
+        }
+    }
+}
@@ -0,0 +1,425 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing of [proto::expression::ScalarFunction] and
+//! [proto::FunctionArgument].
+
+use crate::{
+    parse::{
+        Anchor, Context, Parse,
+        proto::{
+            ExtensionAnchors, ResolvedFunction,
+            expression::{Expression, ExpressionError},
+            extensions::ExtensionFunction,
+        },
+        text::simple_extensions::{
+            ScalarFunction as FunctionDefinition, ScalarFunctionImpl, argument::ArgumentsItem,
+        },
+    },
+    proto::{self, function_argument::ArgType},
+};
+
+/// A parsed [proto::expression::ScalarFunction].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScalarFunction<'a> {
+    /// The anchor of the declared function.
+    anchor: Anchor<ExtensionFunction>,
+
+    /// The definition and implementation of the function, if resolved.
+    function: Option<(&'a FunctionDefinition, &'a ScalarFunctionImpl)>,
+
+    /// The arguments of the call.
+    arguments: Vec<FunctionArgument<'a>>,
+
+    /// The options of the call.
+    options: Vec<proto::FunctionOption>,
+
+    /// The output type of the call.
+    output_type: proto::Type,
+}
+
+impl<'a> ScalarFunction<'a> {
+    /// Returns the anchor of the declared function.
+    ///
+    /// See [proto::expression::ScalarFunction::function_reference].
+    pub fn anchor(&self) -> Anchor<ExtensionFunction> {
+        self.anchor
+    }
+
+    /// Returns the definition of the function, if the function was resolved
+    /// against a [Registry](crate::parse::text::simple_extensions::Registry).
+    pub fn definition(&self) -> Option<&'a FunctionDefinition> {
+        self.function.map(|(definition, _)| definition)
+    }
+
+    /// Returns the implementation of the function the call is bound to, if
+    /// the function was resolved against a
+    /// [Registry](crate::parse::text::simple_extensions::Registry).
+    pub fn implementation(&self) -> Option<&'a ScalarFunctionImpl> {
+        self.function.map(|(_, implementation)| implementation)
+    }
+
+    /// Returns the arguments of the call.
+    ///
+    /// See [proto::expression::ScalarFunction::arguments].
+    pub fn arguments(&self) -> &[FunctionArgument<'a>] {
+        &self.arguments
+    }
+
+    /// Returns the options of the call.
+    ///
+    /// See [proto::expression::ScalarFunction::options].
+    pub fn options(&self) -> &[proto::FunctionOption] {
+        &self.options
+    }
+
+    /// Returns the output type of the call, as set by the producer.
+    ///
+    /// See [proto::expression::ScalarFunction::output_type].
+    pub fn output_type(&self) -> &proto::Type {
+        &self.output_type
+    }
+}
+
+/// A parsed [proto::FunctionArgument].
+#[derive(Clone, Debug, PartialEq)]
+pub enum FunctionArgument<'a> {
+    /// See [ArgType::Enum].
+    Enum(String),
+
+    /// See [ArgType::Type].
+    Type(proto::Type),
+
+    /// See [ArgType::Value].
+    Value(Expression<'a>),
+}
+
+impl FunctionArgument<'_> {
+    /// Returns the kind of this argument, as used in errors.
+    fn kind(&self) -> &'static str {
+        match self {
+            FunctionArgument::Enum(_) => "an enum",
+            FunctionArgument::Type(_) => "a type",
+            FunctionArgument::Value(_) => "a value",
+        }
+    }
+}
+
+/// Returns the kind of a declared argument, as used in errors.
+fn declared_kind(argument: &ArgumentsItem) -> &'static str {
+    match argument {
+        ArgumentsItem::EnumArgument(_) => "an enum",
+        ArgumentsItem::TypeArgument(_) => "a type",
+        ArgumentsItem::ValueArgument(_) => "a value",
+    }
+}
+
+/// Check the arguments of a call against the declared arguments of the
+/// implementation it is bound to. The last declared argument is repeated for
+/// variadic functions.
+fn check_arguments(
+    function: &str,
+    implementation: &ScalarFunctionImpl,
+    arguments: &[FunctionArgument<'_>],
+) -> Result<(), ExpressionError> {
+    implementation
+        .check_argument_count(arguments.len())
+        .map_err(|reason| ExpressionError::ArgumentCount {
+            function: function.to_string(),
+            reason,
+        })?;
+    let declared = &implementation.args;
+    for (index, argument) in arguments.iter().enumerate() {
+        let expected = declared_kind(&declared[index.min(declared.len() - 1)]);
+        if argument.kind() != expected {
+            return Err(ExpressionError::ArgumentKind {
+                function: function.to_string(),
+                index,
+                expected,
+                actual: argument.kind(),
+            });
+        }
+    }
+    Ok(())
+}
+
+impl<'a> Parse<ExtensionAnchors<'a>> for proto::expression::ScalarFunction {
+    type Parsed = ScalarFunction<'a>;
+    type Error = ExpressionError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'a>) -> Result<Self::Parsed, Self::Error> {
+        #[allow(deprecated)]
+        let proto::expression::ScalarFunction {
+            function_reference,
+            arguments,
+            options,
+            output_type,
+            args,
+        } = self;
+
+        if !args.is_empty() {
+            return Err(ExpressionError::DeprecatedArgs);
+        }
+
+        // The function must be declared, and is bound to its implementation
+        // when a registry is available.
+        let anchor = Anchor::new(function_reference);
+        let name = ctx.get_extension_function(&anchor)?.name().to_string();
+        let function = match ctx.registry() {
+            Some(_) => match ctx.resolve_extension_function(&anchor)? {
+                ResolvedFunction::Scalar(definition, implementation) => {
+                    Some((definition, implementation))
+                }
+                resolved => {
+                    return Err(ExpressionError::FunctionKind {
+                        function: name,
+                        expected: "scalar",
+                        actual: resolved.kind(),
+                    });
+                }
+            },
+            None => None,
+        };
+
+        let arguments = arguments
+            .into_iter()
+            .map(|argument| ctx.parse(argument))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if let Some((_, implementation)) = function {
+            check_arguments(&name, implementation, &arguments)?;
+        }
+
+        // The output type is required.
+        let output_type = output_type.ok_or(ExpressionError::MissingField {
+            expression: "scalar function",
+            field: "output type",
+        })?;
+        ctx.parse(output_type.clone())?;
+
+        Ok(ScalarFunction {
+            anchor,
+            function,
+            arguments,
+            options,
+            output_type,
+        })
+    }
+}
+
+impl From<ScalarFunction<'_>> for proto::expression::ScalarFunction {
+    fn from(function: ScalarFunction<'_>) -> Self {
+        let ScalarFunction {
+            anchor,
+            function: _,
+            arguments,
+            options,
+            output_type,
+        } = function;
+
+        #[allow(deprecated)]
+        proto::expression::ScalarFunction {
+            function_reference: anchor.into_inner(),
+            arguments: arguments.into_iter().map(Into::into).collect(),
+            options,
+            output_type: Some(output_type),
+            args: Vec::new(),
+        }
+    }
+}
+
+/// Parse a [proto::AggregateFunction], keeping it as is.
+///
+/// Like scalar function calls, the function must be declared, and is bound to
+/// an aggregate function implementation when the [ExtensionAnchors] have a
+/// registry. The arguments and output type are parsed, the sort fields are
+/// left to the caller.
+pub(crate) fn parse_aggregate_function(
+    function: proto::AggregateFunction,
+    ctx: &mut ExtensionAnchors<'_>,
+) -> Result<proto::AggregateFunction, ExpressionError> {
+    #[allow(deprecated)]
+    if !function.args.is_empty() {
+        return Err(ExpressionError::DeprecatedArgs);
+    }
+
+    let anchor = Anchor::new(function.function_reference);
+    let name = ctx.get_extension_function(&anchor)?.name().to_string();
+    let implementation = match ctx.registry() {
+        Some(_) => match ctx.resolve_extension_function(&anchor)? {
+            ResolvedFunction::Aggregate(_, implementation) => Some(&implementation.impl_),
+            resolved => {
+                return Err(ExpressionError::FunctionKind {
+                    function: name,
+                    expected: "aggregate",
+                    actual: resolved.kind(),
+                });
+            }
+        },
+        None => None,
+    };
+
+    let arguments = function
+        .arguments
+        .iter()
+        .map(|argument| ctx.parse(argument.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    if let Some(implementation) = implementation {
+        check_arguments(&name, implementation, &arguments)?;
+    }
+
+    // The output type is required.
+    let output_type = function
+        .output_type
+        .as_ref()
+        .ok_or(ExpressionError::MissingField {
+            expression: "aggregate function",
+            field: "output type",
+        })?;
+    ctx.parse(output_type.clone())?;
+
+    Ok(function)
+}
+
+impl<'a> Parse<ExtensionAnchors<'a>> for proto::FunctionArgument {
+    type Parsed = FunctionArgument<'a>;
+    type Error = ExpressionError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'a>) -> Result<Self::Parsed, Self::Error> {
+        let proto::FunctionArgument { arg_type } = self;
+
+        // The argument type is a required oneof.
+        Ok(
+            match arg_type.ok_or(ExpressionError::MissingField {
+                expression: "function argument",
+                field: "argument type",
+            })? {
+                ArgType::Enum(value) => FunctionArgument::Enum(value),
+                ArgType::Type(type_) => {
+                    ctx.parse(type_.clone())?;
+                    FunctionArgument::Type(type_)
+                }
+                ArgType::Value(value) => FunctionArgument::Value(ctx.parse(value)?),
+            },
+        )
+    }
+}
+
+impl From<FunctionArgument<'_>> for proto::FunctionArgument {
+    fn from(argument: FunctionArgument<'_>) -> Self {
+        proto::FunctionArgument {
+            arg_type: Some(match argument {
+                FunctionArgument::Enum(value) => ArgType::Enum(value),
+                FunctionArgument::Type(type_) => ArgType::Type(type_),
+                FunctionArgument::Value(value) => ArgType::Value(value.into()),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{
+        context::ContextError,
+        proto::{
+            expression::tests::{anchors, call, i32_literal, i32_type, registry},
+            rel::tests::field,
+        },
+        text::simple_extensions::RejectionReason,
+    };
+
+    #[test]
+    fn bind_function() -> Result<(), ExpressionError> {
+        let registry = registry();
+        let mut ctx = anchors(Some(&registry));
+
+        let add = call(
+            1,
+            vec![
+                ArgType::Enum("SILENT".to_string()),
+                ArgType::Value(field(0)),
+                ArgType::Value(i32_literal(1)),
+            ],
+            i32_type(),
+        );
+        let Expression::ScalarFunction(function) = ctx.parse(add)? else {
+            panic!("expected a scalar function");
+        };
+        assert_eq!(function.definition().map(|f| f.name.as_str()), Some("add"));
+        assert_eq!(
+            function.implementation().map(|i| i.return_type.to_string()),
+            Some("i32".to_string())
+        );
+        assert_eq!(function.arguments().len(), 3);
+
+        // Variadic functions accept repeated arguments
+        let value = |value: &str| {
+            ArgType::Value(proto::Expression {
+                rex_type: Some(proto::expression::RexType::Literal(
+                    proto::expression::Literal {
+                        literal_type: Some(proto::expression::literal::LiteralType::String(
+                            value.to_string(),
+                        )),
+                        ..Default::default()
+                    },
+                )),
+            })
+        };
+        assert!(
+            ctx.parse(call(
+                2,
+                vec![value("a"), value("b"), value("c")],
+                i32_type()
+            ))
+            .is_ok()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        let registry = registry();
+        let mut ctx = anchors(Some(&registry));
+
+        assert_eq!(
+            ctx.parse(call(1, vec![ArgType::Value(field(0))], i32_type())),
+            Err(ExpressionError::ArgumentCount {
+                function: "add".to_string(),
+                reason: RejectionReason::ArgumentCount {
+                    expected: 3,
+                    actual: 1
+                }
+            })
+        );
+        assert_eq!(
+            ctx.parse(call(
+                1,
+                vec![
+                    ArgType::Value(field(0)),
+                    ArgType::Value(field(0)),
+                    ArgType::Value(field(1)),
+                ],
+                i32_type()
+            )),
+            Err(ExpressionError::ArgumentKind {
+                function: "add".to_string(),
+                index: 0,
+                expected: "an enum",
+                actual: "a value"
+            })
+        );
+        assert_eq!(
+            ctx.parse(call(2, vec![], i32_type())),
+            Err(ExpressionError::ArgumentCount {
+                function: "concat".to_string(),
+                reason: RejectionReason::TooFewArguments { min: 1, actual: 0 }
+            })
+        );
+        assert_eq!(
+            ctx.parse(call(3, vec![], i32_type())),
+            Err(ExpressionError::Context(
+                ContextError::UndefinedExtensionFunction(Anchor::new(3))
+            ))
+        );
+    }
+}
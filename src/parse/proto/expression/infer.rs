@@ -0,0 +1,803 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Type inference for [proto::Expression]s.
+//!
+//! The type of an expression is derived from the schema of the input of the
+//! relation it is evaluated on: field references select fields of the input,
+//! and scalar functions return the type derived from their implementation in
+//! the [Registry].
+
+use thiserror::Error;
+
+use crate::{
+    parse::{
+        Anchor,
+        context::ContextError,
+        proto::{
+            ExtensionAnchors, TypeError,
+            expression::reference::{
+                FieldReferenceError, resolve_field_reference, resolve_reference_type,
+            },
+            rel::{SchemaError, output_schema_with_outer},
+            resolve_function,
+            types::concrete_type,
+        },
+        text::simple_extensions::{
+            BasicBuiltinType, ConcreteType, ConcreteTypeKind, Registry, RejectionReason,
+        },
+    },
+    proto::{
+        self,
+        expression::{
+            FieldReference, Literal, RexType,
+            cast::FailureBehavior,
+            field_reference,
+            literal::{LiteralType, interval_day_to_second::PrecisionMode},
+            subquery::SubqueryType,
+        },
+        function_argument::ArgType,
+        r#type::{Kind, Nullability},
+    },
+};
+
+/// Errors inferring the type of a [proto::Expression].
+#[derive(Debug, Error, PartialEq)]
+pub enum InferTypeError {
+    /// The expression type is missing.
+    #[error("expression type must be specified")]
+    Missing,
+
+    /// The type of the expression can not be inferred.
+    #[error("cannot infer the type of {0}")]
+    Unsupported(&'static str),
+
+    /// A required field of an expression is missing.
+    #[error("{expression} {field} must be specified")]
+    MissingField {
+        /// The expression with the missing field.
+        expression: &'static str,
+        /// The missing field.
+        field: &'static str,
+    },
+
+    /// The field reference can not be resolved.
+    #[error(transparent)]
+    FieldReference(#[from] FieldReferenceError),
+
+    /// Values that must have the same type (e.g. the branches of an if-then
+    /// or the elements of a list literal) have different types.
+    #[error("{context} has type `{actual}`, expected `{expected}`")]
+    TypeMismatch {
+        /// The value with the different type.
+        context: &'static str,
+        /// The type of the first value.
+        expected: Box<ConcreteType>,
+        /// The different type.
+        actual: Box<ConcreteType>,
+    },
+
+    /// The arguments of a function call do not match the implementation of
+    /// the function.
+    #[error("invalid arguments for function `{function}`: {reason}")]
+    Function {
+        /// The (compound) name of the function.
+        function: String,
+        /// Why the arguments do not match.
+        reason: RejectionReason,
+    },
+
+    /// The output type of a function call does not match the return type of
+    /// the function.
+    #[error(
+        "output type `{declared}` of function `{function}` does not match its return type `{derived}`"
+    )]
+    OutputType {
+        /// The (compound) name of the function.
+        function: String,
+        /// The output type of the call.
+        declared: Box<ConcreteType>,
+        /// The return type derived from the implementation of the function.
+        derived: Box<ConcreteType>,
+    },
+
+    /// The schema of a subquery can not be derived.
+    #[error("invalid subquery")]
+    Subquery(#[source] Box<SchemaError>),
+
+    /// Type error
+    #[error(transparent)]
+    Type(#[from] TypeError),
+
+    /// Context error
+    #[error(transparent)]
+    Context(#[from] ContextError),
+}
+
+/// Infer the type of an expression evaluated on records of the given input
+/// schema, a struct type.
+///
+/// Scalar functions are resolved against the registry, and their return type
+/// is derived from the types of their arguments and the nullability handling
+/// of their implementation. The output type embedded in the call must match
+/// the derived return type; type variations are not compared.
+///
+/// If-then and switch expressions have the type of their branches, which
+/// must match, and are nullable if any branch is nullable or there is no
+/// else branch. The conditions of if-then expressions must be boolean.
+///
+/// Casts have the type they cast to, which is nullable if the cast returns
+/// null on failure.
+pub fn infer_type(
+    expression: &proto::Expression,
+    input_schema: &ConcreteType,
+    registry: &Registry,
+    anchors: &ExtensionAnchors<'_>,
+) -> Result<ConcreteType, InferTypeError> {
+    expression_type(expression, input_schema, &[], Some(registry), anchors)
+}
+
+/// Infer the type of an expression, see [infer_type].
+///
+/// The outer schemas are the input schemas of the queries enclosing the
+/// expression, innermost first, for outer references. Without a registry,
+/// scalar functions have the output type embedded in the call.
+pub(crate) fn expression_type(
+    expression: &proto::Expression,
+    input: &ConcreteType,
+    outer: &[ConcreteType],
+    registry: Option<&Registry>,
+    ctx: &ExtensionAnchors<'_>,
+) -> Result<ConcreteType, InferTypeError> {
+    let missing = |expression, field| InferTypeError::MissingField { expression, field };
+    let boolean = |nullable| ConcreteType::builtin(BasicBuiltinType::Boolean, nullable);
+    let infer = |expression| expression_type(expression, input, outer, registry, ctx);
+
+    match expression
+        .rex_type
+        .as_ref()
+        .ok_or(InferTypeError::Missing)?
+    {
+        RexType::Literal(literal) => literal_type(literal, ctx),
+        RexType::Selection(reference) => field_type(reference, input, outer, registry, ctx),
+        RexType::ScalarFunction(function) => {
+            let extension_function =
+                ctx.get_extension_function(&Anchor::new(function.function_reference))?;
+            let output_type = function
+                .output_type
+                .clone()
+                .map(|output_type| concrete_type(output_type, ctx))
+                .transpose()?;
+            let Some(registry) = registry else {
+                return output_type.ok_or_else(|| missing("scalar function", "output type"));
+            };
+            let implementation = resolve_function(registry, extension_function)?.implementation();
+
+            // Only value arguments are passed as data.
+            let arguments = function
+                .arguments
+                .iter()
+                .filter_map(|argument| match argument.arg_type.as_ref() {
+                    Some(ArgType::Value(value)) => Some(infer(value)),
+                    _ => None,
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let derived = registry
+                .resolve_call(extension_function.urn(), implementation, &arguments)
+                .map_err(|reason| InferTypeError::Function {
+                    function: extension_function.name().to_string(),
+                    reason,
+                })?;
+
+            match output_type {
+                Some(declared)
+                    if declared.kind != derived.kind || declared.nullable != derived.nullable =>
+                {
+                    Err(InferTypeError::OutputType {
+                        function: extension_function.name().to_string(),
+                        declared: Box::new(declared),
+                        derived: Box::new(derived),
+                    })
+                }
+                _ => Ok(derived),
+            }
+        }
+        RexType::Cast(cast) => {
+            let type_ = cast.r#type.clone().ok_or_else(|| missing("cast", "type"))?;
+            infer(
+                cast.input
+                    .as_deref()
+                    .ok_or_else(|| missing("cast", "input"))?,
+            )?;
+            let mut type_ = concrete_type(type_, ctx)?;
+            // Casts that return null on failure are nullable.
+            type_.nullable |= cast.failure_behavior == FailureBehavior::ReturnNull as i32;
+            Ok(type_)
+        }
+        RexType::IfThen(if_then) => {
+            if if_then.ifs.is_empty() {
+                return Err(missing("if-then", "if clause"));
+            }
+            for clause in &if_then.ifs {
+                let condition = infer(
+                    clause
+                        .r#if
+                        .as_ref()
+                        .ok_or_else(|| missing("if clause", "condition"))?,
+                )?;
+                if condition.kind != ConcreteTypeKind::Builtin(BasicBuiltinType::Boolean) {
+                    return Err(InferTypeError::TypeMismatch {
+                        context: "if clause condition",
+                        expected: Box::new(boolean(condition.nullable)),
+                        actual: Box::new(condition),
+                    });
+                }
+            }
+            let branches = if_then
+                .ifs
+                .iter()
+                .map(|clause| {
+                    clause
+                        .then
+                        .as_ref()
+                        .ok_or_else(|| missing("if clause", "result"))
+                })
+                .chain(if_then.r#else.as_deref().map(Ok))
+                .map(|then| infer(then?))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut type_ = unify("if-then branch", branches)?;
+            type_.nullable |= if_then.r#else.is_none();
+            Ok(type_)
+        }
+        RexType::SwitchExpression(switch) => {
+            if switch.ifs.is_empty() {
+                return Err(missing("switch", "case"));
+            }
+            let branches = switch
+                .ifs
+                .iter()
+                .map(|case| {
+                    case.then
+                        .as_ref()
+                        .ok_or_else(|| missing("switch case", "result"))
+                })
+                .chain(switch.r#else.as_deref().map(Ok))
+                .map(|then| infer(then?))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut type_ = unify("switch branch", branches)?;
+            type_.nullable |= switch.r#else.is_none();
+            Ok(type_)
+        }
+        RexType::SingularOrList(list) => {
+            let value = list
+                .value
+                .as_deref()
+                .ok_or_else(|| missing("singular or list", "value"))?;
+            let nullable = [value]
+                .into_iter()
+                .chain(&list.options)
+                .map(|value| infer(value).map(|type_| type_.nullable))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(boolean(nullable.into_iter().any(|nullable| nullable)))
+        }
+        RexType::MultiOrList(list) => {
+            let nullable = list
+                .value
+                .iter()
+                .map(|value| infer(value).map(|type_| type_.nullable))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(boolean(nullable.into_iter().any(|nullable| nullable)))
+        }
+        RexType::Subquery(subquery) => {
+            match subquery
+                .subquery_type
+                .as_ref()
+                .ok_or_else(|| missing("subquery", "subquery type"))?
+            {
+                // A scalar subquery returns the value of its single field, or
+                // null if there are no records.
+                SubqueryType::Scalar(scalar) => {
+                    let rel = scalar
+                        .input
+                        .as_ref()
+                        .ok_or_else(|| missing("subquery", "input"))?;
+                    // The input of the expression is the innermost outer
+                    // schema of the relations of the subquery.
+                    let outer = [input.clone()]
+                        .into_iter()
+                        .chain(outer.iter().cloned())
+                        .collect::<Vec<_>>();
+                    let schema = output_schema_with_outer(rel, &outer, ctx)
+                        .map_err(|error| InferTypeError::Subquery(Box::new(error)))?;
+                    match schema.kind {
+                        ConcreteTypeKind::Struct(mut fields) if fields.len() == 1 => {
+                            let mut field = fields.remove(0);
+                            field.nullable = true;
+                            Ok(field)
+                        }
+                        _ => Err(InferTypeError::Unsupported(
+                            "a scalar subquery without a single field",
+                        )),
+                    }
+                }
+                SubqueryType::InPredicate(_)
+                | SubqueryType::SetPredicate(_)
+                | SubqueryType::SetComparison(_) => Ok(boolean(false)),
+            }
+        }
+        _ => Err(InferTypeError::Unsupported("this expression type")),
+    }
+}
+
+/// Returns the common type of values that must have the same type, which is
+/// nullable if any of the values is nullable.
+fn unify(
+    context: &'static str,
+    types: impl IntoIterator<Item = ConcreteType>,
+) -> Result<ConcreteType, InferTypeError> {
+    let mut types = types.into_iter();
+    let mut unified = types
+        .next()
+        .ok_or(InferTypeError::Unsupported("an empty list of values"))?;
+    for type_ in types {
+        if type_.kind != unified.kind || type_.variation != unified.variation {
+            return Err(InferTypeError::TypeMismatch {
+                context,
+                expected: Box::new(unified),
+                actual: Box::new(type_),
+            });
+        }
+        unified.nullable |= type_.nullable;
+    }
+    Ok(unified)
+}
+
+/// Returns the type of a literal.
+pub(super) fn literal_type(
+    literal: &Literal,
+    ctx: &ExtensionAnchors<'_>,
+) -> Result<ConcreteType, InferTypeError> {
+    use BasicBuiltinType as B;
+    use ConcreteTypeKind::Builtin;
+
+    let length = |length: usize| i32::try_from(length).unwrap_or(i32::MAX);
+    // Nested and user-defined literals have the type of a proto type.
+    let type_of = |kind: Kind| -> Result<ConcreteTypeKind, InferTypeError> {
+        Ok(concrete_type(proto::Type { kind: Some(kind) }, ctx)?.kind)
+    };
+
+    let kind = match literal
+        .literal_type
+        .as_ref()
+        .ok_or(InferTypeError::MissingField {
+            expression: "literal",
+            field: "literal type",
+        })? {
+        LiteralType::Boolean(_) => Builtin(B::Boolean),
+        LiteralType::I8(_) => Builtin(B::I8),
+        LiteralType::I16(_) => Builtin(B::I16),
+        LiteralType::I32(_) => Builtin(B::I32),
+        LiteralType::I64(_) => Builtin(B::I64),
+        LiteralType::Fp32(_) => Builtin(B::Fp32),
+        LiteralType::Fp64(_) => Builtin(B::Fp64),
+        LiteralType::String(_) => Builtin(B::String),
+        LiteralType::Binary(_) => Builtin(B::Binary),
+        LiteralType::Date(_) => Builtin(B::Date),
+        LiteralType::Time(_) => Builtin(B::Time),
+        LiteralType::IntervalYearToMonth(_) => Builtin(B::IntervalYear),
+        LiteralType::IntervalDayToSecond(interval) => Builtin(B::IntervalDay {
+            precision: interval_precision(interval),
+        }),
+        LiteralType::IntervalCompound(interval) => Builtin(B::IntervalCompound {
+            precision: interval
+                .interval_day_to_second
+                .as_ref()
+                .map(interval_precision)
+                .unwrap_or_default(),
+        }),
+        LiteralType::Uuid(_) => Builtin(B::Uuid),
+        LiteralType::FixedChar(value) => Builtin(B::FixedChar {
+            length: length(value.chars().count()),
+        }),
+        LiteralType::VarChar(var_char) => Builtin(B::VarChar {
+            length: length(var_char.length as usize),
+        }),
+        LiteralType::FixedBinary(value) => Builtin(B::FixedBinary {
+            length: length(value.len()),
+        }),
+        LiteralType::Decimal(decimal) => Builtin(B::Decimal {
+            precision: decimal.precision,
+            scale: decimal.scale,
+        }),
+        LiteralType::PrecisionTime(time) => Builtin(B::PrecisionTime {
+            precision: time.precision,
+        }),
+        LiteralType::PrecisionTimestamp(timestamp) => Builtin(B::PrecisionTimestamp {
+            precision: timestamp.precision,
+        }),
+        LiteralType::PrecisionTimestampTz(timestamp) => Builtin(B::PrecisionTimestampTz {
+            precision: timestamp.precision,
+        }),
+        #[allow(deprecated)]
+        LiteralType::Timestamp(_) => Builtin(B::Timestamp),
+        #[allow(deprecated)]
+        LiteralType::TimestampTz(_) => Builtin(B::TimestampTz),
+        LiteralType::Struct(r#struct) => ConcreteTypeKind::Struct(
+            r#struct
+                .fields
+                .iter()
+                .map(|field| literal_type(field, ctx))
+                .collect::<Result<_, _>>()?,
+        ),
+        LiteralType::List(list) => ConcreteTypeKind::List(Box::new(unify(
+            "list literal element",
+            list.values
+                .iter()
+                .map(|value| literal_type(value, ctx))
+                .collect::<Result<Vec<_>, _>>()?,
+        )?)),
+        LiteralType::Map(map) => {
+            let missing = |field| InferTypeError::MissingField {
+                expression: "map literal",
+                field,
+            };
+            let (keys, values) = map
+                .key_values
+                .iter()
+                .map(|key_value| {
+                    let key = key_value.key.as_ref().ok_or_else(|| missing("key"))?;
+                    let value = key_value.value.as_ref().ok_or_else(|| missing("value"))?;
+                    Ok((literal_type(key, ctx)?, literal_type(value, ctx)?))
+                })
+                .collect::<Result<(Vec<_>, Vec<_>), InferTypeError>>()?;
+            ConcreteTypeKind::Map {
+                key: Box::new(unify("map literal key", keys)?),
+                value: Box::new(unify("map literal value", values)?),
+            }
+        }
+        LiteralType::EmptyList(list) => type_of(Kind::List(Box::new(list.clone())))?,
+        LiteralType::EmptyMap(map) => type_of(Kind::Map(Box::new(map.clone())))?,
+        LiteralType::UserDefined(user_defined) => {
+            type_of(Kind::UserDefined(proto::r#type::UserDefined {
+                type_reference: user_defined.type_reference,
+                type_variation_reference: 0,
+                nullability: Nullability::Required as i32,
+                type_parameters: user_defined.type_parameters.clone(),
+            }))?
+        }
+        // Null literals have the type they are a null value of.
+        LiteralType::Null(type_) => return Ok(concrete_type(type_.clone(), ctx)?),
+    };
+
+    let variation = match literal.type_variation_reference {
+        0 => None,
+        anchor => Some(
+            ctx.get_extension_type_variation(&Anchor::new(anchor))?
+                .name()
+                .to_string(),
+        ),
+    };
+    Ok(ConcreteType {
+        kind,
+        nullable: literal.nullable,
+        variation,
+    })
+}
+
+/// Returns the sub-second precision of a day-time interval literal.
+fn interval_precision(interval: &proto::expression::literal::IntervalDayToSecond) -> i32 {
+    match interval.precision_mode {
+        Some(PrecisionMode::Precision(precision)) => precision,
+        // Deprecated intervals have microsecond precision.
+        #[allow(deprecated)]
+        Some(PrecisionMode::Microseconds(_)) | None => 6,
+    }
+}
+
+/// Returns the type of the field selected by a field reference.
+fn field_type(
+    reference: &FieldReference,
+    input: &ConcreteType,
+    outer: &[ConcreteType],
+    registry: Option<&Registry>,
+    ctx: &ExtensionAnchors<'_>,
+) -> Result<ConcreteType, InferTypeError> {
+    match reference.root_type.as_ref() {
+        // The root is the value of the expression.
+        Some(field_reference::RootType::Expression(expression)) => {
+            let root = expression_type(expression, input, outer, registry, ctx)?;
+            Ok(resolve_reference_type(reference, &root, ctx)?)
+        }
+        _ => Ok(resolve_field_reference(reference, input, outer, ctx)?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parse::proto::{
+            expression::tests::{anchors, call, i32_literal, i32_type, registry},
+            rel::tests::field,
+        },
+        proto::expression::{IfThen, if_then::IfClause, literal},
+    };
+
+    fn literal(literal_type: LiteralType) -> proto::Expression {
+        proto::Expression {
+            rex_type: Some(RexType::Literal(Literal {
+                literal_type: Some(literal_type),
+                ..Default::default()
+            })),
+        }
+    }
+
+    #[test]
+    fn literals() -> Result<(), InferTypeError> {
+        let registry = registry();
+        let ctx = anchors(Some(&registry));
+        let input = ConcreteType::r#struct(vec![], false);
+        let infer = |literal_type| infer_type(&literal(literal_type), &input, &registry, &ctx);
+
+        assert_eq!(
+            infer(LiteralType::Decimal(literal::Decimal {
+                value: vec![0; 16],
+                precision: 10,
+                scale: 2,
+            }))?,
+            ConcreteType::builtin(
+                BasicBuiltinType::Decimal {
+                    precision: 10,
+                    scale: 2
+                },
+                false
+            )
+        );
+        assert_eq!(
+            infer(LiteralType::PrecisionTimestampTz(
+                literal::PrecisionTimestamp {
+                    precision: 6,
+                    value: 0,
+                }
+            ))?,
+            ConcreteType::builtin(
+                BasicBuiltinType::PrecisionTimestampTz { precision: 6 },
+                false
+            )
+        );
+        assert_eq!(
+            infer(LiteralType::List(literal::List {
+                values: vec![Literal {
+                    literal_type: Some(LiteralType::I32(1)),
+                    nullable: true,
+                    ..Default::default()
+                }],
+            }))?,
+            ConcreteType {
+                kind: ConcreteTypeKind::List(Box::new(ConcreteType::builtin(
+                    BasicBuiltinType::I32,
+                    true
+                ))),
+                nullable: false,
+                variation: None,
+            }
+        );
+        assert!(matches!(
+            infer(LiteralType::List(literal::List {
+                values: vec![
+                    Literal {
+                        literal_type: Some(LiteralType::I32(1)),
+                        ..Default::default()
+                    },
+                    Literal {
+                        literal_type: Some(LiteralType::I64(1)),
+                        ..Default::default()
+                    },
+                ],
+            })),
+            Err(InferTypeError::TypeMismatch {
+                context: "list literal element",
+                ..
+            })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn scalar_function() -> Result<(), InferTypeError> {
+        let registry = registry();
+        let ctx = anchors(Some(&registry));
+        let nullable_i32 = ConcreteType::builtin(BasicBuiltinType::I32, true);
+        let input = ConcreteType::r#struct(vec![nullable_i32.clone()], false);
+        let add = |output_type| {
+            call(
+                1,
+                vec![
+                    ArgType::Enum("ERROR".to_string()),
+                    ArgType::Value(field(0)),
+                    ArgType::Value(i32_literal(1)),
+                ],
+                output_type,
+            )
+        };
+
+        // The nullability of the arguments is mirrored by the return type
+        let mut output_type = i32_type();
+        if let Some(Kind::I32(i32)) = output_type.kind.as_mut() {
+            i32.nullability = Nullability::Nullable as i32;
+        }
+        assert_eq!(
+            infer_type(&add(output_type), &input, &registry, &ctx)?,
+            nullable_i32
+        );
+        assert_eq!(
+            infer_type(&add(i32_type()), &input, &registry, &ctx),
+            Err(InferTypeError::OutputType {
+                function: "add".to_string(),
+                declared: Box::new(ConcreteType::builtin(BasicBuiltinType::I32, false)),
+                derived: Box::new(nullable_i32),
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn if_then() -> Result<(), InferTypeError> {
+        let registry = registry();
+        let ctx = anchors(Some(&registry));
+        let input = ConcreteType::r#struct(
+            vec![ConcreteType::builtin(BasicBuiltinType::Boolean, false)],
+            false,
+        );
+        let if_then = |then, r#else: Option<proto::Expression>| proto::Expression {
+            rex_type: Some(RexType::IfThen(Box::new(IfThen {
+                ifs: vec![IfClause {
+                    r#if: Some(field(0)),
+                    then: Some(then),
+                }],
+                r#else: r#else.map(Box::new),
+            }))),
+        };
+
+        assert_eq!(
+            infer_type(
+                &if_then(i32_literal(1), Some(i32_literal(2))),
+                &input,
+                &registry,
+                &ctx
+            )?,
+            ConcreteType::builtin(BasicBuiltinType::I32, false)
+        );
+
+        // Without an else branch, the result is null if no condition holds
+        assert_eq!(
+            infer_type(&if_then(i32_literal(1), None), &input, &registry, &ctx)?,
+            ConcreteType::builtin(BasicBuiltinType::I32, true)
+        );
+        assert!(matches!(
+            infer_type(
+                &if_then(
+                    i32_literal(1),
+                    Some(literal(LiteralType::String("a".to_string())))
+                ),
+                &input,
+                &registry,
+                &ctx
+            ),
+            Err(InferTypeError::TypeMismatch {
+                context: "if-then branch",
+                ..
+            })
+        ));
+
+        // Conditions must be boolean
+        let mut not_boolean = if_then(i32_literal(1), Some(i32_literal(2)));
+        if let Some(RexType::IfThen(expression)) = not_boolean.rex_type.as_mut() {
+            expression.ifs[0].r#if = Some(i32_literal(0));
+        }
+        assert!(matches!(
+            infer_type(&not_boolean, &input, &registry, &ctx),
+            Err(InferTypeError::TypeMismatch {
+                context: "if clause condition",
+                ..
+            })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn correlated_scalar_subquery() -> Result<(), InferTypeError> {
+        use crate::parse::proto::rel::tests::read_rel;
+        use crate::proto::{
+            expression::{Subquery, subquery},
+            rel::RelType,
+            rel_common,
+        };
+
+        let registry = registry();
+        let ctx = anchors(Some(&registry));
+        let input = ConcreteType::r#struct(
+            vec![ConcreteType::builtin(BasicBuiltinType::String, false)],
+            false,
+        );
+        // A projection of a field of an enclosing query onto the records of
+        // the read.
+        let subquery = |steps_out| {
+            let mut outer_field = field(0);
+            if let Some(RexType::Selection(reference)) = outer_field.rex_type.as_mut() {
+                reference.root_type = Some(field_reference::RootType::OuterReference(
+                    field_reference::OuterReference { steps_out },
+                ));
+            }
+            let project = proto::Rel {
+                rel_type: Some(RelType::Project(Box::new(proto::ProjectRel {
+                    common: Some(proto::RelCommon {
+                        emit_kind: Some(rel_common::EmitKind::Emit(rel_common::Emit {
+                            output_mapping: vec![1],
+                        })),
+                        ..Default::default()
+                    }),
+                    input: Some(Box::new(read_rel())),
+                    expressions: vec![outer_field],
+                    ..Default::default()
+                }))),
+            };
+            proto::Expression {
+                rex_type: Some(RexType::Subquery(Box::new(Subquery {
+                    subquery_type: Some(SubqueryType::Scalar(Box::new(subquery::Scalar {
+                        input: Some(Box::new(project)),
+                    }))),
+                }))),
+            }
+        };
+
+        // The input of the expression is the outer schema of the subquery
+        assert_eq!(
+            infer_type(&subquery(1), &input, &registry, &ctx)?,
+            ConcreteType::builtin(BasicBuiltinType::String, true)
+        );
+        assert!(matches!(
+            infer_type(&subquery(2), &input, &registry, &ctx),
+            Err(InferTypeError::Subquery(error)) if matches!(
+                *error,
+                SchemaError::Expression(InferTypeError::FieldReference(
+                    FieldReferenceError::OuterReference {
+                        steps_out: 2,
+                        count: 1
+                    }
+                ))
+            )
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn cast() -> Result<(), InferTypeError> {
+        let registry = registry();
+        let ctx = anchors(Some(&registry));
+        let input = ConcreteType::r#struct(vec![], false);
+        let cast = |failure_behavior: FailureBehavior| proto::Expression {
+            rex_type: Some(RexType::Cast(Box::new(proto::expression::Cast {
+                r#type: Some(i32_type()),
+                input: Some(Box::new(literal(LiteralType::String("1".to_string())))),
+                failure_behavior: failure_behavior.into(),
+            }))),
+        };
+
+        assert_eq!(
+            infer_type(
+                &cast(FailureBehavior::ThrowException),
+                &input,
+                &registry,
+                &ctx
+            )?,
+            ConcreteType::builtin(BasicBuiltinType::I32, false)
+        );
+
+        // A cast that returns null on failure is nullable
+        assert_eq!(
+            infer_type(&cast(FailureBehavior::ReturnNull), &input, &registry, &ctx)?,
+            ConcreteType::builtin(BasicBuiltinType::I32, true)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing of [proto::expression::SingularOrList] and
+//! [proto::expression::MultiOrList].
+
+use crate::{
+    parse::{
+        Context, Parse,
+        proto::{
+            ExtensionAnchors,
+            expression::{Expression, ExpressionError, required},
+        },
+    },
+    proto::{self, expression::multi_or_list::Record},
+};
+
+/// A parsed [proto::expression::SingularOrList].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SingularOrList<'a> {
+    /// The value to look up.
+    value: Expression<'a>,
+
+    /// The options to compare the value with.
+    options: Vec<Expression<'a>>,
+}
+
+impl<'a> SingularOrList<'a> {
+    /// Returns the value to look up.
+    ///
+    /// See [proto::expression::SingularOrList::value].
+    pub fn value(&self) -> &Expression<'a> {
+        &self.value
+    }
+
+    /// Returns the options to compare the value with.
+    ///
+    /// See [proto::expression::SingularOrList::options].
+    pub fn options(&self) -> &[Expression<'a>] {
+        &self.options
+    }
+}
+
+impl<'a> Parse<ExtensionAnchors<'a>> for proto::expression::SingularOrList {
+    type Parsed = SingularOrList<'a>;
+    type Error = ExpressionError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'a>) -> Result<Self::Parsed, Self::Error> {
+        let proto::expression::SingularOrList { value, options } = self;
+
+        Ok(SingularOrList {
+            value: required(value.map(|v| *v), "singular or list", "value", ctx)?,
+            options: options
+                .into_iter()
+                .map(|option| ctx.parse(option))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl From<SingularOrList<'_>> for proto::expression::SingularOrList {
+    fn from(list: SingularOrList<'_>) -> Self {
+        let SingularOrList { value, options } = list;
+
+        proto::expression::SingularOrList {
+            value: Some(Box::new(value.into())),
+            options: options.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// A parsed [proto::expression::MultiOrList].
+#[derive(Clone, Debug, PartialEq)]
+pub struct MultiOrList<'a> {
+    /// The values to look up.
+    value: Vec<Expression<'a>>,
+
+    /// The records to compare the values with, each with a field per value.
+    options: Vec<Vec<Expression<'a>>>,
+}
+
+impl<'a> MultiOrList<'a> {
+    /// Returns the values to look up.
+    ///
+    /// See [proto::expression::MultiOrList::value].
+    pub fn value(&self) -> &[Expression<'a>] {
+        &self.value
+    }
+
+    /// Returns the records to compare the values with. Each record has a
+    /// field per value.
+    ///
+    /// See [proto::expression::MultiOrList::options].
+    pub fn options(&self) -> &[Vec<Expression<'a>>] {
+        &self.options
+    }
+}
+
+impl<'a> Parse<ExtensionAnchors<'a>> for proto::expression::MultiOrList {
+    type Parsed = MultiOrList<'a>;
+    type Error = ExpressionError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'a>) -> Result<Self::Parsed, Self::Error> {
+        let proto::expression::MultiOrList { value, options } = self;
+
+        let value = value
+            .into_iter()
+            .map(|value| ctx.parse(value))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Each record is compared with the values field by field.
+        let options = options
+            .into_iter()
+            .enumerate()
+            .map(|(index, Record { fields })| {
+                if fields.len() != value.len() {
+                    return Err(ExpressionError::MultiOrListRecord {
+                        index,
+                        expected: value.len(),
+                        actual: fields.len(),
+                    });
+                }
+                fields.into_iter().map(|field| ctx.parse(field)).collect()
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(MultiOrList { value, options })
+    }
+}
+
+impl From<MultiOrList<'_>> for proto::expression::MultiOrList {
+    fn from(list: MultiOrList<'_>) -> Self {
+        let MultiOrList { value, options } = list;
+
+        proto::expression::MultiOrList {
+            value: value.into_iter().map(Into::into).collect(),
+            options: options
+                .into_iter()
+                .map(|fields| Record {
+                    fields: fields.into_iter().map(Into::into).collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::proto::{expression::tests::i32_literal, rel::tests::field};
+
+    #[test]
+    fn multi_or_list() {
+        let mut ctx = ExtensionAnchors::default();
+        let list = proto::expression::MultiOrList {
+            value: vec![field(0), field(1)],
+            options: vec![Record {
+                fields: vec![i32_literal(1), i32_literal(2)],
+            }],
+        };
+        let parsed = ctx.parse(list.clone()).unwrap();
+        assert_eq!(parsed.options().len(), 1);
+        assert_eq!(proto::expression::MultiOrList::from(parsed), list);
+
+        assert_eq!(
+            ctx.parse(proto::expression::MultiOrList {
+                options: vec![
+                    Record {
+                        fields: vec![i32_literal(1), i32_literal(2)],
+                    },
+                    Record {
+                        fields: vec![i32_literal(3)],
+                    },
+                ],
+                ..list
+            }),
+            Err(ExpressionError::MultiOrListRecord {
+                index: 1,
+                expected: 2,
+                actual: 1
+            })
+        );
+    }
+}
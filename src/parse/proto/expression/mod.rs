@@ -0,0 +1,497 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing of [proto::Expression].
+//!
+//! Expressions are parsed into a validated expression tree: required fields
+//! and oneofs are checked while parsing, and scalar function calls are bound
+//! to the declared functions of the plan. When the [ExtensionAnchors] have a
+//! [Registry](crate::parse::text::simple_extensions::Registry), calls are
+//! also bound to the implementation of the function they invoke, and their
+//! arguments are checked against it.
+//!
+//! Literals and field references are validated, but kept as their protobuf
+//! types. Expressions of other types (e.g. window functions) are kept as they
+//! are, see [Expression::Other].
+
+use thiserror::Error;
+
+use crate::{
+    parse::{
+        Anchor, Context, Parse,
+        context::ContextError,
+        proto::{ExtensionAnchors, TypeError, rel::RelError},
+        text::simple_extensions::RejectionReason,
+    },
+    proto::{
+        self,
+        expression::{FieldReference, Literal, RexType, field_reference},
+    },
+};
+
+mod cast;
+pub use cast::Cast;
+
+mod conditional;
+pub use conditional::{IfThen, Switch};
+
+mod function;
+pub(crate) use function::parse_aggregate_function;
+pub use function::{FunctionArgument, ScalarFunction};
+
+mod infer;
+pub(crate) use infer::expression_type;
+pub use infer::{InferTypeError, infer_type};
+
+mod list;
+pub use list::{MultiOrList, SingularOrList};
+
+mod reference;
+pub use reference::{FieldReferenceError, resolve_field_reference, resolve_reference_segment};
+
+mod subquery;
+pub use subquery::Subquery;
+
+/// A parsed [proto::Expression].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expression<'a> {
+    /// A literal value, which has a literal type.
+    ///
+    /// See [RexType::Literal].
+    Literal(Literal),
+
+    /// A field reference, which has a reference type and a root type.
+    ///
+    /// See [RexType::Selection].
+    FieldReference(Box<FieldReference>),
+
+    /// See [RexType::ScalarFunction].
+    ScalarFunction(ScalarFunction<'a>),
+
+    /// See [RexType::IfThen].
+    IfThen(Box<IfThen<'a>>),
+
+    /// See [RexType::SwitchExpression].
+    Switch(Box<Switch<'a>>),
+
+    /// See [RexType::SingularOrList].
+    SingularOrList(Box<SingularOrList<'a>>),
+
+    /// See [RexType::MultiOrList].
+    MultiOrList(MultiOrList<'a>),
+
+    /// See [RexType::Cast].
+    Cast(Box<Cast<'a>>),
+
+    /// See [RexType::Subquery].
+    Subquery(Box<Subquery<'a>>),
+
+    /// An expression of a type that is not parsed (e.g. a window function or
+    /// a nested expression), kept as is. Its fields and the functions it
+    /// calls are not validated.
+    Other(proto::Expression),
+}
+
+impl<'a> Expression<'a> {
+    /// Parse a [proto::Expression], binding the functions it calls with the
+    /// given [ExtensionAnchors], e.g. as populated by
+    /// [Plan::parse_with_anchors](super::Plan::parse_with_anchors).
+    pub fn parse_with_anchors(
+        expression: proto::Expression,
+        anchors: &mut ExtensionAnchors<'a>,
+    ) -> Result<Self, ExpressionError> {
+        anchors.parse(expression)
+    }
+}
+
+/// Parse errors for [proto::Expression].
+#[derive(Debug, Error, PartialEq)]
+pub enum ExpressionError {
+    /// The expression type is missing.
+    #[error("expression type must be specified")]
+    Missing,
+
+    /// A required field of an expression is missing.
+    #[error("{expression} {field} must be specified")]
+    MissingField {
+        /// The expression with the missing field.
+        expression: &'static str,
+        /// The missing field.
+        field: &'static str,
+    },
+
+    /// The deprecated arguments of a scalar function are used.
+    #[error("deprecated scalar function args are not supported, use arguments instead")]
+    DeprecatedArgs,
+
+    /// A function call invokes a function of another kind, e.g. a scalar
+    /// function call of an aggregate function.
+    #[error("function `{function}` has kind `{actual}`, expected `{expected}`")]
+    FunctionKind {
+        /// The (compound) name of the function.
+        function: String,
+        /// The kind of function the call expects.
+        expected: &'static str,
+        /// The kind of the declared function.
+        actual: &'static str,
+    },
+
+    /// The number of arguments of a function call does not match the
+    /// implementation of the function.
+    #[error("invalid arguments for function `{function}`: {reason}")]
+    ArgumentCount {
+        /// The (compound) name of the function.
+        function: String,
+        /// Why the arguments do not match.
+        reason: RejectionReason,
+    },
+
+    /// The kind of an argument of a function call does not match the
+    /// implementation of the function.
+    #[error("argument {index} of function `{function}` must be {expected} argument, got {actual}")]
+    ArgumentKind {
+        /// The (compound) name of the function.
+        function: String,
+        /// The (0-based) position of the argument.
+        index: usize,
+        /// The declared kind of argument.
+        expected: &'static str,
+        /// The kind of argument of the call.
+        actual: &'static str,
+    },
+
+    /// An if-then expression without if clauses.
+    #[error("if-then expression requires at least one if clause")]
+    EmptyIfThen,
+
+    /// A record of a multi-or-list does not match the number of values.
+    #[error("multi-or-list record {index} has {actual} fields, expected {expected}")]
+    MultiOrListRecord {
+        /// The (0-based) position of the record.
+        index: usize,
+        /// The number of values of the multi-or-list.
+        expected: usize,
+        /// The number of fields of the record.
+        actual: usize,
+    },
+
+    /// The cast failure behavior is unknown.
+    #[error("unknown cast failure behavior `{0}`")]
+    FailureBehavior(i32),
+
+    /// The set predicate operation is unspecified or unknown.
+    #[error("set predicate operation must be specified, got `{0}`")]
+    PredicateOp(i32),
+
+    /// The set comparison operation is unspecified or unknown.
+    #[error("set comparison operation must be specified, got `{0}`")]
+    ComparisonOp(i32),
+
+    /// The set comparison reduction is unspecified or unknown.
+    #[error("set comparison reduction must be specified, got `{0}`")]
+    ReductionOp(i32),
+
+    /// Type error
+    #[error(transparent)]
+    Type(#[from] TypeError),
+
+    /// Relation error, of a subquery
+    #[error(transparent)]
+    Rel(#[from] RelError),
+
+    /// Context error
+    #[error(transparent)]
+    Context(#[from] ContextError),
+}
+
+impl<'a> Parse<ExtensionAnchors<'a>> for proto::Expression {
+    type Parsed = Expression<'a>;
+    type Error = ExpressionError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'a>) -> Result<Self::Parsed, Self::Error> {
+        let proto::Expression { rex_type } = self;
+
+        // The expression type is a required oneof.
+        Ok(match rex_type.ok_or(ExpressionError::Missing)? {
+            RexType::Literal(literal) => Expression::Literal(parse_literal(literal, ctx)?),
+            RexType::Selection(reference) => {
+                Expression::FieldReference(Box::new(parse_field_reference(*reference, ctx)?))
+            }
+            RexType::ScalarFunction(function) => Expression::ScalarFunction(ctx.parse(function)?),
+            RexType::IfThen(if_then) => Expression::IfThen(Box::new(ctx.parse(*if_then)?)),
+            RexType::SwitchExpression(switch) => Expression::Switch(Box::new(ctx.parse(*switch)?)),
+            RexType::SingularOrList(list) => {
+                Expression::SingularOrList(Box::new(ctx.parse(*list)?))
+            }
+            RexType::MultiOrList(list) => Expression::MultiOrList(ctx.parse(list)?),
+            RexType::Cast(cast) => Expression::Cast(Box::new(ctx.parse(*cast)?)),
+            RexType::Subquery(subquery) => Expression::Subquery(Box::new(ctx.parse(*subquery)?)),
+            rex_type => Expression::Other(proto::Expression {
+                rex_type: Some(rex_type),
+            }),
+        })
+    }
+}
+
+impl From<Expression<'_>> for proto::Expression {
+    fn from(expression: Expression<'_>) -> Self {
+        let rex_type = match expression {
+            Expression::Literal(literal) => RexType::Literal(literal),
+            Expression::FieldReference(reference) => RexType::Selection(reference),
+            Expression::ScalarFunction(function) => RexType::ScalarFunction(function.into()),
+            Expression::IfThen(if_then) => RexType::IfThen(Box::new((*if_then).into())),
+            Expression::Switch(switch) => RexType::SwitchExpression(Box::new((*switch).into())),
+            Expression::SingularOrList(list) => RexType::SingularOrList(Box::new((*list).into())),
+            Expression::MultiOrList(list) => RexType::MultiOrList(list.into()),
+            Expression::Cast(cast) => RexType::Cast(Box::new((*cast).into())),
+            Expression::Subquery(subquery) => RexType::Subquery(Box::new((*subquery).into())),
+            Expression::Other(expression) => return expression,
+        };
+        proto::Expression {
+            rex_type: Some(rex_type),
+        }
+    }
+}
+
+/// Parse a required expression.
+fn required<'a>(
+    expression: Option<proto::Expression>,
+    name: &'static str,
+    field: &'static str,
+    ctx: &mut ExtensionAnchors<'a>,
+) -> Result<Expression<'a>, ExpressionError> {
+    let expression = expression.ok_or(ExpressionError::MissingField {
+        expression: name,
+        field,
+    })?;
+    ctx.parse(expression)
+}
+
+/// Validate a literal: the literal type is a required oneof, and the type
+/// variation must be declared.
+fn parse_literal(literal: Literal, ctx: &ExtensionAnchors<'_>) -> Result<Literal, ExpressionError> {
+    if literal.literal_type.is_none() {
+        return Err(ExpressionError::MissingField {
+            expression: "literal",
+            field: "literal type",
+        });
+    }
+    if literal.type_variation_reference != 0 {
+        ctx.get_extension_type_variation(&Anchor::new(literal.type_variation_reference))?;
+    }
+    Ok(literal)
+}
+
+/// Validate a field reference: the reference type and root type are required
+/// oneofs, and an expression root must be valid.
+fn parse_field_reference(
+    reference: FieldReference,
+    ctx: &mut ExtensionAnchors<'_>,
+) -> Result<FieldReference, ExpressionError> {
+    let FieldReference {
+        reference_type,
+        root_type,
+    } = reference;
+
+    let missing = |field| ExpressionError::MissingField {
+        expression: "field reference",
+        field,
+    };
+    let reference_type = reference_type.ok_or_else(|| missing("reference type"))?;
+    let root_type = match root_type.ok_or_else(|| missing("root type"))? {
+        field_reference::RootType::Expression(root) => {
+            field_reference::RootType::Expression(Box::new(ctx.parse(*root)?.into()))
+        }
+        root_type => root_type,
+    };
+
+    Ok(FieldReference {
+        reference_type: Some(reference_type),
+        root_type: Some(root_type),
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::parse::{
+        proto::rel::tests::field,
+        text::simple_extensions::{ExtensionFile, Registry},
+    };
+    use proto::{
+        extensions::{
+            SimpleExtensionDeclaration, SimpleExtensionUrn,
+            simple_extension_declaration::{ExtensionFunction, MappingType},
+        },
+        function_argument::ArgType,
+    };
+
+    const YAML: &str = r#"
+%YAML 1.2
+---
+urn: extension:substrait-rs:expression
+scalar_functions:
+  - name: add
+    impls:
+      - args:
+          - name: overflow
+            options: [SILENT, SATURATE, ERROR]
+          - value: i32
+          - value: i32
+        return: i32
+  - name: concat
+    impls:
+      - args:
+          - value: string
+        variadic:
+          min: 1
+        return: string
+aggregate_functions:
+  - name: sum
+    impls:
+      - args:
+          - value: i32
+        return: i32
+"#;
+
+    /// A registry defining `add` (with an enum and two `i32` arguments), the
+    /// variadic `concat` and the aggregate `sum`.
+    pub(crate) fn registry() -> Registry {
+        Registry::new([ExtensionFile::read_from_str(YAML).unwrap()]).unwrap()
+    }
+
+    /// Anchors declaring `add` (anchor 1) and `concat` (anchor 2), resolved
+    /// against the registry if given.
+    pub(crate) fn anchors(registry: Option<&Registry>) -> ExtensionAnchors<'_> {
+        let mut ctx = registry
+            .map(ExtensionAnchors::with_registry)
+            .unwrap_or_default();
+        ctx.parse(SimpleExtensionUrn {
+            extension_urn_anchor: 1,
+            urn: "extension:substrait-rs:expression".to_string(),
+        })
+        .expect("valid simple extension urn");
+        for (function_anchor, name) in [(1, "add"), (2, "concat")] {
+            ctx.parse(SimpleExtensionDeclaration {
+                mapping_type: Some(MappingType::ExtensionFunction(ExtensionFunction {
+                    extension_urn_reference: 1,
+                    function_anchor,
+                    name: name.to_string(),
+                    ..Default::default()
+                })),
+            })
+            .expect("valid declaration");
+        }
+        ctx
+    }
+
+    /// A required `i32` type.
+    pub(crate) fn i32_type() -> proto::Type {
+        proto::Type {
+            kind: Some(proto::r#type::Kind::I32(proto::r#type::I32 {
+                type_variation_reference: 0,
+                nullability: proto::r#type::Nullability::Required as i32,
+            })),
+        }
+    }
+
+    /// A non-nullable `i32` literal.
+    pub(crate) fn i32_literal(value: i32) -> proto::Expression {
+        proto::Expression {
+            rex_type: Some(RexType::Literal(Literal {
+                literal_type: Some(proto::expression::literal::LiteralType::I32(value)),
+                ..Default::default()
+            })),
+        }
+    }
+
+    /// A call of the function with the given anchor.
+    pub(crate) fn call(
+        anchor: u32,
+        arguments: Vec<ArgType>,
+        output_type: proto::Type,
+    ) -> proto::Expression {
+        proto::Expression {
+            rex_type: Some(RexType::ScalarFunction(proto::expression::ScalarFunction {
+                function_reference: anchor,
+                arguments: arguments
+                    .into_iter()
+                    .map(|arg_type| proto::FunctionArgument {
+                        arg_type: Some(arg_type),
+                    })
+                    .collect(),
+                output_type: Some(output_type),
+                ..Default::default()
+            })),
+        }
+    }
+
+    #[test]
+    fn parse() -> Result<(), ExpressionError> {
+        let mut ctx = anchors(None);
+
+        let if_then = proto::Expression {
+            rex_type: Some(RexType::IfThen(Box::new(proto::expression::IfThen {
+                ifs: vec![proto::expression::if_then::IfClause {
+                    r#if: Some(field(0)),
+                    then: Some(i32_literal(1)),
+                }],
+                r#else: Some(Box::new(call(
+                    1,
+                    vec![
+                        ArgType::Enum("SILENT".to_string()),
+                        ArgType::Value(field(1)),
+                        ArgType::Value(i32_literal(2)),
+                    ],
+                    i32_type(),
+                ))),
+            }))),
+        };
+        let Expression::IfThen(parsed) = ctx.parse(if_then.clone())? else {
+            panic!("expected an if-then expression");
+        };
+        assert!(matches!(parsed.ifs()[0].0, Expression::FieldReference(_)));
+        let Some(Expression::ScalarFunction(function)) = parsed.else_() else {
+            panic!("expected a scalar function");
+        };
+        assert_eq!(function.anchor(), Anchor::new(1));
+        assert!(function.implementation().is_none());
+        assert_eq!(proto::Expression::from(Expression::IfThen(parsed)), if_then);
+        Ok(())
+    }
+
+    #[test]
+    fn missing() {
+        let mut ctx = anchors(None);
+        assert_eq!(
+            ctx.parse(proto::Expression::default()),
+            Err(ExpressionError::Missing)
+        );
+        assert_eq!(
+            ctx.parse(proto::Expression {
+                rex_type: Some(RexType::Literal(Literal::default())),
+            }),
+            Err(ExpressionError::MissingField {
+                expression: "literal",
+                field: "literal type"
+            })
+        );
+        assert_eq!(
+            ctx.parse(proto::Expression {
+                rex_type: Some(RexType::Selection(Box::new(FieldReference {
+                    root_type: None,
+                    ..Default::default()
+                }))),
+            }),
+            Err(ExpressionError::MissingField {
+                expression: "field reference",
+                field: "reference type"
+            })
+        );
+        assert_eq!(
+            ctx.parse(proto::Expression {
+                rex_type: Some(RexType::IfThen(Box::default())),
+            }),
+            Err(ExpressionError::EmptyIfThen)
+        );
+    }
+}
@@ -0,0 +1,477 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Resolution of [FieldReference]s against input schemas.
+
+use std::fmt::Write;
+
+use thiserror::Error;
+
+use crate::{
+    parse::{
+        proto::{
+            ExtensionAnchors,
+            expression::{InferTypeError, infer::literal_type},
+        },
+        text::simple_extensions::{ConcreteType, ConcreteTypeKind},
+    },
+    proto::expression::{FieldReference, ReferenceSegment, field_reference, reference_segment},
+};
+
+/// Errors resolving a [FieldReference].
+///
+/// Paths start at the root of the reference (`$`), followed by the segments
+/// that were resolved: `.n` for struct fields, `[n]` for list elements and
+/// `[key]` for map keys.
+#[derive(Debug, Error, PartialEq)]
+pub enum FieldReferenceError {
+    /// A required field of the reference is missing.
+    #[error("field reference {0} must be specified")]
+    Missing(&'static str),
+
+    /// The reference is not supported.
+    #[error("{0} are not supported")]
+    Unsupported(&'static str),
+
+    /// An outer reference steps out of more queries than there are.
+    #[error("outer reference steps out {steps_out} queries, but there are {count} outer queries")]
+    OuterReference {
+        /// The number of queries the reference steps out of.
+        steps_out: u32,
+        /// The number of outer queries.
+        count: usize,
+    },
+
+    /// A struct field ordinal is out of range.
+    #[error("field `{field}` of `{path}` is out of bounds for {count} fields")]
+    OutOfRange {
+        /// The path of the struct.
+        path: String,
+        /// The field ordinal.
+        field: i32,
+        /// The number of fields of the struct.
+        count: usize,
+    },
+
+    /// A segment does not match the type it selects from.
+    #[error("cannot select a {segment} of `{path}` with type `{type_}`")]
+    Segment {
+        /// The path of the selected value.
+        path: String,
+        /// The kind of segment: struct field, list element or map key.
+        segment: &'static str,
+        /// The type of the selected value.
+        type_: Box<ConcreteType>,
+    },
+
+    /// A map key literal does not have the key type of the map.
+    #[error("map key of `{path}` has type `{actual}`, expected `{expected}`")]
+    MapKey {
+        /// The path of the map.
+        path: String,
+        /// The key type of the map.
+        expected: Box<ConcreteType>,
+        /// The type of the map key literal.
+        actual: Box<ConcreteType>,
+    },
+
+    /// The type of a map key literal can not be inferred.
+    #[error("invalid map key")]
+    MapKeyLiteral(#[source] Box<InferTypeError>),
+}
+
+/// Returns the type of the field selected by a field reference.
+///
+/// Root references select from the `input` schema, and outer references from
+/// the schemas of the `outer` queries, innermost first (an outer reference
+/// stepping out one query selects from `outer[0]`). References rooted in an
+/// expression can be resolved with [resolve_reference_segment] against the
+/// type of the expression. Masked references are not supported. Map key
+/// literals are typed with the given anchors.
+///
+/// Selected values are nullable if any value they are selected from is
+/// nullable. List elements and map values are always nullable, as the
+/// selected element or key may not exist.
+pub fn resolve_field_reference(
+    reference: &FieldReference,
+    input: &ConcreteType,
+    outer: &[ConcreteType],
+    anchors: &ExtensionAnchors<'_>,
+) -> Result<ConcreteType, FieldReferenceError> {
+    let root = match reference
+        .root_type
+        .as_ref()
+        .ok_or(FieldReferenceError::Missing("root type"))?
+    {
+        field_reference::RootType::RootReference(_) => input,
+        field_reference::RootType::OuterReference(outer_reference) => {
+            let steps_out = outer_reference.steps_out;
+            (steps_out as usize)
+                .checked_sub(1)
+                .and_then(|index| outer.get(index))
+                .ok_or(FieldReferenceError::OuterReference {
+                    steps_out,
+                    count: outer.len(),
+                })?
+        }
+        field_reference::RootType::Expression(_) => {
+            return Err(FieldReferenceError::Unsupported(
+                "references rooted in an expression",
+            ));
+        }
+    };
+    resolve_reference_type(reference, root, anchors)
+}
+
+/// Returns the type of the value selected by the direct reference of a field
+/// reference from the given root type.
+pub(crate) fn resolve_reference_type(
+    reference: &FieldReference,
+    root: &ConcreteType,
+    anchors: &ExtensionAnchors<'_>,
+) -> Result<ConcreteType, FieldReferenceError> {
+    match reference
+        .reference_type
+        .as_ref()
+        .ok_or(FieldReferenceError::Missing("reference type"))?
+    {
+        field_reference::ReferenceType::DirectReference(segment) => {
+            resolve_reference_segment(segment, root, anchors)
+        }
+        field_reference::ReferenceType::MaskedReference(_) => {
+            Err(FieldReferenceError::Unsupported("masked references"))
+        }
+    }
+}
+
+/// Returns the type of the value selected by a reference segment (and its
+/// children) from a value of the given type, see [resolve_field_reference].
+pub fn resolve_reference_segment(
+    segment: &ReferenceSegment,
+    root: &ConcreteType,
+    anchors: &ExtensionAnchors<'_>,
+) -> Result<ConcreteType, FieldReferenceError> {
+    use reference_segment::ReferenceType;
+
+    let mut path = String::from("$");
+    let mut current = root.clone();
+    let mut segment = Some(segment);
+    while let Some(reference) = segment {
+        let invalid = |segment, type_: ConcreteType, path: &str| FieldReferenceError::Segment {
+            path: path.to_string(),
+            segment,
+            type_: Box::new(type_),
+        };
+        let (mut selected, child) = match reference
+            .reference_type
+            .as_ref()
+            .ok_or(FieldReferenceError::Missing("segment"))?
+        {
+            ReferenceType::StructField(struct_field) => {
+                let field = struct_field.field;
+                let fields = match &current.kind {
+                    ConcreteTypeKind::Struct(fields) => fields.iter().collect::<Vec<_>>(),
+                    ConcreteTypeKind::NamedStruct { fields } => fields.values().collect(),
+                    _ => return Err(invalid("struct field", current, &path)),
+                };
+                let selected = usize::try_from(field)
+                    .ok()
+                    .and_then(|index| fields.get(index))
+                    .map(|&field| field.clone())
+                    .ok_or_else(|| FieldReferenceError::OutOfRange {
+                        path: path.clone(),
+                        field,
+                        count: fields.len(),
+                    })?;
+                let _ = write!(path, ".{field}");
+                (selected, &struct_field.child)
+            }
+            ReferenceType::ListElement(list_element) => {
+                let ConcreteTypeKind::List(element) = &current.kind else {
+                    return Err(invalid("list element", current, &path));
+                };
+                let _ = write!(path, "[{}]", list_element.offset);
+                let mut element = element.as_ref().clone();
+                element.nullable = true;
+                (element, &list_element.child)
+            }
+            ReferenceType::MapKey(map_key) => {
+                let ConcreteTypeKind::Map { key, value } = &current.kind else {
+                    return Err(invalid("map key", current, &path));
+                };
+                let literal = map_key
+                    .map_key
+                    .as_ref()
+                    .ok_or(FieldReferenceError::Missing("map key"))?;
+                let literal = literal_type(literal, anchors)
+                    .map_err(|err| FieldReferenceError::MapKeyLiteral(Box::new(err)))?;
+                if literal.kind != key.kind || literal.variation != key.variation {
+                    return Err(FieldReferenceError::MapKey {
+                        path,
+                        expected: key.clone(),
+                        actual: Box::new(literal),
+                    });
+                }
+                path.push_str("[key]");
+                let mut value = value.as_ref().clone();
+                value.nullable = true;
+                (value, &map_key.child)
+            }
+        };
+        selected.nullable |= current.nullable;
+        current = selected;
+        segment = child.as_deref();
+    }
+    Ok(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parse::text::simple_extensions::BasicBuiltinType,
+        proto::expression::{Literal, literal::LiteralType},
+    };
+
+    fn i32(nullable: bool) -> ConcreteType {
+        ConcreteType::builtin(BasicBuiltinType::I32, nullable)
+    }
+
+    /// A struct field segment, with an optional child.
+    fn struct_field(field: i32, child: Option<ReferenceSegment>) -> ReferenceSegment {
+        ReferenceSegment {
+            reference_type: Some(reference_segment::ReferenceType::StructField(Box::new(
+                reference_segment::StructField {
+                    field,
+                    child: child.map(Box::new),
+                },
+            ))),
+        }
+    }
+
+    fn reference(
+        segment: ReferenceSegment,
+        root_type: field_reference::RootType,
+    ) -> FieldReference {
+        FieldReference {
+            reference_type: Some(field_reference::ReferenceType::DirectReference(segment)),
+            root_type: Some(root_type),
+        }
+    }
+
+    /// `struct<i32, struct?<list<i32>, map<string, i32>>>`
+    fn schema() -> ConcreteType {
+        let list = ConcreteType {
+            kind: ConcreteTypeKind::List(Box::new(i32(false))),
+            nullable: false,
+            variation: None,
+        };
+        let map = ConcreteType {
+            kind: ConcreteTypeKind::Map {
+                key: Box::new(ConcreteType::builtin(BasicBuiltinType::String, false)),
+                value: Box::new(i32(false)),
+            },
+            nullable: false,
+            variation: None,
+        };
+        ConcreteType::r#struct(
+            vec![i32(false), ConcreteType::r#struct(vec![list, map], true)],
+            false,
+        )
+    }
+
+    #[test]
+    fn nested() -> Result<(), FieldReferenceError> {
+        let root = || field_reference::RootType::RootReference(field_reference::RootReference {});
+        assert_eq!(
+            resolve_field_reference(
+                &reference(struct_field(0, None), root()),
+                &schema(),
+                &[],
+                &ExtensionAnchors::default()
+            )?,
+            i32(false)
+        );
+
+        // Fields of the nullable struct are nullable
+        let list_element = ReferenceSegment {
+            reference_type: Some(reference_segment::ReferenceType::ListElement(Box::new(
+                reference_segment::ListElement {
+                    offset: 3,
+                    child: None,
+                },
+            ))),
+        };
+        let segment = struct_field(1, Some(struct_field(0, Some(list_element))));
+        assert_eq!(
+            resolve_field_reference(
+                &reference(segment, root()),
+                &schema(),
+                &[],
+                &ExtensionAnchors::default()
+            )?,
+            i32(true)
+        );
+
+        let map_key = ReferenceSegment {
+            reference_type: Some(reference_segment::ReferenceType::MapKey(Box::new(
+                reference_segment::MapKey {
+                    map_key: Some(Literal {
+                        literal_type: Some(LiteralType::String("a".to_string())),
+                        ..Default::default()
+                    }),
+                    child: None,
+                },
+            ))),
+        };
+        let segment = struct_field(1, Some(struct_field(1, Some(map_key))));
+        assert_eq!(
+            resolve_field_reference(
+                &reference(segment, root()),
+                &schema(),
+                &[],
+                &ExtensionAnchors::default()
+            )?,
+            i32(true)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn list_elements_and_map_keys() {
+        let root = || field_reference::RootType::RootReference(field_reference::RootReference {});
+        let anchors = ExtensionAnchors::default();
+        let map_key = |literal_type| ReferenceSegment {
+            reference_type: Some(reference_segment::ReferenceType::MapKey(Box::new(
+                reference_segment::MapKey {
+                    map_key: Some(Literal {
+                        literal_type: Some(literal_type),
+                        ..Default::default()
+                    }),
+                    child: None,
+                },
+            ))),
+        };
+        let list = ConcreteType {
+            kind: ConcreteTypeKind::List(Box::new(i32(false))),
+            nullable: false,
+            variation: None,
+        };
+        let map = ConcreteType {
+            kind: ConcreteTypeKind::Map {
+                key: Box::new(ConcreteType::builtin(BasicBuiltinType::String, false)),
+                value: Box::new(i32(false)),
+            },
+            nullable: false,
+            variation: None,
+        };
+        let schema = ConcreteType::r#struct(vec![list, map], false);
+
+        // The selected element or key may not exist
+        let list_element = ReferenceSegment {
+            reference_type: Some(reference_segment::ReferenceType::ListElement(Box::new(
+                reference_segment::ListElement {
+                    offset: 0,
+                    child: None,
+                },
+            ))),
+        };
+        assert_eq!(
+            resolve_field_reference(
+                &reference(struct_field(0, Some(list_element)), root()),
+                &schema,
+                &[],
+                &anchors
+            ),
+            Ok(i32(true))
+        );
+        let segment = struct_field(1, Some(map_key(LiteralType::String("a".to_string()))));
+        assert_eq!(
+            resolve_field_reference(&reference(segment, root()), &schema, &[], &anchors),
+            Ok(i32(true))
+        );
+
+        // Map keys must have the key type of the map
+        let segment = struct_field(1, Some(map_key(LiteralType::I32(1))));
+        assert_eq!(
+            resolve_field_reference(&reference(segment, root()), &schema, &[], &anchors),
+            Err(FieldReferenceError::MapKey {
+                path: "$.1".to_string(),
+                expected: Box::new(ConcreteType::builtin(BasicBuiltinType::String, false)),
+                actual: Box::new(i32(false))
+            })
+        );
+    }
+
+    #[test]
+    fn out_of_range() {
+        let root = || field_reference::RootType::RootReference(field_reference::RootReference {});
+        let segment = struct_field(1, Some(struct_field(2, None)));
+        assert_eq!(
+            resolve_field_reference(
+                &reference(segment, root()),
+                &schema(),
+                &[],
+                &ExtensionAnchors::default()
+            ),
+            Err(FieldReferenceError::OutOfRange {
+                path: "$.1".to_string(),
+                field: 2,
+                count: 2
+            })
+        );
+
+        let segment = struct_field(0, Some(struct_field(0, None)));
+        assert_eq!(
+            resolve_field_reference(
+                &reference(segment, root()),
+                &schema(),
+                &[],
+                &ExtensionAnchors::default()
+            ),
+            Err(FieldReferenceError::Segment {
+                path: "$.0".to_string(),
+                segment: "struct field",
+                type_: Box::new(i32(false))
+            })
+        );
+    }
+
+    #[test]
+    fn outer_reference() -> Result<(), FieldReferenceError> {
+        let outer = |steps_out| {
+            field_reference::RootType::OuterReference(field_reference::OuterReference { steps_out })
+        };
+        let outer_schemas = [ConcreteType::r#struct(vec![i32(true)], false), schema()];
+        assert_eq!(
+            resolve_field_reference(
+                &reference(struct_field(0, None), outer(1)),
+                &schema(),
+                &outer_schemas,
+                &ExtensionAnchors::default()
+            )?,
+            i32(true)
+        );
+        assert!(
+            resolve_field_reference(
+                &reference(struct_field(1, None), outer(2)),
+                &schema(),
+                &outer_schemas,
+                &ExtensionAnchors::default()
+            )?
+            .nullable
+        );
+        assert_eq!(
+            resolve_field_reference(
+                &reference(struct_field(0, None), outer(3)),
+                &schema(),
+                &outer_schemas,
+                &ExtensionAnchors::default()
+            ),
+            Err(FieldReferenceError::OuterReference {
+                steps_out: 3,
+                count: 2
+            })
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,228 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing of [proto::expression::Subquery].
+
+use crate::{
+    parse::{
+        Context, Parse,
+        proto::{
+            ExtensionAnchors,
+            expression::{Expression, ExpressionError, required},
+            rel::Rel,
+        },
+    },
+    proto::{
+        self,
+        expression::subquery::{
+            self as pb, SubqueryType,
+            set_comparison::{ComparisonOp, ReductionOp},
+            set_predicate::PredicateOp,
+        },
+    },
+};
+
+/// A parsed [proto::expression::Subquery].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Subquery<'a> {
+    /// A subquery returning a single value.
+    ///
+    /// See [SubqueryType::Scalar].
+    Scalar {
+        /// The relation computing the value.
+        input: Rel,
+    },
+
+    /// Whether the needles are contained in the haystack.
+    ///
+    /// See [SubqueryType::InPredicate].
+    InPredicate {
+        /// The values to look up.
+        needles: Vec<Expression<'a>>,
+        /// The relation to look up the values in.
+        haystack: Rel,
+    },
+
+    /// A predicate on the records of a relation.
+    ///
+    /// See [SubqueryType::SetPredicate].
+    SetPredicate {
+        /// The predicate, which is not [PredicateOp::Unspecified].
+        op: PredicateOp,
+        /// The relation of the records.
+        tuples: Rel,
+    },
+
+    /// A comparison of a value with the values of a relation.
+    ///
+    /// See [SubqueryType::SetComparison].
+    SetComparison {
+        /// How the comparisons are reduced, which is not
+        /// [ReductionOp::Unspecified].
+        reduction_op: ReductionOp,
+        /// The comparison, which is not [ComparisonOp::Unspecified].
+        comparison_op: ComparisonOp,
+        /// The value to compare.
+        left: Expression<'a>,
+        /// The relation of the values to compare with.
+        right: Rel,
+    },
+}
+
+impl<'a> Parse<ExtensionAnchors<'a>> for proto::expression::Subquery {
+    type Parsed = Subquery<'a>;
+    type Error = ExpressionError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'a>) -> Result<Self::Parsed, Self::Error> {
+        let proto::expression::Subquery { subquery_type } = self;
+
+        // Relations of subqueries are required.
+        let mut rel = |rel: Option<Box<proto::Rel>>, field| {
+            let rel = rel.ok_or(ExpressionError::MissingField {
+                expression: "subquery",
+                field,
+            })?;
+            Ok::<_, ExpressionError>(ctx.parse(*rel)?)
+        };
+
+        // The subquery type is a required oneof.
+        Ok(
+            match subquery_type.ok_or(ExpressionError::MissingField {
+                expression: "subquery",
+                field: "subquery type",
+            })? {
+                SubqueryType::Scalar(scalar) => Subquery::Scalar {
+                    input: rel(scalar.input, "input")?,
+                },
+                SubqueryType::InPredicate(in_predicate) => {
+                    let pb::InPredicate { needles, haystack } = *in_predicate;
+                    let haystack = rel(haystack, "haystack")?;
+                    Subquery::InPredicate {
+                        needles: needles
+                            .into_iter()
+                            .map(|needle| ctx.parse(needle))
+                            .collect::<Result<_, _>>()?,
+                        haystack,
+                    }
+                }
+                SubqueryType::SetPredicate(set_predicate) => {
+                    let pb::SetPredicate {
+                        predicate_op,
+                        tuples,
+                    } = *set_predicate;
+                    let op = match PredicateOp::try_from(predicate_op) {
+                        Ok(PredicateOp::Unspecified) | Err(_) => {
+                            return Err(ExpressionError::PredicateOp(predicate_op));
+                        }
+                        Ok(op) => op,
+                    };
+                    Subquery::SetPredicate {
+                        op,
+                        tuples: rel(tuples, "tuples")?,
+                    }
+                }
+                SubqueryType::SetComparison(set_comparison) => {
+                    let pb::SetComparison {
+                        reduction_op,
+                        comparison_op,
+                        left,
+                        right,
+                    } = *set_comparison;
+                    let right = rel(right, "right")?;
+                    Subquery::SetComparison {
+                        reduction_op: match ReductionOp::try_from(reduction_op) {
+                            Ok(ReductionOp::Unspecified) | Err(_) => {
+                                return Err(ExpressionError::ReductionOp(reduction_op));
+                            }
+                            Ok(op) => op,
+                        },
+                        comparison_op: match ComparisonOp::try_from(comparison_op) {
+                            Ok(ComparisonOp::Unspecified) | Err(_) => {
+                                return Err(ExpressionError::ComparisonOp(comparison_op));
+                            }
+                            Ok(op) => op,
+                        },
+                        left: required(left.map(|left| *left), "subquery", "left", ctx)?,
+                        right,
+                    }
+                }
+            },
+        )
+    }
+}
+
+impl From<Subquery<'_>> for proto::expression::Subquery {
+    fn from(subquery: Subquery<'_>) -> Self {
+        let rel = |rel: Rel| Some(Box::new(rel.into()));
+
+        let subquery_type = match subquery {
+            Subquery::Scalar { input } => {
+                SubqueryType::Scalar(Box::new(pb::Scalar { input: rel(input) }))
+            }
+            Subquery::InPredicate { needles, haystack } => {
+                SubqueryType::InPredicate(Box::new(pb::InPredicate {
+                    needles: needles.into_iter().map(Into::into).collect(),
+                    haystack: rel(haystack),
+                }))
+            }
+            Subquery::SetPredicate { op, tuples } => {
+                SubqueryType::SetPredicate(Box::new(pb::SetPredicate {
+                    predicate_op: op.into(),
+                    tuples: rel(tuples),
+                }))
+            }
+            Subquery::SetComparison {
+                reduction_op,
+                comparison_op,
+                left,
+                right,
+            } => SubqueryType::SetComparison(Box::new(pb::SetComparison {
+                reduction_op: reduction_op.into(),
+                comparison_op: comparison_op.into(),
+                left: Some(Box::new(left.into())),
+                right: rel(right),
+            })),
+        };
+        proto::expression::Subquery {
+            subquery_type: Some(subquery_type),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::proto::{expression::tests::i32_literal, rel::tests::read_rel};
+
+    #[test]
+    fn set_comparison() {
+        let mut ctx = ExtensionAnchors::default();
+        let subquery = proto::expression::Subquery {
+            subquery_type: Some(SubqueryType::SetComparison(Box::new(pb::SetComparison {
+                reduction_op: ReductionOp::Any.into(),
+                comparison_op: ComparisonOp::Lt.into(),
+                left: Some(Box::new(i32_literal(1))),
+                right: Some(Box::new(read_rel())),
+            }))),
+        };
+        let parsed = ctx.parse(subquery.clone()).unwrap();
+        assert!(matches!(
+            parsed,
+            Subquery::SetComparison {
+                comparison_op: ComparisonOp::Lt,
+                right: Rel::Read(_),
+                ..
+            }
+        ));
+        assert_eq!(proto::expression::Subquery::from(parsed), subquery);
+
+        assert_eq!(
+            ctx.parse(proto::expression::Subquery {
+                subquery_type: Some(SubqueryType::SetPredicate(Box::new(pb::SetPredicate {
+                    predicate_op: PredicateOp::Unspecified.into(),
+                    tuples: Some(Box::new(read_rel())),
+                }))),
+            }),
+            Err(ExpressionError::PredicateOp(0))
+        );
+    }
+}
@@ -3,4 +3,10 @@
 //! Parsing of [crate::proto::extensions] types.
 
 mod simple_extension_urn;
-pub use simple_extension_urn::SimpleExtensionUrn;
+pub use simple_extension_urn::{SimpleExtensionUrn, SimpleExtensionUrnError};
+
+mod simple_extension_declaration;
+pub use simple_extension_declaration::{
+    ExtensionFunction, ExtensionType, ExtensionTypeVariation, SimpleExtensionDeclaration,
+    SimpleExtensionDeclarationError,
+};
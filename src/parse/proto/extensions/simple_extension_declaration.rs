@@ -0,0 +1,701 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing of [proto::extensions::SimpleExtensionDeclaration].
+
+use thiserror::Error;
+
+use crate::{
+    parse::{
+        Anchor, Parse, context::ContextError, proto::ExtensionAnchors,
+        proto::extensions::SimpleExtensionUrn,
+    },
+    proto,
+    urn::Urn,
+};
+
+/// A parsed [proto::extensions::SimpleExtensionDeclaration].
+#[derive(Clone, Debug, PartialEq)]
+pub enum SimpleExtensionDeclaration {
+    /// A function declaration.
+    Function(ExtensionFunction),
+
+    /// A type declaration.
+    Type(ExtensionType),
+
+    /// A type variation declaration.
+    TypeVariation(ExtensionTypeVariation),
+}
+
+/// A parsed [proto::extensions::simple_extension_declaration::ExtensionFunction].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExtensionFunction {
+    /// The anchor of the simple extension that defines this function.
+    extension_urn: Anchor<SimpleExtensionUrn>,
+
+    /// The urn of the simple extension that defines this function.
+    urn: Urn,
+
+    /// The anchor value of this function.
+    anchor: Anchor<Self>,
+
+    /// The (compound) name of this function.
+    name: String,
+}
+
+impl ExtensionFunction {
+    /// Returns the anchor of the simple extension that defines this function.
+    ///
+    /// See [proto::extensions::simple_extension_declaration::ExtensionFunction::extension_urn_reference].
+    pub fn extension_urn(&self) -> Anchor<SimpleExtensionUrn> {
+        self.extension_urn
+    }
+
+    /// Returns the urn of the simple extension that defines this function.
+    pub fn urn(&self) -> &Urn {
+        &self.urn
+    }
+
+    /// Returns the anchor value of this function.
+    ///
+    /// See [proto::extensions::simple_extension_declaration::ExtensionFunction::function_anchor].
+    pub fn anchor(&self) -> Anchor<Self> {
+        self.anchor
+    }
+
+    /// Returns the name of this function.
+    ///
+    /// See [proto::extensions::simple_extension_declaration::ExtensionFunction::name].
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// A parsed [proto::extensions::simple_extension_declaration::ExtensionType].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExtensionType {
+    /// The anchor of the simple extension that defines this type.
+    extension_urn: Anchor<SimpleExtensionUrn>,
+
+    /// The urn of the simple extension that defines this type.
+    urn: Urn,
+
+    /// The anchor value of this type.
+    anchor: Anchor<Self>,
+
+    /// The name of this type.
+    name: String,
+}
+
+impl ExtensionType {
+    /// Returns the anchor of the simple extension that defines this type.
+    ///
+    /// See [proto::extensions::simple_extension_declaration::ExtensionType::extension_urn_reference].
+    pub fn extension_urn(&self) -> Anchor<SimpleExtensionUrn> {
+        self.extension_urn
+    }
+
+    /// Returns the urn of the simple extension that defines this type.
+    pub fn urn(&self) -> &Urn {
+        &self.urn
+    }
+
+    /// Returns the anchor value of this type.
+    ///
+    /// See [proto::extensions::simple_extension_declaration::ExtensionType::type_anchor].
+    pub fn anchor(&self) -> Anchor<Self> {
+        self.anchor
+    }
+
+    /// Returns the name of this type.
+    ///
+    /// See [proto::extensions::simple_extension_declaration::ExtensionType::name].
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// A parsed [proto::extensions::simple_extension_declaration::ExtensionTypeVariation].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExtensionTypeVariation {
+    /// The anchor of the simple extension that defines this type variation.
+    extension_urn: Anchor<SimpleExtensionUrn>,
+
+    /// The urn of the simple extension that defines this type variation.
+    urn: Urn,
+
+    /// The anchor value of this type variation.
+    anchor: Anchor<Self>,
+
+    /// The name of this type variation.
+    name: String,
+}
+
+impl ExtensionTypeVariation {
+    /// Returns the anchor of the simple extension that defines this type
+    /// variation.
+    ///
+    /// See [proto::extensions::simple_extension_declaration::ExtensionTypeVariation::extension_urn_reference].
+    pub fn extension_urn(&self) -> Anchor<SimpleExtensionUrn> {
+        self.extension_urn
+    }
+
+    /// Returns the urn of the simple extension that defines this type
+    /// variation.
+    pub fn urn(&self) -> &Urn {
+        &self.urn
+    }
+
+    /// Returns the anchor value of this type variation.
+    ///
+    /// See [proto::extensions::simple_extension_declaration::ExtensionTypeVariation::type_variation_anchor].
+    pub fn anchor(&self) -> Anchor<Self> {
+        self.anchor
+    }
+
+    /// Returns the name of this type variation.
+    ///
+    /// See [proto::extensions::simple_extension_declaration::ExtensionTypeVariation::name].
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Parse errors for [proto::extensions::SimpleExtensionDeclaration].
+#[derive(Debug, Error, PartialEq)]
+pub enum SimpleExtensionDeclarationError {
+    /// The mapping type is missing.
+    #[error("simple extension declaration mapping type must be specified")]
+    Missing,
+
+    /// The name is missing.
+    #[error("simple extension declaration name must be specified")]
+    MissingName,
+
+    /// The declaration only references its simple extension with the
+    /// deprecated extension URI reference.
+    #[error(
+        "simple extension declaration only sets the deprecated extension URI reference {0}, which is not supported"
+    )]
+    DeprecatedUriReference(u32),
+
+    /// Context error
+    #[error(transparent)]
+    Context(#[from] ContextError),
+}
+
+/// Resolves the simple extension urn referenced by a declaration.
+///
+/// Simple extensions are only registered by URN, so declarations that only
+/// set the deprecated extension URI reference can not be resolved.
+fn resolve_urn(
+    ctx: &ExtensionAnchors<'_>,
+    extension_urn_reference: u32,
+    extension_uri_reference: u32,
+) -> Result<(Anchor<SimpleExtensionUrn>, Urn), SimpleExtensionDeclarationError> {
+    let anchor = Anchor::new(extension_urn_reference);
+    match ctx.get_simple_extension_urn(&anchor) {
+        Some(urn) => Ok((anchor, urn.clone())),
+        None if extension_urn_reference == 0 && extension_uri_reference != 0 => Err(
+            SimpleExtensionDeclarationError::DeprecatedUriReference(extension_uri_reference),
+        ),
+        None => Err(ContextError::UndefinedSimpleExtension(anchor).into()),
+    }
+}
+
+impl Parse<ExtensionAnchors<'_>> for proto::extensions::SimpleExtensionDeclaration {
+    type Parsed = SimpleExtensionDeclaration;
+    type Error = SimpleExtensionDeclarationError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'_>) -> Result<Self::Parsed, Self::Error> {
+        use proto::extensions::simple_extension_declaration::MappingType;
+
+        let proto::extensions::SimpleExtensionDeclaration { mapping_type } = self;
+
+        // The mapping type is a required oneof.
+        match mapping_type.ok_or(SimpleExtensionDeclarationError::Missing)? {
+            MappingType::ExtensionFunction(function) => {
+                #[allow(deprecated)]
+                let proto::extensions::simple_extension_declaration::ExtensionFunction {
+                    extension_urn_reference,
+                    extension_uri_reference,
+                    function_anchor,
+                    name,
+                    ..
+                } = function;
+
+                if name.is_empty() {
+                    return Err(SimpleExtensionDeclarationError::MissingName);
+                }
+
+                // The referenced simple extension must be declared.
+                let (extension_urn, urn) =
+                    resolve_urn(ctx, extension_urn_reference, extension_uri_reference)?;
+
+                let function = ExtensionFunction {
+                    extension_urn,
+                    urn,
+                    anchor: Anchor::new(function_anchor),
+                    name,
+                };
+
+                // The anchor must be unique.
+                ctx.add_extension_function(&function)?;
+
+                Ok(SimpleExtensionDeclaration::Function(function))
+            }
+            MappingType::ExtensionType(r#type) => {
+                #[allow(deprecated)]
+                let proto::extensions::simple_extension_declaration::ExtensionType {
+                    extension_urn_reference,
+                    extension_uri_reference,
+                    type_anchor,
+                    name,
+                    ..
+                } = r#type;
+
+                if name.is_empty() {
+                    return Err(SimpleExtensionDeclarationError::MissingName);
+                }
+
+                // The referenced simple extension must be declared.
+                let (extension_urn, urn) =
+                    resolve_urn(ctx, extension_urn_reference, extension_uri_reference)?;
+
+                let r#type = ExtensionType {
+                    extension_urn,
+                    urn,
+                    anchor: Anchor::new(type_anchor),
+                    name,
+                };
+
+                // The anchor must be unique.
+                ctx.add_extension_type(&r#type)?;
+
+                Ok(SimpleExtensionDeclaration::Type(r#type))
+            }
+            MappingType::ExtensionTypeVariation(type_variation) => {
+                #[allow(deprecated)]
+                let proto::extensions::simple_extension_declaration::ExtensionTypeVariation {
+                    extension_urn_reference,
+                    extension_uri_reference,
+                    type_variation_anchor,
+                    name,
+                    ..
+                } = type_variation;
+
+                if name.is_empty() {
+                    return Err(SimpleExtensionDeclarationError::MissingName);
+                }
+
+                // The referenced simple extension must be declared.
+                let (extension_urn, urn) =
+                    resolve_urn(ctx, extension_urn_reference, extension_uri_reference)?;
+
+                let type_variation = ExtensionTypeVariation {
+                    extension_urn,
+                    urn,
+                    anchor: Anchor::new(type_variation_anchor),
+                    name,
+                };
+
+                // The anchor must be unique.
+                ctx.add_extension_type_variation(&type_variation)?;
+
+                Ok(SimpleExtensionDeclaration::TypeVariation(type_variation))
+            }
+        }
+    }
+}
+
+impl From<SimpleExtensionDeclaration> for proto::extensions::SimpleExtensionDeclaration {
+    fn from(simple_extension_declaration: SimpleExtensionDeclaration) -> Self {
+        use proto::extensions::simple_extension_declaration::{self as decl, MappingType};
+
+        let mapping_type = match simple_extension_declaration {
+            SimpleExtensionDeclaration::Function(ExtensionFunction {
+                extension_urn,
+                anchor,
+                name,
+                ..
+            }) => MappingType::ExtensionFunction(decl::ExtensionFunction {
+                extension_urn_reference: extension_urn.into_inner(),
+                function_anchor: anchor.into_inner(),
+                name,
+                ..Default::default()
+            }),
+            SimpleExtensionDeclaration::Type(ExtensionType {
+                extension_urn,
+                anchor,
+                name,
+                ..
+            }) => MappingType::ExtensionType(decl::ExtensionType {
+                extension_urn_reference: extension_urn.into_inner(),
+                type_anchor: anchor.into_inner(),
+                name,
+                ..Default::default()
+            }),
+            SimpleExtensionDeclaration::TypeVariation(ExtensionTypeVariation {
+                extension_urn,
+                anchor,
+                name,
+                ..
+            }) => MappingType::ExtensionTypeVariation(decl::ExtensionTypeVariation {
+                extension_urn_reference: extension_urn.into_inner(),
+                type_variation_anchor: anchor.into_inner(),
+                name,
+                ..Default::default()
+            }),
+        };
+
+        proto::extensions::SimpleExtensionDeclaration {
+            mapping_type: Some(mapping_type),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{
+        Context as _,
+        proto::ResolvedFunction,
+        text::simple_extensions::{ExtensionFile, Registry},
+    };
+    use proto::extensions::simple_extension_declaration::{self as decl, MappingType};
+
+    const YAML_ADD: &str = r#"
+%YAML 1.2
+---
+urn: extension:substrait-rs:test
+scalar_functions:
+  - name: add
+    impls:
+      - args:
+          - value: i32
+          - value: i32
+        return: i32
+      - args:
+          - value: i64
+          - value: i64
+        return: i64
+  - name: negate
+    impls:
+      - args:
+          - value: fp64
+        return: fp64
+aggregate_functions:
+  - name: sum
+    impls:
+      - args:
+          - value: i32
+        return: i64?
+      - args:
+          - value: i64
+        return: i64?
+window_functions:
+  - name: rank
+    impls:
+      - return: i64
+"#;
+
+    fn ctx() -> ExtensionAnchors<'static> {
+        let mut ctx = ExtensionAnchors::default();
+        ctx.parse(proto::extensions::SimpleExtensionUrn {
+            extension_urn_anchor: 1,
+            urn: "extension:substrait-rs:test".to_string(),
+        })
+        .expect("valid simple extension urn");
+        ctx
+    }
+
+    fn function(
+        function_anchor: u32,
+        extension_urn_reference: u32,
+    ) -> Result<SimpleExtensionDeclaration, SimpleExtensionDeclarationError> {
+        ctx().parse(proto::extensions::SimpleExtensionDeclaration {
+            mapping_type: Some(MappingType::ExtensionFunction(decl::ExtensionFunction {
+                extension_urn_reference,
+                function_anchor,
+                name: "add:i32_i32".to_string(),
+                ..Default::default()
+            })),
+        })
+    }
+
+    #[test]
+    fn parse() -> Result<(), SimpleExtensionDeclarationError> {
+        let mut ctx = ctx();
+
+        let SimpleExtensionDeclaration::Function(function) = function(7, 1)? else {
+            panic!("expected a function declaration");
+        };
+        assert_eq!(function.anchor(), Anchor::new(7));
+        assert_eq!(function.extension_urn(), Anchor::new(1));
+        assert_eq!(function.name(), "add:i32_i32");
+
+        ctx.parse(proto::extensions::SimpleExtensionDeclaration {
+            mapping_type: Some(MappingType::ExtensionType(decl::ExtensionType {
+                extension_urn_reference: 1,
+                type_anchor: 1,
+                name: "point".to_string(),
+                ..Default::default()
+            })),
+        })?;
+        assert_eq!(ctx.get_extension_type(&Anchor::new(1))?.name(), "point");
+
+        ctx.parse(proto::extensions::SimpleExtensionDeclaration {
+            mapping_type: Some(MappingType::ExtensionTypeVariation(
+                decl::ExtensionTypeVariation {
+                    extension_urn_reference: 1,
+                    type_variation_anchor: 1,
+                    name: "dict_encoded".to_string(),
+                    ..Default::default()
+                },
+            )),
+        })?;
+        assert_eq!(
+            ctx.get_extension_type_variation(&Anchor::new(1))?.name(),
+            "dict_encoded"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn missing() {
+        assert_eq!(
+            ctx().parse(proto::extensions::SimpleExtensionDeclaration::default()),
+            Err(SimpleExtensionDeclarationError::Missing)
+        );
+        assert_eq!(
+            ctx().parse(proto::extensions::SimpleExtensionDeclaration {
+                mapping_type: Some(MappingType::ExtensionType(decl::ExtensionType {
+                    extension_urn_reference: 1,
+                    ..Default::default()
+                })),
+            }),
+            Err(SimpleExtensionDeclarationError::MissingName)
+        );
+    }
+
+    #[test]
+    fn undefined_simple_extension() {
+        assert_eq!(
+            function(7, 2),
+            Err(SimpleExtensionDeclarationError::Context(
+                ContextError::UndefinedSimpleExtension(Anchor::new(2))
+            ))
+        );
+    }
+
+    #[test]
+    fn deprecated_uri_reference() {
+        #[allow(deprecated)]
+        let declaration = proto::extensions::SimpleExtensionDeclaration {
+            mapping_type: Some(MappingType::ExtensionFunction(decl::ExtensionFunction {
+                extension_uri_reference: 1,
+                function_anchor: 7,
+                name: "add:i32_i32".to_string(),
+                ..Default::default()
+            })),
+        };
+        assert_eq!(
+            ctx().parse(declaration),
+            Err(SimpleExtensionDeclarationError::DeprecatedUriReference(1))
+        );
+    }
+
+    #[test]
+    fn duplicate_anchor() {
+        let mut ctx = ctx();
+        let declaration = proto::extensions::SimpleExtensionDeclaration {
+            mapping_type: Some(MappingType::ExtensionFunction(decl::ExtensionFunction {
+                extension_urn_reference: 1,
+                function_anchor: 7,
+                name: "add:i32_i32".to_string(),
+                ..Default::default()
+            })),
+        };
+        assert!(ctx.parse(declaration.clone()).is_ok());
+        assert_eq!(
+            ctx.parse(declaration),
+            Err(SimpleExtensionDeclarationError::Context(
+                ContextError::DuplicateExtensionFunction(Anchor::new(7))
+            ))
+        );
+    }
+
+    #[test]
+    fn undefined_anchor() {
+        let ctx = ctx();
+        assert_eq!(
+            ctx.get_extension_function(&Anchor::new(7)),
+            Err(ContextError::UndefinedExtensionFunction(Anchor::new(7)))
+        );
+    }
+
+    #[test]
+    fn convert() -> Result<(), SimpleExtensionDeclarationError> {
+        let declaration = proto::extensions::SimpleExtensionDeclaration {
+            mapping_type: Some(MappingType::ExtensionTypeVariation(
+                decl::ExtensionTypeVariation {
+                    extension_urn_reference: 1,
+                    type_variation_anchor: 3,
+                    name: "dict_encoded".to_string(),
+                    ..Default::default()
+                },
+            )),
+        };
+        assert_eq!(
+            proto::extensions::SimpleExtensionDeclaration::from(ctx().parse(declaration.clone())?),
+            declaration
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn resolve_function() -> Result<(), SimpleExtensionDeclarationError> {
+        let registry = Registry::new([ExtensionFile::read_from_str(YAML_ADD).unwrap()]).unwrap();
+        let declaration =
+            |function_anchor, name: &str| proto::extensions::SimpleExtensionDeclaration {
+                mapping_type: Some(MappingType::ExtensionFunction(decl::ExtensionFunction {
+                    extension_urn_reference: 1,
+                    function_anchor,
+                    name: name.to_string(),
+                    ..Default::default()
+                })),
+            };
+        let mut ctx = ExtensionAnchors::with_registry(&registry);
+        ctx.parse(proto::extensions::SimpleExtensionUrn {
+            extension_urn_anchor: 1,
+            urn: "extension:substrait-rs:test".to_string(),
+        })
+        .unwrap();
+
+        ctx.parse(declaration(1, "add:i64_i64"))?;
+        let resolved = ctx.resolve_extension_function(&Anchor::new(1))?;
+        assert!(matches!(resolved, ResolvedFunction::Scalar(..)));
+        assert_eq!(resolved.name(), "add");
+        assert_eq!(resolved.implementation().return_type.to_string(), "i64");
+
+        // A plain name resolves when the function has a single implementation.
+        ctx.parse(declaration(2, "negate"))?;
+        assert!(ctx.resolve_extension_function(&Anchor::new(2)).is_ok());
+
+        let urn = ctx
+            .get_simple_extension_urn(&Anchor::new(1))
+            .unwrap()
+            .clone();
+        assert_eq!(
+            ctx.parse(declaration(3, "add")),
+            Err(SimpleExtensionDeclarationError::Context(
+                ContextError::UnknownFunctionSignature {
+                    urn: urn.clone(),
+                    name: "add".to_string()
+                }
+            ))
+        );
+        assert_eq!(
+            ctx.parse(declaration(3, "add:fp32_fp32")),
+            Err(SimpleExtensionDeclarationError::Context(
+                ContextError::UnknownFunctionSignature {
+                    urn: urn.clone(),
+                    name: "add:fp32_fp32".to_string()
+                }
+            ))
+        );
+        assert_eq!(
+            ctx.parse(declaration(3, "subtract:i32_i32")),
+            Err(SimpleExtensionDeclarationError::Context(
+                ContextError::UnknownFunction {
+                    urn,
+                    name: "subtract:i32_i32".to_string()
+                }
+            ))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn resolve_aggregate_and_window_functions() -> Result<(), SimpleExtensionDeclarationError> {
+        let registry = Registry::new([ExtensionFile::read_from_str(YAML_ADD).unwrap()]).unwrap();
+        let declaration =
+            |function_anchor, name: &str| proto::extensions::SimpleExtensionDeclaration {
+                mapping_type: Some(MappingType::ExtensionFunction(decl::ExtensionFunction {
+                    extension_urn_reference: 1,
+                    function_anchor,
+                    name: name.to_string(),
+                    ..Default::default()
+                })),
+            };
+        let mut ctx = ExtensionAnchors::with_registry(&registry);
+        ctx.parse(proto::extensions::SimpleExtensionUrn {
+            extension_urn_anchor: 1,
+            urn: "extension:substrait-rs:test".to_string(),
+        })
+        .unwrap();
+
+        ctx.parse(declaration(1, "sum:i64"))?;
+        let resolved = ctx.resolve_extension_function(&Anchor::new(1))?;
+        assert_eq!(resolved.kind(), "aggregate");
+        assert_eq!(resolved.implementation().signature(), "i64");
+
+        // Window functions without arguments have an empty signature.
+        ctx.parse(declaration(2, "rank:"))?;
+        assert!(matches!(
+            ctx.resolve_extension_function(&Anchor::new(2))?,
+            ResolvedFunction::Window(..)
+        ));
+        ctx.parse(declaration(3, "rank"))?;
+
+        assert!(matches!(
+            ctx.parse(declaration(4, "sum")),
+            Err(SimpleExtensionDeclarationError::Context(
+                ContextError::UnknownFunctionSignature { .. }
+            ))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn resolve_function_without_registry() {
+        let mut ctx = ctx();
+        ctx.parse(proto::extensions::SimpleExtensionDeclaration {
+            mapping_type: Some(MappingType::ExtensionFunction(decl::ExtensionFunction {
+                extension_urn_reference: 1,
+                function_anchor: 1,
+                name: "add:i32_i32".to_string(),
+                ..Default::default()
+            })),
+        })
+        .unwrap();
+        assert_eq!(
+            ctx.resolve_extension_function(&Anchor::new(1)),
+            Err(ContextError::NoRegistry)
+        );
+    }
+
+    #[test]
+    fn resolve_function_unknown_urn() {
+        let registry = Registry::new([]).unwrap();
+        let mut ctx = ExtensionAnchors::with_registry(&registry);
+        let simple_extension_urn = ctx
+            .parse(proto::extensions::SimpleExtensionUrn {
+                extension_urn_anchor: 1,
+                urn: "extension:substrait-rs:test".to_string(),
+            })
+            .unwrap();
+        assert_eq!(
+            ctx.parse(proto::extensions::SimpleExtensionDeclaration {
+                mapping_type: Some(MappingType::ExtensionFunction(decl::ExtensionFunction {
+                    extension_urn_reference: 1,
+                    function_anchor: 1,
+                    name: "add:i32_i32".to_string(),
+                    ..Default::default()
+                })),
+            }),
+            Err(SimpleExtensionDeclarationError::Context(
+                ContextError::UnknownSimpleExtension(simple_extension_urn.urn().clone())
+            ))
+        );
+    }
+}
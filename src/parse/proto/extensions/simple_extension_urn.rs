@@ -50,11 +50,11 @@ pub enum SimpleExtensionUrnError {
     Context(#[from] ContextError),
 }
 
-impl Parse<ExtensionAnchors> for proto::extensions::SimpleExtensionUrn {
+impl Parse<ExtensionAnchors<'_>> for proto::extensions::SimpleExtensionUrn {
     type Parsed = SimpleExtensionUrn;
     type Error = SimpleExtensionUrnError;
 
-    fn parse(self, ctx: &mut ExtensionAnchors) -> Result<Self::Parsed, Self::Error> {
+    fn parse(self, ctx: &mut ExtensionAnchors<'_>) -> Result<Self::Parsed, Self::Error> {
         let proto::extensions::SimpleExtensionUrn {
             extension_urn_anchor: anchor,
             urn,
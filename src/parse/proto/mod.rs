@@ -10,27 +10,72 @@ pub use version::{Version, VersionError};
 mod plan_version;
 pub use plan_version::{PlanVersion, PlanVersionError};
 
+mod plan;
+pub use plan::{Plan, PlanError};
+
+mod plan_rel;
+pub use plan_rel::{PlanRel, PlanRelError, RelRoot};
+
+mod types;
+pub use types::{Type, TypeError, proto_type};
+
 use crate::{
-    parse::{Anchor, context::ContextError, proto::extensions::SimpleExtensionUrn},
+    parse::{
+        Anchor,
+        context::ContextError,
+        proto::extensions::{
+            ExtensionFunction, ExtensionType, ExtensionTypeVariation, SimpleExtensionUrn,
+        },
+        text::simple_extensions::{
+            AggregateFunction, AggregateImpl, Registry, ScalarFunction, ScalarFunctionImpl,
+            WindowFunction, WindowImpl, split_compound_name,
+        },
+    },
     urn::Urn,
 };
 
+pub mod expression;
+
 pub mod extensions;
 
+pub mod rel;
+
 /// Tracks the known anchors for simple extensions, as needed for parsing a
 /// Substrait protobuf.
 ///
-/// At the moment, this only tracks simple extension URNs by anchor, as further
-/// parsing of simple extensions, or the protobuf plan itself, is not supported
-/// yet.
+/// This tracks simple extension URNs, and the functions, types and type
+/// variations declared in those extensions, by anchor.
+///
+/// When constructed with [ExtensionAnchors::with_registry], this is the
+/// per-plan "Local Registry": declared functions are resolved against the
+/// referenced (global) [Registry] while parsing, and plans that declare
+/// functions the registry does not define are rejected.
 #[derive(Default)]
-pub struct ExtensionAnchors {
+pub struct ExtensionAnchors<'a> {
+    registry: Option<&'a Registry>,
     simple_extensions: HashMap<Anchor<SimpleExtensionUrn>, Urn>,
+    extension_functions: HashMap<Anchor<ExtensionFunction>, ExtensionFunction>,
+    extension_types: HashMap<Anchor<ExtensionType>, ExtensionType>,
+    extension_type_variations: HashMap<Anchor<ExtensionTypeVariation>, ExtensionTypeVariation>,
 }
 
-impl super::Context for ExtensionAnchors {}
+impl super::Context for ExtensionAnchors<'_> {}
+
+impl<'a> ExtensionAnchors<'a> {
+    /// Create an empty set of anchors that resolves declared functions
+    /// against the given [Registry].
+    pub fn with_registry(registry: &'a Registry) -> Self {
+        Self {
+            registry: Some(registry),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the [Registry] used to resolve declarations, if any.
+    pub fn registry(&self) -> Option<&'a Registry> {
+        self.registry
+    }
 
-impl ExtensionAnchors {
     /// Register a [SimpleExtensionUrn] with this registry, rejecting duplicate
     /// anchors.
     pub fn add_simple_extension_urn(
@@ -52,4 +97,261 @@ impl ExtensionAnchors {
     pub fn get_simple_extension_urn(&self, anchor: &Anchor<SimpleExtensionUrn>) -> Option<&Urn> {
         self.simple_extensions.get(anchor)
     }
+
+    /// Register an [ExtensionFunction] declaration with this registry,
+    /// rejecting duplicate anchors.
+    ///
+    /// When a [Registry] is set, the declared function must resolve, see
+    /// [ExtensionAnchors::resolve_extension_function].
+    pub fn add_extension_function(
+        &mut self,
+        extension_function: &ExtensionFunction,
+    ) -> Result<(), ContextError> {
+        if let Some(registry) = self.registry {
+            resolve_function(registry, extension_function)?;
+        }
+
+        let anchor = extension_function.anchor();
+        match self.extension_functions.entry(anchor) {
+            Entry::Occupied(_) => Err(ContextError::DuplicateExtensionFunction(anchor)),
+            Entry::Vacant(entry) => {
+                entry.insert(extension_function.clone());
+                Ok(())
+            }
+        }
+    }
+
+    /// Look up the [ExtensionFunction] for a previously declared anchor.
+    /// Returns an error if the anchor has not been declared.
+    pub fn get_extension_function(
+        &self,
+        anchor: &Anchor<ExtensionFunction>,
+    ) -> Result<&ExtensionFunction, ContextError> {
+        self.extension_functions
+            .get(anchor)
+            .ok_or(ContextError::UndefinedExtensionFunction(*anchor))
+    }
+
+    /// Look up the definition and implementation of the function referenced
+    /// by a previously declared function anchor in the [Registry].
+    ///
+    /// Returns an error if the anchor has not been declared, if no registry is
+    /// set, or if the registry does not define the function.
+    pub fn resolve_extension_function(
+        &self,
+        anchor: &Anchor<ExtensionFunction>,
+    ) -> Result<ResolvedFunction<'a>, ContextError> {
+        let extension_function = self.get_extension_function(anchor)?;
+        let registry = self.registry.ok_or(ContextError::NoRegistry)?;
+        resolve_function(registry, extension_function)
+    }
+
+    /// Register an [ExtensionType] declaration with this registry, rejecting
+    /// duplicate anchors.
+    pub fn add_extension_type(
+        &mut self,
+        extension_type: &ExtensionType,
+    ) -> Result<(), ContextError> {
+        let anchor = extension_type.anchor();
+        match self.extension_types.entry(anchor) {
+            Entry::Occupied(_) => Err(ContextError::DuplicateExtensionType(anchor)),
+            Entry::Vacant(entry) => {
+                entry.insert(extension_type.clone());
+                Ok(())
+            }
+        }
+    }
+
+    /// Look up the [ExtensionType] for a previously declared anchor. Returns
+    /// an error if the anchor has not been declared.
+    pub fn get_extension_type(
+        &self,
+        anchor: &Anchor<ExtensionType>,
+    ) -> Result<&ExtensionType, ContextError> {
+        self.extension_types
+            .get(anchor)
+            .ok_or(ContextError::UndefinedExtensionType(*anchor))
+    }
+
+    /// Look up the anchor of the [ExtensionType] declared with a name and, if
+    /// given, the URN of the extension defining it. Returns an error if no
+    /// type, or more than one type, matches.
+    pub fn find_extension_type(
+        &self,
+        urn: Option<&Urn>,
+        name: &str,
+    ) -> Result<Anchor<ExtensionType>, ContextError> {
+        let mut anchors = self
+            .extension_types
+            .values()
+            .filter(|extension_type| {
+                extension_type.name() == name && urn.is_none_or(|urn| extension_type.urn() == urn)
+            })
+            .map(ExtensionType::anchor);
+        match (anchors.next(), anchors.next()) {
+            (Some(anchor), None) => Ok(anchor),
+            (None, _) => Err(ContextError::UndeclaredExtensionType(name.to_string())),
+            (Some(_), Some(_)) => Err(ContextError::AmbiguousExtensionType(name.to_string())),
+        }
+    }
+
+    /// Register an [ExtensionTypeVariation] declaration with this registry,
+    /// rejecting duplicate anchors.
+    pub fn add_extension_type_variation(
+        &mut self,
+        extension_type_variation: &ExtensionTypeVariation,
+    ) -> Result<(), ContextError> {
+        let anchor = extension_type_variation.anchor();
+        match self.extension_type_variations.entry(anchor) {
+            Entry::Occupied(_) => Err(ContextError::DuplicateExtensionTypeVariation(anchor)),
+            Entry::Vacant(entry) => {
+                entry.insert(extension_type_variation.clone());
+                Ok(())
+            }
+        }
+    }
+
+    /// Look up the [ExtensionTypeVariation] for a previously declared anchor.
+    /// Returns an error if the anchor has not been declared.
+    pub fn get_extension_type_variation(
+        &self,
+        anchor: &Anchor<ExtensionTypeVariation>,
+    ) -> Result<&ExtensionTypeVariation, ContextError> {
+        self.extension_type_variations
+            .get(anchor)
+            .ok_or(ContextError::UndefinedExtensionTypeVariation(*anchor))
+    }
+
+    /// Look up the anchor of the [ExtensionTypeVariation] declared with a
+    /// name and, if given, the URN of the extension defining it. Returns an
+    /// error if no variation, or more than one variation, matches.
+    pub fn find_extension_type_variation(
+        &self,
+        urn: Option<&Urn>,
+        name: &str,
+    ) -> Result<Anchor<ExtensionTypeVariation>, ContextError> {
+        let mut anchors = self
+            .extension_type_variations
+            .values()
+            .filter(|variation| {
+                variation.name() == name && urn.is_none_or(|urn| variation.urn() == urn)
+            })
+            .map(ExtensionTypeVariation::anchor);
+        match (anchors.next(), anchors.next()) {
+            (Some(anchor), None) => Ok(anchor),
+            (None, _) => Err(ContextError::UndeclaredExtensionTypeVariation(
+                name.to_string(),
+            )),
+            (Some(_), Some(_)) => Err(ContextError::AmbiguousExtensionTypeVariation(
+                name.to_string(),
+            )),
+        }
+    }
+}
+
+/// The definition and implementation of a declared function, as resolved
+/// against a [Registry].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ResolvedFunction<'a> {
+    /// A scalar function.
+    Scalar(&'a ScalarFunction, &'a ScalarFunctionImpl),
+
+    /// An aggregate function.
+    Aggregate(&'a AggregateFunction, &'a AggregateImpl),
+
+    /// A window function.
+    Window(&'a WindowFunction, &'a WindowImpl),
+}
+
+impl<'a> ResolvedFunction<'a> {
+    /// Returns the name of the function.
+    pub fn name(&self) -> &'a str {
+        match self {
+            ResolvedFunction::Scalar(function, _) => &function.name,
+            ResolvedFunction::Aggregate(function, _) => &function.name,
+            ResolvedFunction::Window(function, _) => &function.name,
+        }
+    }
+
+    /// Returns the kind of the function: `scalar`, `aggregate` or `window`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ResolvedFunction::Scalar(..) => "scalar",
+            ResolvedFunction::Aggregate(..) => "aggregate",
+            ResolvedFunction::Window(..) => "window",
+        }
+    }
+
+    /// Returns the arguments, options and return type of the implementation,
+    /// which all kinds of functions share.
+    pub fn implementation(&self) -> &'a ScalarFunctionImpl {
+        match self {
+            ResolvedFunction::Scalar(_, implementation) => implementation,
+            ResolvedFunction::Aggregate(_, implementation) => &implementation.impl_,
+            ResolvedFunction::Window(_, implementation) => &implementation.aggregate.impl_,
+        }
+    }
+}
+
+/// Resolves a declared function against a [Registry].
+///
+/// The function is looked up by name among the scalar, aggregate and window
+/// functions of the extension. The name of the declaration is a compound name
+/// (e.g. `add:i32_i32`), and the signature part selects the implementation. A
+/// plain name (e.g. `add`) resolves only if the function has exactly one
+/// implementation.
+fn resolve_function<'a>(
+    registry: &'a Registry,
+    extension_function: &ExtensionFunction,
+) -> Result<ResolvedFunction<'a>, ContextError> {
+    let urn = extension_function.urn();
+    let compound_name = extension_function.name();
+    let (name, signature) = split_compound_name(compound_name);
+
+    let extension = registry
+        .get_extension(urn)
+        .ok_or_else(|| ContextError::UnknownSimpleExtension(urn.clone()))?;
+    let unknown_signature = || ContextError::UnknownFunctionSignature {
+        urn: urn.clone(),
+        name: compound_name.to_string(),
+    };
+
+    if let Some(function) = extension.get_scalar_function(name) {
+        let function_impl = function
+            .get_impl_by_compound_name(compound_name)
+            .ok_or_else(unknown_signature)?;
+        Ok(ResolvedFunction::Scalar(function, function_impl))
+    } else if let Some(function) = extension.get_aggregate_function(name) {
+        let function_impl = match signature {
+            Some(signature) => function.get_impl(signature),
+            None => single_impl(&function.impls),
+        };
+        Ok(ResolvedFunction::Aggregate(
+            function,
+            function_impl.ok_or_else(unknown_signature)?,
+        ))
+    } else if let Some(function) = extension.get_window_function(name) {
+        let function_impl = match signature {
+            Some(signature) => function.get_impl(signature),
+            None => single_impl(&function.impls),
+        };
+        Ok(ResolvedFunction::Window(
+            function,
+            function_impl.ok_or_else(unknown_signature)?,
+        ))
+    } else {
+        Err(ContextError::UnknownFunction {
+            urn: urn.clone(),
+            name: compound_name.to_string(),
+        })
+    }
+}
+
+/// Returns the implementation of a function referenced without a signature,
+/// which must be its only implementation.
+fn single_impl<T>(impls: &[T]) -> Option<&T> {
+    match impls {
+        [impl_] => Some(impl_),
+        _ => None,
+    }
 }
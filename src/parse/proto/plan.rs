@@ -0,0 +1,424 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing of [proto::Plan].
+
+use thiserror::Error;
+
+use crate::{
+    parse::{
+        Context, Parse,
+        proto::{
+            ExtensionAnchors, PlanRel, PlanRelError, Version, VersionError,
+            extensions::{
+                SimpleExtensionDeclaration, SimpleExtensionDeclarationError, SimpleExtensionUrn,
+                SimpleExtensionUrnError,
+            },
+        },
+        text::simple_extensions::Registry,
+    },
+    proto,
+};
+
+/// A parsed [proto::Plan].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Plan {
+    /// The version of Substrait used to produce this plan.
+    version: Version,
+
+    /// The simple extension urns used by this plan.
+    extension_urns: Vec<SimpleExtensionUrn>,
+
+    /// The extension declarations of this plan.
+    extensions: Vec<SimpleExtensionDeclaration>,
+
+    /// The relations of this plan.
+    relations: Vec<PlanRel>,
+
+    /// Optional advanced extensions.
+    advanced_extensions: Option<proto::extensions::AdvancedExtension>,
+
+    /// The type urls of protobuf messages referenced by this plan.
+    expected_type_urls: Vec<String>,
+}
+
+impl Plan {
+    /// Parse a [proto::Plan], resolving the functions it declares against the
+    /// given [Registry].
+    ///
+    /// Unlike [Plan::try_from], this fails when a declared function is not
+    /// defined by the registry.
+    pub fn parse_with_registry(plan: proto::Plan, registry: &Registry) -> Result<Self, PlanError> {
+        plan.parse(&mut ExtensionAnchors::with_registry(registry))
+    }
+
+    /// Parse a [proto::Plan], registering its simple extension urns and
+    /// extension declarations with the given [ExtensionAnchors].
+    ///
+    /// The anchors can then be used to parse the expressions of the plan,
+    /// see [Expression::parse_with_anchors](super::expression::Expression::parse_with_anchors).
+    pub fn parse_with_anchors(
+        plan: proto::Plan,
+        anchors: &mut ExtensionAnchors<'_>,
+    ) -> Result<Self, PlanError> {
+        plan.parse(anchors)
+    }
+
+    /// Returns the version of this plan.
+    ///
+    /// See [proto::Plan::version].
+    pub fn version(&self) -> &Version {
+        &self.version
+    }
+
+    /// Returns the simple extension urns of this plan.
+    ///
+    /// See [proto::Plan::extension_urns].
+    pub fn extension_urns(&self) -> &[SimpleExtensionUrn] {
+        &self.extension_urns
+    }
+
+    /// Returns the extension declarations of this plan.
+    ///
+    /// See [proto::Plan::extensions].
+    pub fn extensions(&self) -> &[SimpleExtensionDeclaration] {
+        &self.extensions
+    }
+
+    /// Returns the relations of this plan.
+    ///
+    /// See [proto::Plan::relations].
+    pub fn relations(&self) -> &[PlanRel] {
+        &self.relations
+    }
+
+    /// Returns the advanced extensions of this plan, if set.
+    ///
+    /// See [proto::Plan::advanced_extensions].
+    pub fn advanced_extensions(&self) -> Option<&proto::extensions::AdvancedExtension> {
+        self.advanced_extensions.as_ref()
+    }
+
+    /// Returns the expected type urls of this plan.
+    ///
+    /// See [proto::Plan::expected_type_urls].
+    pub fn expected_type_urls(&self) -> &[String] {
+        &self.expected_type_urls
+    }
+}
+
+/// Parse errors for [proto::Plan].
+#[derive(Debug, Error, PartialEq)]
+pub enum PlanError {
+    /// Version is missing.
+    #[error("version must be specified")]
+    MissingVersion,
+
+    /// Version error.
+    #[error("version must be valid")]
+    Version(#[from] VersionError),
+
+    /// Simple extension urn error.
+    #[error("invalid simple extension urn")]
+    SimpleExtensionUrn(#[from] SimpleExtensionUrnError),
+
+    /// Simple extension declaration error.
+    #[error("invalid simple extension declaration")]
+    SimpleExtensionDeclaration(#[from] SimpleExtensionDeclarationError),
+
+    /// Plan relation error.
+    #[error("invalid plan relation")]
+    PlanRel(#[from] PlanRelError),
+}
+
+impl Parse<ExtensionAnchors<'_>> for proto::Plan {
+    type Parsed = Plan;
+    type Error = PlanError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'_>) -> Result<Self::Parsed, Self::Error> {
+        // Deprecated fields are not carried over to the parsed plan.
+        let proto::Plan {
+            version,
+            extension_urns,
+            extensions,
+            relations,
+            advanced_extensions,
+            expected_type_urls,
+            ..
+        } = self;
+
+        // The version is required, and must be valid and compatible.
+        let version = version
+            .map(Version::try_from)
+            .transpose()?
+            .ok_or(PlanError::MissingVersion)?;
+
+        // Register the simple extension urns, rejecting duplicate anchors.
+        let extension_urns = extension_urns
+            .into_iter()
+            .map(|extension_urn| ctx.parse(extension_urn))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Register the extension declarations, which must reference the
+        // simple extension urns declared above.
+        let extensions = extensions
+            .into_iter()
+            .map(|extension| ctx.parse(extension))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Parse the relations.
+        let relations = relations
+            .into_iter()
+            .map(|relation| ctx.parse(relation))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Plan {
+            version,
+            extension_urns,
+            extensions,
+            relations,
+            advanced_extensions,
+            expected_type_urls,
+        })
+    }
+}
+
+impl TryFrom<proto::Plan> for Plan {
+    type Error = PlanError;
+
+    fn try_from(value: proto::Plan) -> Result<Self, Self::Error> {
+        value.parse(&mut ExtensionAnchors::default())
+    }
+}
+
+impl From<Plan> for proto::Plan {
+    fn from(plan: Plan) -> Self {
+        let Plan {
+            version,
+            extension_urns,
+            extensions,
+            relations,
+            advanced_extensions,
+            expected_type_urls,
+        } = plan;
+
+        proto::Plan {
+            version: Some(version.into()),
+            extension_urns: extension_urns.into_iter().map(Into::into).collect(),
+            extensions: extensions.into_iter().map(Into::into).collect(),
+            relations: relations.into_iter().map(Into::into).collect(),
+            advanced_extensions,
+            expected_type_urls,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parse::{Anchor, context::ContextError},
+        version,
+    };
+
+    fn plan() -> proto::Plan {
+        proto::Plan {
+            version: Some(version::version()),
+            extension_urns: vec![proto::extensions::SimpleExtensionUrn {
+                extension_urn_anchor: 1,
+                urn: "extension:substrait-rs:test".to_string(),
+            }],
+            extensions: vec![proto::extensions::SimpleExtensionDeclaration {
+                mapping_type: Some(
+                    proto::extensions::simple_extension_declaration::MappingType::ExtensionFunction(
+                        proto::extensions::simple_extension_declaration::ExtensionFunction {
+                            extension_urn_reference: 1,
+                            function_anchor: 7,
+                            name: "add:i32_i32".to_string(),
+                            ..Default::default()
+                        },
+                    ),
+                ),
+            }],
+            relations: vec![proto::PlanRel {
+                rel_type: Some(proto::plan_rel::RelType::Root(proto::RelRoot {
+                    input: Some(crate::parse::proto::rel::tests::read_rel()),
+                    names: vec!["a".to_string()],
+                })),
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parse() -> Result<(), PlanError> {
+        let mut ctx = ExtensionAnchors::default();
+        let plan = ctx.parse(plan())?;
+        assert_eq!(plan.extension_urns().len(), 1);
+        assert_eq!(plan.extensions().len(), 1);
+        assert_eq!(plan.relations().len(), 1);
+        assert!(
+            ctx.get_simple_extension_urn(&Anchor::new(1)).is_some(),
+            "extension urns should be registered in the context"
+        );
+        assert_eq!(
+            ctx.get_extension_function(&Anchor::new(7))
+                .map(|function| function.name()),
+            Ok("add:i32_i32")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn window_function() -> Result<(), PlanError> {
+        // Expressions of types that are not parsed, like window functions,
+        // are kept as they are.
+        let window_function = proto::Expression {
+            rex_type: Some(proto::expression::RexType::WindowFunction(
+                proto::expression::WindowFunction {
+                    function_reference: 7,
+                    ..Default::default()
+                },
+            )),
+        };
+        let project = proto::Rel {
+            rel_type: Some(proto::rel::RelType::Project(Box::new(proto::ProjectRel {
+                input: Some(Box::new(crate::parse::proto::rel::tests::read_rel())),
+                expressions: vec![window_function.clone()],
+                ..Default::default()
+            }))),
+        };
+        let plan = proto::Plan {
+            relations: vec![proto::PlanRel {
+                rel_type: Some(proto::plan_rel::RelType::Rel(project.clone())),
+            }],
+            ..plan()
+        };
+        let plan = Plan::try_from(plan)?;
+        assert_eq!(
+            proto::Plan::from(plan).relations,
+            [proto::PlanRel {
+                rel_type: Some(proto::plan_rel::RelType::Rel(project)),
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn missing_version() {
+        let plan = proto::Plan {
+            version: None,
+            ..plan()
+        };
+        assert_eq!(Plan::try_from(plan), Err(PlanError::MissingVersion));
+    }
+
+    #[test]
+    fn incompatible_version() {
+        let mut version = version::version();
+        version.major_number += 1;
+        let plan = proto::Plan {
+            version: Some(version),
+            ..plan()
+        };
+        assert!(matches!(
+            Plan::try_from(plan),
+            Err(PlanError::Version(VersionError::Substrait(_, _)))
+        ));
+    }
+
+    #[test]
+    fn duplicate_extension_urn() {
+        let mut plan = plan();
+        plan.extension_urns.push(plan.extension_urns[0].clone());
+        assert_eq!(
+            Plan::try_from(plan),
+            Err(PlanError::SimpleExtensionUrn(
+                SimpleExtensionUrnError::Context(ContextError::DuplicateSimpleExtension(
+                    Anchor::new(1)
+                ))
+            ))
+        );
+    }
+
+    #[test]
+    fn undefined_simple_extension() {
+        let plan = proto::Plan {
+            extension_urns: vec![],
+            ..plan()
+        };
+        assert_eq!(
+            Plan::try_from(plan),
+            Err(PlanError::SimpleExtensionDeclaration(
+                SimpleExtensionDeclarationError::Context(ContextError::UndefinedSimpleExtension(
+                    Anchor::new(1)
+                ))
+            ))
+        );
+    }
+
+    #[test]
+    fn invalid_plan_rel() {
+        let plan = proto::Plan {
+            relations: vec![proto::PlanRel::default()],
+            ..plan()
+        };
+        assert_eq!(
+            Plan::try_from(plan),
+            Err(PlanError::PlanRel(PlanRelError::Missing))
+        );
+    }
+
+    #[test]
+    fn parse_with_registry() {
+        let registry = Registry::new([]).unwrap();
+        assert_eq!(
+            Plan::parse_with_registry(plan(), &registry),
+            Err(PlanError::SimpleExtensionDeclaration(
+                SimpleExtensionDeclarationError::Context(ContextError::UnknownSimpleExtension(
+                    "extension:substrait-rs:test".parse().unwrap()
+                ))
+            ))
+        );
+    }
+
+    #[cfg(feature = "extensions")]
+    #[test]
+    fn parse_with_core_registry() -> Result<(), PlanError> {
+        use proto::extensions::simple_extension_declaration::{ExtensionFunction, MappingType};
+
+        let registry = Registry::from_core_extensions();
+        let declaration =
+            |function_anchor, name: &str| proto::extensions::SimpleExtensionDeclaration {
+                mapping_type: Some(MappingType::ExtensionFunction(ExtensionFunction {
+                    extension_urn_reference: 1,
+                    function_anchor,
+                    name: name.to_string(),
+                    ..Default::default()
+                })),
+            };
+        // Aggregate and window functions are resolved as well.
+        let plan = proto::Plan {
+            extension_urns: vec![proto::extensions::SimpleExtensionUrn {
+                extension_urn_anchor: 1,
+                urn: "extension:io.substrait:functions_arithmetic".to_string(),
+            }],
+            extensions: vec![
+                declaration(1, "add:i64_i64"),
+                declaration(2, "sum:i64"),
+                declaration(3, "rank:"),
+            ],
+            ..plan()
+        };
+        let plan = Plan::parse_with_registry(plan, &registry)?;
+        assert_eq!(plan.extensions().len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn convert() -> Result<(), PlanError> {
+        let plan = plan();
+        assert_eq!(proto::Plan::from(Plan::try_from(plan.clone())?), plan);
+        Ok(())
+    }
+}
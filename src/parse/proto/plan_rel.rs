@@ -0,0 +1,185 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing of [proto::PlanRel] and [proto::RelRoot].
+
+use thiserror::Error;
+
+use crate::{
+    parse::{
+        Context, Parse,
+        proto::{
+            ExtensionAnchors,
+            rel::{Rel, RelError},
+        },
+    },
+    proto,
+};
+
+/// A parsed [proto::PlanRel].
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlanRel {
+    /// A relation that is not the root of the plan, e.g. a shared subtree.
+    ///
+    /// See [proto::plan_rel::RelType::Rel].
+    Rel(Rel),
+
+    /// The root of a relation tree, including its output field names.
+    ///
+    /// See [proto::plan_rel::RelType::Root].
+    Root(RelRoot),
+}
+
+/// A parsed [proto::RelRoot].
+#[derive(Clone, Debug, PartialEq)]
+pub struct RelRoot {
+    /// The input relation.
+    input: Rel,
+
+    /// The field names of the output, in depth-first order.
+    names: Vec<String>,
+}
+
+impl RelRoot {
+    /// Returns the input relation of this root.
+    ///
+    /// See [proto::RelRoot::input].
+    pub fn input(&self) -> &Rel {
+        &self.input
+    }
+
+    /// Returns the output field names of this root.
+    ///
+    /// See [proto::RelRoot::names].
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+}
+
+/// Parse errors for [proto::PlanRel] and [proto::RelRoot].
+#[derive(Debug, Error, PartialEq)]
+pub enum PlanRelError {
+    /// The relation type is missing.
+    #[error("plan relation type must be specified")]
+    Missing,
+
+    /// The input of a root relation is missing.
+    #[error("root relation input must be specified")]
+    MissingInput,
+
+    /// The relation is invalid.
+    #[error(transparent)]
+    Rel(#[from] RelError),
+}
+
+impl Parse<ExtensionAnchors<'_>> for proto::PlanRel {
+    type Parsed = PlanRel;
+    type Error = PlanRelError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'_>) -> Result<Self::Parsed, Self::Error> {
+        let proto::PlanRel { rel_type } = self;
+
+        // The relation type is a required oneof.
+        match rel_type.ok_or(PlanRelError::Missing)? {
+            proto::plan_rel::RelType::Rel(rel) => Ok(PlanRel::Rel(ctx.parse(rel)?)),
+            proto::plan_rel::RelType::Root(root) => Ok(PlanRel::Root(root.parse(ctx)?)),
+        }
+    }
+}
+
+impl From<PlanRel> for proto::PlanRel {
+    fn from(plan_rel: PlanRel) -> Self {
+        proto::PlanRel {
+            rel_type: Some(match plan_rel {
+                PlanRel::Rel(rel) => proto::plan_rel::RelType::Rel(rel.into()),
+                PlanRel::Root(root) => proto::plan_rel::RelType::Root(root.into()),
+            }),
+        }
+    }
+}
+
+impl Parse<ExtensionAnchors<'_>> for proto::RelRoot {
+    type Parsed = RelRoot;
+    type Error = PlanRelError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'_>) -> Result<Self::Parsed, Self::Error> {
+        let proto::RelRoot { input, names } = self;
+
+        // The input is required.
+        let input = ctx.parse(input.ok_or(PlanRelError::MissingInput)?)?;
+
+        Ok(RelRoot { input, names })
+    }
+}
+
+impl From<RelRoot> for proto::RelRoot {
+    fn from(rel_root: RelRoot) -> Self {
+        let RelRoot { input, names } = rel_root;
+
+        proto::RelRoot {
+            input: Some(input.into()),
+            names,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::proto::rel::tests::read_rel;
+
+    #[test]
+    fn parse() -> Result<(), PlanRelError> {
+        let mut ctx = ExtensionAnchors::default();
+
+        let plan_rel = proto::PlanRel {
+            rel_type: Some(proto::plan_rel::RelType::Rel(read_rel())),
+        };
+        assert!(matches!(ctx.parse(plan_rel)?, PlanRel::Rel(Rel::Read(_))));
+
+        let plan_rel = proto::PlanRel {
+            rel_type: Some(proto::plan_rel::RelType::Root(proto::RelRoot {
+                input: Some(read_rel()),
+                names: vec!["a".to_string()],
+            })),
+        };
+        let PlanRel::Root(root) = ctx.parse(plan_rel)? else {
+            panic!("expected a root relation");
+        };
+        assert_eq!(root.names(), ["a".to_string()]);
+        Ok(())
+    }
+
+    #[test]
+    fn missing() {
+        let mut ctx = ExtensionAnchors::default();
+        assert_eq!(
+            ctx.parse(proto::PlanRel::default()),
+            Err(PlanRelError::Missing)
+        );
+        assert_eq!(
+            ctx.parse(proto::PlanRel {
+                rel_type: Some(proto::plan_rel::RelType::Root(proto::RelRoot::default())),
+            }),
+            Err(PlanRelError::MissingInput)
+        );
+        assert_eq!(
+            ctx.parse(proto::PlanRel {
+                rel_type: Some(proto::plan_rel::RelType::Rel(proto::Rel::default())),
+            }),
+            Err(PlanRelError::Rel(RelError::Missing))
+        );
+    }
+
+    #[test]
+    fn convert() -> Result<(), PlanRelError> {
+        let plan_rel = proto::PlanRel {
+            rel_type: Some(proto::plan_rel::RelType::Root(proto::RelRoot {
+                input: Some(read_rel()),
+                names: vec!["a".to_string(), "b".to_string()],
+            })),
+        };
+        let parsed = plan_rel.clone().parse(&mut ExtensionAnchors::default())?;
+        assert_eq!(proto::PlanRel::from(parsed), plan_rel);
+        Ok(())
+    }
+}
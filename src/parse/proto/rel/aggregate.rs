@@ -0,0 +1,343 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing of [proto::AggregateRel].
+
+use crate::{
+    parse::{
+        Context, Parse,
+        proto::{
+            ExtensionAnchors,
+            expression::parse_aggregate_function,
+            rel::{Rel, RelError, parse_expression, parse_input, required},
+        },
+    },
+    proto::{self, aggregate_rel::Grouping},
+};
+
+/// A parsed [proto::AggregateRel].
+#[derive(Clone, Debug, PartialEq)]
+pub struct AggregateRel {
+    /// Common fields of relations.
+    common: Option<proto::RelCommon>,
+
+    /// The input relation.
+    input: Box<Rel>,
+
+    /// The groupings of the aggregation.
+    groupings: Vec<Grouping>,
+
+    /// The aggregate functions computed for each group.
+    measures: Vec<Measure>,
+
+    /// The expressions referenced by the groupings.
+    grouping_expressions: Vec<proto::Expression>,
+
+    /// Optional advanced extension.
+    advanced_extension: Option<proto::extensions::AdvancedExtension>,
+}
+
+impl AggregateRel {
+    /// Returns the common fields of this relation.
+    ///
+    /// See [proto::AggregateRel::common].
+    pub fn common(&self) -> Option<&proto::RelCommon> {
+        self.common.as_ref()
+    }
+
+    /// Returns the input relation.
+    ///
+    /// See [proto::AggregateRel::input].
+    pub fn input(&self) -> &Rel {
+        &self.input
+    }
+
+    /// Returns the groupings of the aggregation.
+    ///
+    /// The expression references of the groupings are valid indices into
+    /// [AggregateRel::grouping_expressions].
+    ///
+    /// See [proto::AggregateRel::groupings].
+    pub fn groupings(&self) -> &[Grouping] {
+        &self.groupings
+    }
+
+    /// Returns the aggregate functions computed for each group.
+    ///
+    /// See [proto::AggregateRel::measures].
+    pub fn measures(&self) -> &[Measure] {
+        &self.measures
+    }
+
+    /// Returns the expressions referenced by the groupings.
+    ///
+    /// See [proto::AggregateRel::grouping_expressions].
+    pub fn grouping_expressions(&self) -> &[proto::Expression] {
+        &self.grouping_expressions
+    }
+
+    /// Returns the advanced extension of this relation.
+    ///
+    /// See [proto::AggregateRel::advanced_extension].
+    pub fn advanced_extension(&self) -> Option<&proto::extensions::AdvancedExtension> {
+        self.advanced_extension.as_ref()
+    }
+}
+
+/// A parsed [proto::aggregate_rel::Measure].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Measure {
+    /// The aggregate function, declared by the plan.
+    measure: proto::AggregateFunction,
+
+    /// An optional filter on the records that are aggregated.
+    filter: Option<proto::Expression>,
+}
+
+impl Measure {
+    /// Returns the aggregate function.
+    ///
+    /// See [proto::aggregate_rel::Measure::measure].
+    pub fn measure(&self) -> &proto::AggregateFunction {
+        &self.measure
+    }
+
+    /// Returns the filter on the records that are aggregated, if any.
+    ///
+    /// See [proto::aggregate_rel::Measure::filter].
+    pub fn filter(&self) -> Option<&proto::Expression> {
+        self.filter.as_ref()
+    }
+}
+
+impl Parse<ExtensionAnchors<'_>> for proto::AggregateRel {
+    type Parsed = AggregateRel;
+    type Error = RelError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'_>) -> Result<Self::Parsed, Self::Error> {
+        let proto::AggregateRel {
+            common,
+            input,
+            groupings,
+            measures,
+            grouping_expressions,
+            advanced_extension,
+        } = self;
+
+        let input = parse_input(input, "aggregate", ctx)?;
+
+        // Groupings reference the grouping expressions by index.
+        let count = grouping_expressions.len();
+        if let Some(&reference) = groupings
+            .iter()
+            .flat_map(|grouping| &grouping.expression_references)
+            .find(|&&reference| reference as usize >= count)
+        {
+            return Err(RelError::GroupingReference { reference, count });
+        }
+
+        Ok(AggregateRel {
+            common,
+            input,
+            groupings,
+            measures: measures
+                .into_iter()
+                .map(|measure| measure.parse(ctx))
+                .collect::<Result<_, _>>()?,
+            grouping_expressions: grouping_expressions
+                .into_iter()
+                .map(|expression| parse_expression(expression, ctx))
+                .collect::<Result<_, _>>()?,
+            advanced_extension,
+        })
+    }
+}
+
+impl From<AggregateRel> for proto::AggregateRel {
+    fn from(aggregate: AggregateRel) -> Self {
+        let AggregateRel {
+            common,
+            input,
+            groupings,
+            measures,
+            grouping_expressions,
+            advanced_extension,
+        } = aggregate;
+
+        proto::AggregateRel {
+            common,
+            input: Some(Box::new((*input).into())),
+            groupings,
+            measures: measures.into_iter().map(Into::into).collect(),
+            grouping_expressions,
+            advanced_extension,
+        }
+    }
+}
+
+impl Parse<ExtensionAnchors<'_>> for proto::aggregate_rel::Measure {
+    type Parsed = Measure;
+    type Error = RelError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'_>) -> Result<Self::Parsed, Self::Error> {
+        let proto::aggregate_rel::Measure { measure, filter } = self;
+
+        // The aggregate function must be declared, and is bound to its
+        // implementation when a registry is available.
+        let measure = parse_aggregate_function(required(measure, "aggregate", "measure")?, ctx)?;
+        for sort in &measure.sorts {
+            ctx.parse(sort.clone())?;
+        }
+
+        Ok(Measure {
+            measure,
+            filter: filter
+                .map(|filter| parse_expression(filter, ctx))
+                .transpose()?,
+        })
+    }
+}
+
+impl From<Measure> for proto::aggregate_rel::Measure {
+    fn from(measure: Measure) -> Self {
+        let Measure { measure, filter } = measure;
+
+        proto::aggregate_rel::Measure {
+            measure: Some(measure),
+            filter,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{
+        Anchor,
+        context::ContextError,
+        proto::{
+            expression::{
+                ExpressionError,
+                tests::{anchors, i32_type, registry},
+            },
+            rel::tests::{field, read_rel},
+        },
+        text::simple_extensions::RejectionReason,
+    };
+    use proto::{
+        extensions::{
+            SimpleExtensionDeclaration,
+            simple_extension_declaration::{ExtensionFunction, MappingType},
+        },
+        function_argument::ArgType,
+    };
+
+    /// A measure calling the function with the given anchor.
+    fn measure(anchor: u32, arguments: Vec<proto::Expression>) -> proto::aggregate_rel::Measure {
+        proto::aggregate_rel::Measure {
+            measure: Some(proto::AggregateFunction {
+                function_reference: anchor,
+                arguments: arguments
+                    .into_iter()
+                    .map(|argument| proto::FunctionArgument {
+                        arg_type: Some(ArgType::Value(argument)),
+                    })
+                    .collect(),
+                output_type: Some(i32_type()),
+                ..Default::default()
+            }),
+            filter: None,
+        }
+    }
+
+    #[test]
+    fn groupings_and_measures() {
+        let mut ctx = ExtensionAnchors::default();
+        let aggregate = proto::AggregateRel {
+            input: Some(Box::new(read_rel())),
+            groupings: vec![Grouping {
+                expression_references: vec![0],
+                ..Default::default()
+            }],
+            grouping_expressions: vec![field(0)],
+            ..Default::default()
+        };
+        let parsed = ctx.parse(aggregate.clone()).unwrap();
+        assert_eq!(parsed.grouping_expressions(), [field(0)]);
+        assert_eq!(proto::AggregateRel::from(parsed), aggregate);
+
+        assert_eq!(
+            ctx.parse(proto::AggregateRel {
+                grouping_expressions: vec![],
+                ..aggregate.clone()
+            }),
+            Err(RelError::GroupingReference {
+                reference: 0,
+                count: 0
+            })
+        );
+
+        // Measures must use declared functions
+        assert_eq!(
+            ctx.parse(proto::AggregateRel {
+                measures: vec![proto::aggregate_rel::Measure {
+                    measure: Some(proto::AggregateFunction {
+                        function_reference: 1,
+                        ..Default::default()
+                    }),
+                    filter: None,
+                }],
+                ..aggregate
+            }),
+            Err(RelError::Expression(Box::new(ExpressionError::Context(
+                ContextError::UndefinedExtensionFunction(Anchor::new(1))
+            ))))
+        );
+    }
+
+    #[test]
+    fn bind_measures() {
+        let registry = registry();
+        let mut ctx = anchors(Some(&registry));
+        ctx.parse(SimpleExtensionDeclaration {
+            mapping_type: Some(MappingType::ExtensionFunction(ExtensionFunction {
+                extension_urn_reference: 1,
+                function_anchor: 3,
+                name: "sum".to_string(),
+                ..Default::default()
+            })),
+        })
+        .unwrap();
+        let aggregate = |measure| proto::AggregateRel {
+            input: Some(Box::new(read_rel())),
+            measures: vec![measure],
+            ..Default::default()
+        };
+
+        let parsed = ctx.parse(aggregate(measure(3, vec![field(0)]))).unwrap();
+        assert_eq!(parsed.measures().len(), 1);
+
+        // Measures must call aggregate functions, with matching arguments
+        assert_eq!(
+            ctx.parse(aggregate(measure(1, vec![field(0)]))),
+            Err(RelError::Expression(Box::new(
+                ExpressionError::FunctionKind {
+                    function: "add".to_string(),
+                    expected: "aggregate",
+                    actual: "scalar"
+                }
+            )))
+        );
+        assert_eq!(
+            ctx.parse(aggregate(measure(3, vec![field(0), field(0)]))),
+            Err(RelError::Expression(Box::new(
+                ExpressionError::ArgumentCount {
+                    function: "sum".to_string(),
+                    reason: RejectionReason::ArgumentCount {
+                        expected: 1,
+                        actual: 2
+                    }
+                }
+            )))
+        );
+    }
+}
@@ -0,0 +1,214 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing of [proto::ExtensionSingleRel], [proto::ExtensionMultiRel] and
+//! [proto::ExtensionLeafRel].
+//!
+//! The details of extension relations are opaque to this crate, and are kept
+//! as they are.
+
+use crate::{
+    parse::{
+        Context, Parse,
+        proto::{
+            ExtensionAnchors,
+            rel::{Rel, RelError, parse_input, required},
+        },
+    },
+    proto,
+};
+
+/// A parsed [proto::ExtensionSingleRel].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExtensionSingleRel {
+    /// Common fields of relations.
+    common: Option<proto::RelCommon>,
+
+    /// The input relation.
+    input: Box<Rel>,
+
+    /// The details of the extension.
+    detail: prost_types::Any,
+}
+
+impl ExtensionSingleRel {
+    /// Returns the common fields of this relation.
+    ///
+    /// See [proto::ExtensionSingleRel::common].
+    pub fn common(&self) -> Option<&proto::RelCommon> {
+        self.common.as_ref()
+    }
+
+    /// Returns the input relation.
+    ///
+    /// See [proto::ExtensionSingleRel::input].
+    pub fn input(&self) -> &Rel {
+        &self.input
+    }
+
+    /// Returns the details of the extension.
+    ///
+    /// See [proto::ExtensionSingleRel::detail].
+    pub fn detail(&self) -> &prost_types::Any {
+        &self.detail
+    }
+}
+
+impl Parse<ExtensionAnchors<'_>> for proto::ExtensionSingleRel {
+    type Parsed = ExtensionSingleRel;
+    type Error = RelError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'_>) -> Result<Self::Parsed, Self::Error> {
+        let proto::ExtensionSingleRel {
+            common,
+            input,
+            detail,
+        } = self;
+
+        Ok(ExtensionSingleRel {
+            common,
+            input: parse_input(input, "extension single", ctx)?,
+            detail: required(detail, "extension single", "detail")?,
+        })
+    }
+}
+
+impl From<ExtensionSingleRel> for proto::ExtensionSingleRel {
+    fn from(extension: ExtensionSingleRel) -> Self {
+        let ExtensionSingleRel {
+            common,
+            input,
+            detail,
+        } = extension;
+
+        proto::ExtensionSingleRel {
+            common,
+            input: Some(Box::new((*input).into())),
+            detail: Some(detail),
+        }
+    }
+}
+
+/// A parsed [proto::ExtensionMultiRel].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExtensionMultiRel {
+    /// Common fields of relations.
+    common: Option<proto::RelCommon>,
+
+    /// The input relations.
+    inputs: Vec<Rel>,
+
+    /// The details of the extension.
+    detail: prost_types::Any,
+}
+
+impl ExtensionMultiRel {
+    /// Returns the common fields of this relation.
+    ///
+    /// See [proto::ExtensionMultiRel::common].
+    pub fn common(&self) -> Option<&proto::RelCommon> {
+        self.common.as_ref()
+    }
+
+    /// Returns the input relations.
+    ///
+    /// See [proto::ExtensionMultiRel::inputs].
+    pub fn inputs(&self) -> &[Rel] {
+        &self.inputs
+    }
+
+    /// Returns the details of the extension.
+    ///
+    /// See [proto::ExtensionMultiRel::detail].
+    pub fn detail(&self) -> &prost_types::Any {
+        &self.detail
+    }
+}
+
+impl Parse<ExtensionAnchors<'_>> for proto::ExtensionMultiRel {
+    type Parsed = ExtensionMultiRel;
+    type Error = RelError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'_>) -> Result<Self::Parsed, Self::Error> {
+        let proto::ExtensionMultiRel {
+            common,
+            inputs,
+            detail,
+        } = self;
+
+        Ok(ExtensionMultiRel {
+            common,
+            inputs: inputs
+                .into_iter()
+                .map(|input| ctx.parse(input))
+                .collect::<Result<_, _>>()?,
+            detail: required(detail, "extension multi", "detail")?,
+        })
+    }
+}
+
+impl From<ExtensionMultiRel> for proto::ExtensionMultiRel {
+    fn from(extension: ExtensionMultiRel) -> Self {
+        let ExtensionMultiRel {
+            common,
+            inputs,
+            detail,
+        } = extension;
+
+        proto::ExtensionMultiRel {
+            common,
+            inputs: inputs.into_iter().map(Into::into).collect(),
+            detail: Some(detail),
+        }
+    }
+}
+
+/// A parsed [proto::ExtensionLeafRel].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExtensionLeafRel {
+    /// Common fields of relations.
+    common: Option<proto::RelCommon>,
+
+    /// The details of the extension.
+    detail: prost_types::Any,
+}
+
+impl ExtensionLeafRel {
+    /// Returns the common fields of this relation.
+    ///
+    /// See [proto::ExtensionLeafRel::common].
+    pub fn common(&self) -> Option<&proto::RelCommon> {
+        self.common.as_ref()
+    }
+
+    /// Returns the details of the extension.
+    ///
+    /// See [proto::ExtensionLeafRel::detail].
+    pub fn detail(&self) -> &prost_types::Any {
+        &self.detail
+    }
+}
+
+impl Parse<ExtensionAnchors<'_>> for proto::ExtensionLeafRel {
+    type Parsed = ExtensionLeafRel;
+    type Error = RelError;
+
+    fn parse(self, _ctx: &mut ExtensionAnchors<'_>) -> Result<Self::Parsed, Self::Error> {
+        let proto::ExtensionLeafRel { common, detail } = self;
+
+        Ok(ExtensionLeafRel {
+            common,
+            detail: required(detail, "extension leaf", "detail")?,
+        })
+    }
+}
+
+impl From<ExtensionLeafRel> for proto::ExtensionLeafRel {
+    fn from(extension: ExtensionLeafRel) -> Self {
+        let ExtensionLeafRel { common, detail } = extension;
+
+        proto::ExtensionLeafRel {
+            common,
+            detail: Some(detail),
+        }
+    }
+}
@@ -0,0 +1,133 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing of [proto::FetchRel].
+
+use crate::{
+    parse::{
+        Parse,
+        proto::{
+            ExtensionAnchors,
+            rel::{Rel, RelError, parse_expression, parse_input},
+        },
+    },
+    proto::{
+        self,
+        fetch_rel::{CountMode, OffsetMode},
+    },
+};
+
+/// A parsed [proto::FetchRel].
+#[derive(Clone, Debug, PartialEq)]
+pub struct FetchRel {
+    /// Common fields of relations.
+    common: Option<proto::RelCommon>,
+
+    /// The input relation.
+    input: Box<Rel>,
+
+    /// The number of records to skip, if any.
+    offset_mode: Option<OffsetMode>,
+
+    /// The number of records to return, if limited.
+    count_mode: Option<CountMode>,
+
+    /// Optional advanced extension.
+    advanced_extension: Option<proto::extensions::AdvancedExtension>,
+}
+
+impl FetchRel {
+    /// Returns the common fields of this relation.
+    ///
+    /// See [proto::FetchRel::common].
+    pub fn common(&self) -> Option<&proto::RelCommon> {
+        self.common.as_ref()
+    }
+
+    /// Returns the input relation.
+    ///
+    /// See [proto::FetchRel::input].
+    pub fn input(&self) -> &Rel {
+        &self.input
+    }
+
+    /// Returns the number of records to skip, if any.
+    ///
+    /// See [proto::FetchRel::offset_mode].
+    pub fn offset_mode(&self) -> Option<&OffsetMode> {
+        self.offset_mode.as_ref()
+    }
+
+    /// Returns the number of records to return, if limited.
+    ///
+    /// See [proto::FetchRel::count_mode].
+    pub fn count_mode(&self) -> Option<&CountMode> {
+        self.count_mode.as_ref()
+    }
+
+    /// Returns the advanced extension of this relation.
+    ///
+    /// See [proto::FetchRel::advanced_extension].
+    pub fn advanced_extension(&self) -> Option<&proto::extensions::AdvancedExtension> {
+        self.advanced_extension.as_ref()
+    }
+}
+
+impl Parse<ExtensionAnchors<'_>> for proto::FetchRel {
+    type Parsed = FetchRel;
+    type Error = RelError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'_>) -> Result<Self::Parsed, Self::Error> {
+        let proto::FetchRel {
+            common,
+            input,
+            advanced_extension,
+            offset_mode,
+            count_mode,
+        } = self;
+
+        let input = parse_input(input, "fetch", ctx)?;
+
+        // The offset and count expressions are parsed, the deprecated
+        // constants are kept as is.
+        let offset_mode = match offset_mode {
+            Some(OffsetMode::OffsetExpr(offset)) => Some(OffsetMode::OffsetExpr(Box::new(
+                parse_expression(*offset, ctx)?,
+            ))),
+            offset_mode => offset_mode,
+        };
+        let count_mode = match count_mode {
+            Some(CountMode::CountExpr(count)) => Some(CountMode::CountExpr(Box::new(
+                parse_expression(*count, ctx)?,
+            ))),
+            count_mode => count_mode,
+        };
+
+        Ok(FetchRel {
+            common,
+            input,
+            offset_mode,
+            count_mode,
+            advanced_extension,
+        })
+    }
+}
+
+impl From<FetchRel> for proto::FetchRel {
+    fn from(fetch: FetchRel) -> Self {
+        let FetchRel {
+            common,
+            input,
+            offset_mode,
+            count_mode,
+            advanced_extension,
+        } = fetch;
+
+        proto::FetchRel {
+            common,
+            input: Some(Box::new((*input).into())),
+            advanced_extension,
+            offset_mode,
+            count_mode,
+        }
+    }
+}
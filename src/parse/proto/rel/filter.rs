@@ -0,0 +1,99 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing of [proto::FilterRel].
+
+use crate::{
+    parse::{
+        Parse,
+        proto::{
+            ExtensionAnchors,
+            rel::{Rel, RelError, parse_expression, parse_input, required},
+        },
+    },
+    proto,
+};
+
+/// A parsed [proto::FilterRel].
+#[derive(Clone, Debug, PartialEq)]
+pub struct FilterRel {
+    /// Common fields of relations.
+    common: Option<proto::RelCommon>,
+
+    /// The input relation.
+    input: Box<Rel>,
+
+    /// The condition records must satisfy.
+    condition: proto::Expression,
+
+    /// Optional advanced extension.
+    advanced_extension: Option<proto::extensions::AdvancedExtension>,
+}
+
+impl FilterRel {
+    /// Returns the common fields of this relation.
+    ///
+    /// See [proto::FilterRel::common].
+    pub fn common(&self) -> Option<&proto::RelCommon> {
+        self.common.as_ref()
+    }
+
+    /// Returns the input relation.
+    ///
+    /// See [proto::FilterRel::input].
+    pub fn input(&self) -> &Rel {
+        &self.input
+    }
+
+    /// Returns the filter condition.
+    ///
+    /// See [proto::FilterRel::condition].
+    pub fn condition(&self) -> &proto::Expression {
+        &self.condition
+    }
+
+    /// Returns the advanced extension of this relation.
+    ///
+    /// See [proto::FilterRel::advanced_extension].
+    pub fn advanced_extension(&self) -> Option<&proto::extensions::AdvancedExtension> {
+        self.advanced_extension.as_ref()
+    }
+}
+
+impl Parse<ExtensionAnchors<'_>> for proto::FilterRel {
+    type Parsed = FilterRel;
+    type Error = RelError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'_>) -> Result<Self::Parsed, Self::Error> {
+        let proto::FilterRel {
+            common,
+            input,
+            condition,
+            advanced_extension,
+        } = self;
+
+        Ok(FilterRel {
+            common,
+            input: parse_input(input, "filter", ctx)?,
+            condition: parse_expression(*required(condition, "filter", "condition")?, ctx)?,
+            advanced_extension,
+        })
+    }
+}
+
+impl From<FilterRel> for proto::FilterRel {
+    fn from(filter: FilterRel) -> Self {
+        let FilterRel {
+            common,
+            input,
+            condition,
+            advanced_extension,
+        } = filter;
+
+        proto::FilterRel {
+            common,
+            input: Some(Box::new((*input).into())),
+            condition: Some(Box::new(condition)),
+            advanced_extension,
+        }
+    }
+}
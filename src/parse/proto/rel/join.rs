@@ -0,0 +1,276 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing of [proto::JoinRel] and [proto::CrossRel].
+
+use crate::{
+    parse::{
+        Parse,
+        proto::{
+            ExtensionAnchors,
+            rel::{Rel, RelError, parse_expression, parse_input, required},
+        },
+    },
+    proto::{self, join_rel::JoinType},
+};
+
+/// A parsed [proto::JoinRel].
+#[derive(Clone, Debug, PartialEq)]
+pub struct JoinRel {
+    /// Common fields of relations.
+    common: Option<proto::RelCommon>,
+
+    /// The left input relation.
+    left: Box<Rel>,
+
+    /// The right input relation.
+    right: Box<Rel>,
+
+    /// The join condition.
+    expression: proto::Expression,
+
+    /// An optional filter applied after the join.
+    post_join_filter: Option<proto::Expression>,
+
+    /// The type of join.
+    join_type: JoinType,
+
+    /// Optional advanced extension.
+    advanced_extension: Option<proto::extensions::AdvancedExtension>,
+}
+
+impl JoinRel {
+    /// Returns the common fields of this relation.
+    ///
+    /// See [proto::JoinRel::common].
+    pub fn common(&self) -> Option<&proto::RelCommon> {
+        self.common.as_ref()
+    }
+
+    /// Returns the left input relation.
+    ///
+    /// See [proto::JoinRel::left].
+    pub fn left(&self) -> &Rel {
+        &self.left
+    }
+
+    /// Returns the right input relation.
+    ///
+    /// See [proto::JoinRel::right].
+    pub fn right(&self) -> &Rel {
+        &self.right
+    }
+
+    /// Returns the join condition.
+    ///
+    /// See [proto::JoinRel::expression].
+    pub fn expression(&self) -> &proto::Expression {
+        &self.expression
+    }
+
+    /// Returns the filter applied after the join, if any.
+    ///
+    /// See [proto::JoinRel::post_join_filter].
+    pub fn post_join_filter(&self) -> Option<&proto::Expression> {
+        self.post_join_filter.as_ref()
+    }
+
+    /// Returns the type of join.
+    ///
+    /// See [proto::JoinRel::type].
+    pub fn join_type(&self) -> JoinType {
+        self.join_type
+    }
+
+    /// Returns the advanced extension of this relation.
+    ///
+    /// See [proto::JoinRel::advanced_extension].
+    pub fn advanced_extension(&self) -> Option<&proto::extensions::AdvancedExtension> {
+        self.advanced_extension.as_ref()
+    }
+}
+
+impl Parse<ExtensionAnchors<'_>> for proto::JoinRel {
+    type Parsed = JoinRel;
+    type Error = RelError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'_>) -> Result<Self::Parsed, Self::Error> {
+        let proto::JoinRel {
+            common,
+            left,
+            right,
+            expression,
+            post_join_filter,
+            r#type,
+            advanced_extension,
+        } = self;
+
+        // The join type must be specified.
+        let join_type = match JoinType::try_from(r#type) {
+            Ok(JoinType::Unspecified) | Err(_) => return Err(RelError::JoinType(r#type)),
+            Ok(join_type) => join_type,
+        };
+
+        Ok(JoinRel {
+            common,
+            left: parse_input(left, "join", ctx)?,
+            right: parse_input(right, "join", ctx)?,
+            expression: parse_expression(*required(expression, "join", "expression")?, ctx)?,
+            post_join_filter: post_join_filter
+                .map(|filter| parse_expression(*filter, ctx))
+                .transpose()?,
+            join_type,
+            advanced_extension,
+        })
+    }
+}
+
+impl From<JoinRel> for proto::JoinRel {
+    fn from(join: JoinRel) -> Self {
+        let JoinRel {
+            common,
+            left,
+            right,
+            expression,
+            post_join_filter,
+            join_type,
+            advanced_extension,
+        } = join;
+
+        proto::JoinRel {
+            common,
+            left: Some(Box::new((*left).into())),
+            right: Some(Box::new((*right).into())),
+            expression: Some(Box::new(expression)),
+            post_join_filter: post_join_filter.map(Box::new),
+            r#type: join_type.into(),
+            advanced_extension,
+        }
+    }
+}
+
+/// A parsed [proto::CrossRel].
+#[derive(Clone, Debug, PartialEq)]
+pub struct CrossRel {
+    /// Common fields of relations.
+    common: Option<proto::RelCommon>,
+
+    /// The left input relation.
+    left: Box<Rel>,
+
+    /// The right input relation.
+    right: Box<Rel>,
+
+    /// Optional advanced extension.
+    advanced_extension: Option<proto::extensions::AdvancedExtension>,
+}
+
+impl CrossRel {
+    /// Returns the common fields of this relation.
+    ///
+    /// See [proto::CrossRel::common].
+    pub fn common(&self) -> Option<&proto::RelCommon> {
+        self.common.as_ref()
+    }
+
+    /// Returns the left input relation.
+    ///
+    /// See [proto::CrossRel::left].
+    pub fn left(&self) -> &Rel {
+        &self.left
+    }
+
+    /// Returns the right input relation.
+    ///
+    /// See [proto::CrossRel::right].
+    pub fn right(&self) -> &Rel {
+        &self.right
+    }
+
+    /// Returns the advanced extension of this relation.
+    ///
+    /// See [proto::CrossRel::advanced_extension].
+    pub fn advanced_extension(&self) -> Option<&proto::extensions::AdvancedExtension> {
+        self.advanced_extension.as_ref()
+    }
+}
+
+impl Parse<ExtensionAnchors<'_>> for proto::CrossRel {
+    type Parsed = CrossRel;
+    type Error = RelError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'_>) -> Result<Self::Parsed, Self::Error> {
+        let proto::CrossRel {
+            common,
+            left,
+            right,
+            advanced_extension,
+        } = self;
+
+        Ok(CrossRel {
+            common,
+            left: parse_input(left, "cross", ctx)?,
+            right: parse_input(right, "cross", ctx)?,
+            advanced_extension,
+        })
+    }
+}
+
+impl From<CrossRel> for proto::CrossRel {
+    fn from(cross: CrossRel) -> Self {
+        let CrossRel {
+            common,
+            left,
+            right,
+            advanced_extension,
+        } = cross;
+
+        proto::CrossRel {
+            common,
+            left: Some(Box::new((*left).into())),
+            right: Some(Box::new((*right).into())),
+            advanced_extension,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{
+        Context as _,
+        proto::rel::tests::{field, read_rel},
+    };
+
+    #[test]
+    fn join_type() {
+        let mut ctx = ExtensionAnchors::default();
+        let join = proto::JoinRel {
+            left: Some(Box::new(read_rel())),
+            right: Some(Box::new(read_rel())),
+            expression: Some(Box::new(field(0))),
+            r#type: JoinType::Left.into(),
+            ..Default::default()
+        };
+        let parsed = ctx.parse(join.clone()).unwrap();
+        assert_eq!(parsed.join_type(), JoinType::Left);
+        assert_eq!(proto::JoinRel::from(parsed), join);
+
+        let unspecified = proto::JoinRel {
+            r#type: JoinType::Unspecified.into(),
+            ..join.clone()
+        };
+        assert_eq!(ctx.parse(unspecified), Err(RelError::JoinType(0)));
+
+        let missing = proto::JoinRel {
+            expression: None,
+            ..join
+        };
+        assert_eq!(
+            ctx.parse(missing),
+            Err(RelError::MissingField {
+                rel: "join",
+                field: "expression"
+            })
+        );
+    }
+}
@@ -0,0 +1,411 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing of [proto::Rel].
+//!
+//! Relations are parsed into a validated relation tree: required inputs and
+//! oneofs are checked while parsing, so the parsed types hold them as plain
+//! fields. Expressions are only validated: they are parsed (binding and
+//! checking their function calls, see
+//! [Expression](crate::parse::proto::expression::Expression)) and converted
+//! back, so the parsed relations hold [proto::Expression]s. Relations of the
+//! other types (e.g. write and physical relations) are kept as they are, see
+//! [Rel::Other].
+
+use thiserror::Error;
+
+use crate::{
+    parse::{
+        Context, Parse,
+        context::ContextError,
+        proto::{ExtensionAnchors, expression::ExpressionError},
+    },
+    proto,
+};
+
+mod aggregate;
+pub use aggregate::{AggregateRel, Measure};
+
+mod extension;
+pub use extension::{ExtensionLeafRel, ExtensionMultiRel, ExtensionSingleRel};
+
+mod fetch;
+pub use fetch::FetchRel;
+
+mod filter;
+pub use filter::FilterRel;
+
+mod join;
+pub use join::{CrossRel, JoinRel};
+
+mod project;
+pub use project::ProjectRel;
+
+mod read;
+pub use read::ReadRel;
+
+mod schema;
+pub(crate) use schema::output_schema_with_outer;
+pub use schema::{SchemaError, output_schema};
+
+mod set;
+pub use set::SetRel;
+
+mod sort;
+pub use sort::{SortField, SortKind, SortRel};
+
+/// A parsed [proto::Rel].
+///
+/// Every parsed relation is boxed, keeping the size of the enum small.
+/// References and relations kept as is hold their protobuf message.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Rel {
+    /// See [proto::rel::RelType::Read].
+    Read(Box<ReadRel>),
+
+    /// See [proto::rel::RelType::Filter].
+    Filter(Box<FilterRel>),
+
+    /// See [proto::rel::RelType::Fetch].
+    Fetch(Box<FetchRel>),
+
+    /// See [proto::rel::RelType::Aggregate].
+    Aggregate(Box<AggregateRel>),
+
+    /// See [proto::rel::RelType::Sort].
+    Sort(Box<SortRel>),
+
+    /// See [proto::rel::RelType::Join].
+    Join(Box<JoinRel>),
+
+    /// See [proto::rel::RelType::Project].
+    Project(Box<ProjectRel>),
+
+    /// See [proto::rel::RelType::Set].
+    Set(Box<SetRel>),
+
+    /// See [proto::rel::RelType::ExtensionSingle].
+    ExtensionSingle(Box<ExtensionSingleRel>),
+
+    /// See [proto::rel::RelType::ExtensionMulti].
+    ExtensionMulti(Box<ExtensionMultiRel>),
+
+    /// See [proto::rel::RelType::ExtensionLeaf].
+    ExtensionLeaf(Box<ExtensionLeafRel>),
+
+    /// See [proto::rel::RelType::Cross].
+    Cross(Box<CrossRel>),
+
+    /// A reference to a relation of the plan, by ordinal.
+    ///
+    /// See [proto::rel::RelType::Reference].
+    Reference(proto::ReferenceRel),
+
+    /// A relation of a type that is not parsed (e.g. a write or physical
+    /// relation), kept as is. Its inputs and expressions are not validated.
+    Other(proto::Rel),
+}
+
+/// Parse errors for [proto::Rel].
+#[derive(Debug, Error, PartialEq)]
+pub enum RelError {
+    /// The relation type is missing.
+    #[error("relation type must be specified")]
+    Missing,
+
+    /// A required input of a relation is missing.
+    #[error("{0} relation input must be specified")]
+    MissingInput(&'static str),
+
+    /// A required field of a relation is missing.
+    #[error("{rel} relation {field} must be specified")]
+    MissingField {
+        /// The relation with the missing field.
+        rel: &'static str,
+        /// The missing field.
+        field: &'static str,
+    },
+
+    /// A set relation has fewer than two inputs.
+    #[error("set relation requires at least two inputs, got {0}")]
+    SetInputs(usize),
+
+    /// The join type is unspecified or unknown.
+    #[error("join type must be specified, got `{0}`")]
+    JoinType(i32),
+
+    /// The set operation is unspecified or unknown.
+    #[error("set operation must be specified, got `{0}`")]
+    SetOp(i32),
+
+    /// The sort direction is unspecified or unknown.
+    #[error("sort direction must be specified, got `{0}`")]
+    SortDirection(i32),
+
+    /// A grouping references a grouping expression that does not exist.
+    #[error(
+        "grouping expression reference `{reference}` is out of bounds for {count} grouping expressions"
+    )]
+    GroupingReference {
+        /// The reference.
+        reference: u32,
+        /// The number of grouping expressions.
+        count: usize,
+    },
+
+    /// An expression of a relation is invalid.
+    #[error(transparent)]
+    Expression(Box<ExpressionError>),
+
+    /// Context error
+    #[error(transparent)]
+    Context(#[from] ContextError),
+}
+
+impl From<ExpressionError> for RelError {
+    fn from(err: ExpressionError) -> Self {
+        RelError::Expression(Box::new(err))
+    }
+}
+
+impl Parse<ExtensionAnchors<'_>> for proto::Rel {
+    type Parsed = Rel;
+    type Error = RelError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'_>) -> Result<Self::Parsed, Self::Error> {
+        use proto::rel::RelType;
+
+        let proto::Rel { rel_type } = self;
+
+        // The relation type is a required oneof.
+        Ok(match rel_type.ok_or(RelError::Missing)? {
+            RelType::Read(read) => Rel::Read(Box::new(ctx.parse(*read)?)),
+            RelType::Filter(filter) => Rel::Filter(Box::new(ctx.parse(*filter)?)),
+            RelType::Fetch(fetch) => Rel::Fetch(Box::new(ctx.parse(*fetch)?)),
+            RelType::Aggregate(aggregate) => Rel::Aggregate(Box::new(ctx.parse(*aggregate)?)),
+            RelType::Sort(sort) => Rel::Sort(Box::new(ctx.parse(*sort)?)),
+            RelType::Join(join) => Rel::Join(Box::new(ctx.parse(*join)?)),
+            RelType::Project(project) => Rel::Project(Box::new(ctx.parse(*project)?)),
+            RelType::Set(set) => Rel::Set(Box::new(ctx.parse(set)?)),
+            RelType::ExtensionSingle(extension) => {
+                Rel::ExtensionSingle(Box::new(ctx.parse(*extension)?))
+            }
+            RelType::ExtensionMulti(extension) => {
+                Rel::ExtensionMulti(Box::new(ctx.parse(extension)?))
+            }
+            RelType::ExtensionLeaf(extension) => {
+                Rel::ExtensionLeaf(Box::new(ctx.parse(extension)?))
+            }
+            RelType::Cross(cross) => Rel::Cross(Box::new(ctx.parse(*cross)?)),
+            RelType::Reference(reference) => Rel::Reference(reference),
+            rel_type => Rel::Other(proto::Rel {
+                rel_type: Some(rel_type),
+            }),
+        })
+    }
+}
+
+impl From<Rel> for proto::Rel {
+    fn from(rel: Rel) -> Self {
+        use proto::rel::RelType;
+
+        let rel_type = match rel {
+            Rel::Read(read) => RelType::Read(Box::new((*read).into())),
+            Rel::Filter(filter) => RelType::Filter(Box::new((*filter).into())),
+            Rel::Fetch(fetch) => RelType::Fetch(Box::new((*fetch).into())),
+            Rel::Aggregate(aggregate) => RelType::Aggregate(Box::new((*aggregate).into())),
+            Rel::Sort(sort) => RelType::Sort(Box::new((*sort).into())),
+            Rel::Join(join) => RelType::Join(Box::new((*join).into())),
+            Rel::Project(project) => RelType::Project(Box::new((*project).into())),
+            Rel::Set(set) => RelType::Set((*set).into()),
+            Rel::ExtensionSingle(extension) => {
+                RelType::ExtensionSingle(Box::new((*extension).into()))
+            }
+            Rel::ExtensionMulti(extension) => RelType::ExtensionMulti((*extension).into()),
+            Rel::ExtensionLeaf(extension) => RelType::ExtensionLeaf((*extension).into()),
+            Rel::Cross(cross) => RelType::Cross(Box::new((*cross).into())),
+            Rel::Reference(reference) => RelType::Reference(reference),
+            Rel::Other(rel) => return rel,
+        };
+        proto::Rel {
+            rel_type: Some(rel_type),
+        }
+    }
+}
+
+/// Parse the required input of a relation.
+fn parse_input(
+    input: Option<Box<proto::Rel>>,
+    rel: &'static str,
+    ctx: &mut ExtensionAnchors<'_>,
+) -> Result<Box<Rel>, RelError> {
+    let input = input.ok_or(RelError::MissingInput(rel))?;
+    Ok(Box::new(ctx.parse(*input)?))
+}
+
+/// Validate an expression of a relation, keeping it as a [proto::Expression].
+///
+/// The parsed expression is converted back, which drops the implementations
+/// its function calls are bound to.
+fn parse_expression(
+    expression: proto::Expression,
+    ctx: &mut ExtensionAnchors<'_>,
+) -> Result<proto::Expression, RelError> {
+    Ok(ctx.parse(expression)?.into())
+}
+
+/// Returns a required field of a relation.
+fn required<T>(field: Option<T>, rel: &'static str, name: &'static str) -> Result<T, RelError> {
+    field.ok_or(RelError::MissingField { rel, field: name })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::parse::{
+        Anchor,
+        proto::expression::tests::{call, i32_type},
+    };
+
+    /// A read of the named table `t`, with a single `i32` column `a`.
+    pub(crate) fn read_rel() -> proto::Rel {
+        proto::Rel {
+            rel_type: Some(proto::rel::RelType::Read(Box::new(proto::ReadRel {
+                base_schema: Some(proto::NamedStruct {
+                    names: vec!["a".to_string()],
+                    r#struct: Some(proto::r#type::Struct {
+                        types: vec![proto::Type {
+                            kind: Some(proto::r#type::Kind::I32(proto::r#type::I32 {
+                                type_variation_reference: 0,
+                                nullability: proto::r#type::Nullability::Required as i32,
+                            })),
+                        }],
+                        type_variation_reference: 0,
+                        nullability: proto::r#type::Nullability::Required as i32,
+                    }),
+                }),
+                read_type: Some(proto::read_rel::ReadType::NamedTable(
+                    proto::read_rel::NamedTable {
+                        names: vec!["t".to_string()],
+                        ..Default::default()
+                    },
+                )),
+                ..Default::default()
+            }))),
+        }
+    }
+
+    /// A field reference to the field of the input at the given index.
+    pub(crate) fn field(index: i32) -> proto::Expression {
+        use proto::expression::{
+            FieldReference, ReferenceSegment, field_reference, reference_segment,
+        };
+
+        proto::Expression {
+            rex_type: Some(proto::expression::RexType::Selection(Box::new(
+                FieldReference {
+                    reference_type: Some(field_reference::ReferenceType::DirectReference(
+                        ReferenceSegment {
+                            reference_type: Some(reference_segment::ReferenceType::StructField(
+                                Box::new(reference_segment::StructField {
+                                    field: index,
+                                    child: None,
+                                }),
+                            )),
+                        },
+                    )),
+                    root_type: Some(field_reference::RootType::RootReference(
+                        field_reference::RootReference {},
+                    )),
+                },
+            ))),
+        }
+    }
+
+    #[test]
+    fn parse() -> Result<(), RelError> {
+        let mut ctx = ExtensionAnchors::default();
+
+        let filter = proto::Rel {
+            rel_type: Some(proto::rel::RelType::Filter(Box::new(proto::FilterRel {
+                input: Some(Box::new(read_rel())),
+                condition: Some(Box::new(field(0))),
+                ..Default::default()
+            }))),
+        };
+        let Rel::Filter(parsed) = ctx.parse(filter.clone())? else {
+            panic!("expected a filter relation");
+        };
+        assert!(matches!(parsed.input(), Rel::Read(_)));
+        assert_eq!(parsed.condition(), &field(0));
+        assert_eq!(proto::Rel::from(Rel::Filter(parsed)), filter);
+        Ok(())
+    }
+
+    #[test]
+    fn missing() {
+        let mut ctx = ExtensionAnchors::default();
+        assert_eq!(ctx.parse(proto::Rel::default()), Err(RelError::Missing));
+
+        // Errors of inputs are reported
+        let filter = proto::Rel {
+            rel_type: Some(proto::rel::RelType::Filter(Box::new(proto::FilterRel {
+                input: Some(Box::new(proto::Rel::default())),
+                condition: Some(Box::new(field(0))),
+                ..Default::default()
+            }))),
+        };
+        assert_eq!(ctx.parse(filter), Err(RelError::Missing));
+    }
+
+    #[test]
+    fn expressions() {
+        let mut ctx = ExtensionAnchors::default();
+
+        // Expressions are parsed, so calls of undeclared functions are
+        // reported
+        let filter = proto::Rel {
+            rel_type: Some(proto::rel::RelType::Filter(Box::new(proto::FilterRel {
+                input: Some(Box::new(read_rel())),
+                condition: Some(Box::new(call(1, vec![], i32_type()))),
+                ..Default::default()
+            }))),
+        };
+        assert_eq!(
+            ctx.parse(filter),
+            Err(RelError::Expression(Box::new(ExpressionError::Context(
+                ContextError::UndefinedExtensionFunction(Anchor::new(1))
+            ))))
+        );
+    }
+
+    #[test]
+    fn other() -> Result<(), RelError> {
+        let mut ctx = ExtensionAnchors::default();
+
+        // Relations of other types are kept as they are, also as inputs.
+        let hash_join = proto::Rel {
+            rel_type: Some(proto::rel::RelType::HashJoin(Box::new(
+                proto::HashJoinRel {
+                    left: Some(Box::new(read_rel())),
+                    right: Some(Box::new(read_rel())),
+                    ..Default::default()
+                },
+            ))),
+        };
+        assert_eq!(ctx.parse(hash_join.clone())?, Rel::Other(hash_join.clone()));
+
+        let filter = proto::Rel {
+            rel_type: Some(proto::rel::RelType::Filter(Box::new(proto::FilterRel {
+                input: Some(Box::new(hash_join.clone())),
+                condition: Some(Box::new(field(0))),
+                ..Default::default()
+            }))),
+        };
+        let Rel::Filter(parsed) = ctx.parse(filter.clone())? else {
+            panic!("expected a filter relation");
+        };
+        assert_eq!(parsed.input(), &Rel::Other(hash_join));
+        assert_eq!(proto::Rel::from(Rel::Filter(parsed)), filter);
+        Ok(())
+    }
+}
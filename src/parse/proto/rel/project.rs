@@ -0,0 +1,102 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing of [proto::ProjectRel].
+
+use crate::{
+    parse::{
+        Parse,
+        proto::{
+            ExtensionAnchors,
+            rel::{Rel, RelError, parse_expression, parse_input},
+        },
+    },
+    proto,
+};
+
+/// A parsed [proto::ProjectRel].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProjectRel {
+    /// Common fields of relations.
+    common: Option<proto::RelCommon>,
+
+    /// The input relation.
+    input: Box<Rel>,
+
+    /// The expressions appended to the fields of the input.
+    expressions: Vec<proto::Expression>,
+
+    /// Optional advanced extension.
+    advanced_extension: Option<proto::extensions::AdvancedExtension>,
+}
+
+impl ProjectRel {
+    /// Returns the common fields of this relation.
+    ///
+    /// See [proto::ProjectRel::common].
+    pub fn common(&self) -> Option<&proto::RelCommon> {
+        self.common.as_ref()
+    }
+
+    /// Returns the input relation.
+    ///
+    /// See [proto::ProjectRel::input].
+    pub fn input(&self) -> &Rel {
+        &self.input
+    }
+
+    /// Returns the projected expressions.
+    ///
+    /// See [proto::ProjectRel::expressions].
+    pub fn expressions(&self) -> &[proto::Expression] {
+        &self.expressions
+    }
+
+    /// Returns the advanced extension of this relation.
+    ///
+    /// See [proto::ProjectRel::advanced_extension].
+    pub fn advanced_extension(&self) -> Option<&proto::extensions::AdvancedExtension> {
+        self.advanced_extension.as_ref()
+    }
+}
+
+impl Parse<ExtensionAnchors<'_>> for proto::ProjectRel {
+    type Parsed = ProjectRel;
+    type Error = RelError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'_>) -> Result<Self::Parsed, Self::Error> {
+        let proto::ProjectRel {
+            common,
+            input,
+            expressions,
+            advanced_extension,
+        } = self;
+
+        Ok(ProjectRel {
+            common,
+            input: parse_input(input, "project", ctx)?,
+            expressions: expressions
+                .into_iter()
+                .map(|expression| parse_expression(expression, ctx))
+                .collect::<Result<_, _>>()?,
+            advanced_extension,
+        })
+    }
+}
+
+impl From<ProjectRel> for proto::ProjectRel {
+    fn from(project: ProjectRel) -> Self {
+        let ProjectRel {
+            common,
+            input,
+            expressions,
+            advanced_extension,
+        } = project;
+
+        proto::ProjectRel {
+            common,
+            input: Some(Box::new((*input).into())),
+            expressions,
+            advanced_extension,
+        }
+    }
+}
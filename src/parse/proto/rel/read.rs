@@ -0,0 +1,181 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing of [proto::ReadRel].
+
+use crate::{
+    parse::{
+        Parse,
+        proto::{
+            ExtensionAnchors,
+            rel::{RelError, parse_expression, required},
+        },
+    },
+    proto::{self, read_rel::ReadType},
+};
+
+/// A parsed [proto::ReadRel].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReadRel {
+    /// Common fields of relations.
+    common: Option<proto::RelCommon>,
+
+    /// The schema of the data read.
+    base_schema: proto::NamedStruct,
+
+    /// An optional filter that records must satisfy.
+    filter: Option<proto::Expression>,
+
+    /// An optional filter that may be applied on a best effort basis.
+    best_effort_filter: Option<proto::Expression>,
+
+    /// An optional projection of the fields of the base schema.
+    projection: Option<proto::expression::MaskExpression>,
+
+    /// The source of the data.
+    read_type: ReadType,
+
+    /// Optional advanced extension.
+    advanced_extension: Option<proto::extensions::AdvancedExtension>,
+}
+
+impl ReadRel {
+    /// Returns the common fields of this relation.
+    ///
+    /// See [proto::ReadRel::common].
+    pub fn common(&self) -> Option<&proto::RelCommon> {
+        self.common.as_ref()
+    }
+
+    /// Returns the schema of the data read.
+    ///
+    /// See [proto::ReadRel::base_schema].
+    pub fn base_schema(&self) -> &proto::NamedStruct {
+        &self.base_schema
+    }
+
+    /// Returns the filter records must satisfy, if any.
+    ///
+    /// See [proto::ReadRel::filter].
+    pub fn filter(&self) -> Option<&proto::Expression> {
+        self.filter.as_ref()
+    }
+
+    /// Returns the best effort filter, if any.
+    ///
+    /// See [proto::ReadRel::best_effort_filter].
+    pub fn best_effort_filter(&self) -> Option<&proto::Expression> {
+        self.best_effort_filter.as_ref()
+    }
+
+    /// Returns the projection of the base schema, if any.
+    ///
+    /// See [proto::ReadRel::projection].
+    pub fn projection(&self) -> Option<&proto::expression::MaskExpression> {
+        self.projection.as_ref()
+    }
+
+    /// Returns the source of the data.
+    ///
+    /// See [proto::ReadRel::read_type].
+    pub fn read_type(&self) -> &ReadType {
+        &self.read_type
+    }
+
+    /// Returns the advanced extension of this relation.
+    ///
+    /// See [proto::ReadRel::advanced_extension].
+    pub fn advanced_extension(&self) -> Option<&proto::extensions::AdvancedExtension> {
+        self.advanced_extension.as_ref()
+    }
+}
+
+impl Parse<ExtensionAnchors<'_>> for proto::ReadRel {
+    type Parsed = ReadRel;
+    type Error = RelError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'_>) -> Result<Self::Parsed, Self::Error> {
+        let proto::ReadRel {
+            common,
+            base_schema,
+            filter,
+            best_effort_filter,
+            projection,
+            advanced_extension,
+            read_type,
+        } = self;
+
+        Ok(ReadRel {
+            common,
+            base_schema: required(base_schema, "read", "base schema")?,
+            filter: filter
+                .map(|filter| parse_expression(*filter, ctx))
+                .transpose()?,
+            best_effort_filter: best_effort_filter
+                .map(|filter| parse_expression(*filter, ctx))
+                .transpose()?,
+            projection,
+            read_type: required(read_type, "read", "read type")?,
+            advanced_extension,
+        })
+    }
+}
+
+impl From<ReadRel> for proto::ReadRel {
+    fn from(read: ReadRel) -> Self {
+        let ReadRel {
+            common,
+            base_schema,
+            filter,
+            best_effort_filter,
+            projection,
+            read_type,
+            advanced_extension,
+        } = read;
+
+        proto::ReadRel {
+            common,
+            base_schema: Some(base_schema),
+            filter: filter.map(Box::new),
+            best_effort_filter: best_effort_filter.map(Box::new),
+            projection,
+            advanced_extension,
+            read_type: Some(read_type),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{Context as _, proto::rel::tests::read_rel};
+
+    #[test]
+    fn missing() {
+        let mut ctx = ExtensionAnchors::default();
+        let Some(proto::rel::RelType::Read(read)) = read_rel().rel_type else {
+            unreachable!()
+        };
+        assert!(ctx.parse((*read).clone()).is_ok());
+
+        assert_eq!(
+            ctx.parse(proto::ReadRel {
+                base_schema: None,
+                ..(*read).clone()
+            }),
+            Err(RelError::MissingField {
+                rel: "read",
+                field: "base schema"
+            })
+        );
+        assert_eq!(
+            ctx.parse(proto::ReadRel {
+                read_type: None,
+                ..*read
+            }),
+            Err(RelError::MissingField {
+                rel: "read",
+                field: "read type"
+            })
+        );
+    }
+}
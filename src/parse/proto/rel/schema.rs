@@ -0,0 +1,974 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Output schemas of relations.
+//!
+//! The output schema of a relation is the struct type of its records. It is
+//! derived from the schemas of its inputs, the types of its expressions (see
+//! [InferTypeError]) and its [proto::RelCommon] emit.
+
+use thiserror::Error;
+
+use crate::{
+    parse::{
+        proto::{
+            ExtensionAnchors, TypeError,
+            expression::{InferTypeError, expression_type},
+            rel::{Rel, RelError},
+            types::concrete_type,
+        },
+        text::simple_extensions::{BasicBuiltinType, ConcreteType, ConcreteTypeKind},
+    },
+    proto::{
+        self,
+        expand_rel::{ExpandField, expand_field::FieldType},
+        expression::mask_expression,
+        join_rel::JoinType,
+        rel::RelType,
+        rel_common::EmitKind,
+        set_rel::SetOp,
+    },
+};
+
+/// Errors deriving the output schema of a relation.
+#[derive(Debug, Error, PartialEq)]
+pub enum SchemaError {
+    /// The output schema of the relation can not be derived.
+    #[error("cannot derive the output schema of {0}")]
+    Unsupported(&'static str),
+
+    /// An emit output mapping refers to a field that does not exist.
+    #[error("emit output mapping `{index}` is out of bounds for {count} fields")]
+    EmitOutOfRange {
+        /// The output mapping.
+        index: i32,
+        /// The number of fields of the relation.
+        count: usize,
+    },
+
+    /// A read projection selects a field that does not exist.
+    #[error("read projection field `{field}` is out of bounds for {count} fields")]
+    ProjectionOutOfRange {
+        /// The selected field.
+        field: i32,
+        /// The number of fields of the base schema.
+        count: usize,
+    },
+
+    /// An input of a set relation has a different number of fields than the
+    /// first input.
+    #[error("set relation input {index} has {actual} fields, expected {expected}")]
+    SetFieldCount {
+        /// The index of the input.
+        index: usize,
+        /// The number of fields of the first input.
+        expected: usize,
+        /// The number of fields of the input.
+        actual: usize,
+    },
+
+    /// A field of an input of a set relation has a different type than the
+    /// field of the first input, other than its nullability.
+    #[error("set relation input {index} field {field} has type `{actual}`, expected `{expected}`")]
+    SetFieldType {
+        /// The index of the input.
+        index: usize,
+        /// The index of the field.
+        field: usize,
+        /// The type of the field of the first input.
+        expected: ConcreteType,
+        /// The type of the field of the input.
+        actual: ConcreteType,
+    },
+
+    /// The relation is invalid.
+    #[error(transparent)]
+    Rel(#[from] RelError),
+
+    /// The type of an expression can not be inferred.
+    #[error(transparent)]
+    Expression(#[from] InferTypeError),
+
+    /// Type error
+    #[error(transparent)]
+    Type(#[from] TypeError),
+}
+
+/// Returns the type of a logical join, which must be specified.
+fn logical_join_type(r#type: i32) -> Result<JoinType, RelError> {
+    match JoinType::try_from(r#type) {
+        Ok(JoinType::Unspecified) | Err(_) => Err(RelError::JoinType(r#type)),
+        Ok(join_type) => Ok(join_type),
+    }
+}
+
+/// Returns the logical join type of a physical join, whose join type is
+/// declared in the given module, as a `Result<JoinType, RelError>`.
+macro_rules! physical_join_type {
+    ($module:ident, $type:expr) => {{
+        use proto::$module::JoinType as Physical;
+        match Physical::try_from($type) {
+            Ok(Physical::Inner) => Ok(JoinType::Inner),
+            Ok(Physical::Outer) => Ok(JoinType::Outer),
+            Ok(Physical::Left) => Ok(JoinType::Left),
+            Ok(Physical::Right) => Ok(JoinType::Right),
+            Ok(Physical::LeftSemi) => Ok(JoinType::LeftSemi),
+            Ok(Physical::RightSemi) => Ok(JoinType::RightSemi),
+            Ok(Physical::LeftAnti) => Ok(JoinType::LeftAnti),
+            Ok(Physical::RightAnti) => Ok(JoinType::RightAnti),
+            Ok(Physical::LeftSingle) => Ok(JoinType::LeftSingle),
+            Ok(Physical::RightSingle) => Ok(JoinType::RightSingle),
+            Ok(Physical::LeftMark) => Ok(JoinType::LeftMark),
+            Ok(Physical::RightMark) => Ok(JoinType::RightMark),
+            Ok(Physical::Unspecified) | Err(_) => Err(RelError::JoinType($type)),
+        }
+    }};
+}
+
+impl Rel {
+    /// Returns the output schema of this relation, see [output_schema].
+    pub fn output_schema(
+        &self,
+        anchors: &ExtensionAnchors<'_>,
+    ) -> Result<ConcreteType, SchemaError> {
+        output_schema(&self.clone().into(), anchors)
+    }
+}
+
+/// Returns the output schema of a relation, as a (non-nullable) struct type.
+///
+/// The types of expressions are inferred with the given anchors, and scalar
+/// functions are typed by their implementation when the anchors have a
+/// [Registry](crate::parse::text::simple_extensions::Registry). Extension,
+/// write, DDL and update relations, and references to other relations of a
+/// plan, are not supported.
+pub fn output_schema(
+    rel: &proto::Rel,
+    anchors: &ExtensionAnchors<'_>,
+) -> Result<ConcreteType, SchemaError> {
+    output_schema_with_outer(rel, &[], anchors)
+}
+
+/// Returns the output schema of a relation of a subquery, see
+/// [output_schema]. The outer schemas are the input schemas of the enclosing
+/// queries, innermost first, for outer references.
+pub(crate) fn output_schema_with_outer(
+    rel: &proto::Rel,
+    outer: &[ConcreteType],
+    anchors: &ExtensionAnchors<'_>,
+) -> Result<ConcreteType, SchemaError> {
+    let rel_type = rel.rel_type.as_ref().ok_or(RelError::Missing)?;
+    let (common, fields) = match rel_type {
+        RelType::Read(read) => (&read.common, read_schema(read, anchors)?),
+        RelType::Filter(filter) => (
+            &filter.common,
+            input_schema(&filter.input, "filter", outer, anchors)?,
+        ),
+        RelType::Fetch(fetch) => (
+            &fetch.common,
+            input_schema(&fetch.input, "fetch", outer, anchors)?,
+        ),
+        RelType::Sort(sort) => (
+            &sort.common,
+            input_schema(&sort.input, "sort", outer, anchors)?,
+        ),
+        RelType::Project(project) => {
+            // Expressions are appended to the fields of the input.
+            let mut fields = input_schema(&project.input, "project", outer, anchors)?;
+            let input = ConcreteType::r#struct(fields.clone(), false);
+            for expression in &project.expressions {
+                fields.push(expression_type(
+                    expression,
+                    &input,
+                    outer,
+                    anchors.registry(),
+                    anchors,
+                )?);
+            }
+            (&project.common, fields)
+        }
+        RelType::Cross(cross) => {
+            let mut fields = input_schema(&cross.left, "cross", outer, anchors)?;
+            fields.extend(input_schema(&cross.right, "cross", outer, anchors)?);
+            (&cross.common, fields)
+        }
+        RelType::Join(join) => (
+            &join.common,
+            join_schema(
+                &join.left,
+                &join.right,
+                logical_join_type(join.r#type)?,
+                "join",
+                outer,
+                anchors,
+            )?,
+        ),
+        RelType::HashJoin(join) => (
+            &join.common,
+            join_schema(
+                &join.left,
+                &join.right,
+                physical_join_type!(hash_join_rel, join.r#type)?,
+                "hash join",
+                outer,
+                anchors,
+            )?,
+        ),
+        RelType::MergeJoin(join) => (
+            &join.common,
+            join_schema(
+                &join.left,
+                &join.right,
+                physical_join_type!(merge_join_rel, join.r#type)?,
+                "merge join",
+                outer,
+                anchors,
+            )?,
+        ),
+        RelType::NestedLoopJoin(join) => (
+            &join.common,
+            join_schema(
+                &join.left,
+                &join.right,
+                physical_join_type!(nested_loop_join_rel, join.r#type)?,
+                "nested loop join",
+                outer,
+                anchors,
+            )?,
+        ),
+        RelType::Exchange(exchange) => (
+            &exchange.common,
+            input_schema(&exchange.input, "exchange", outer, anchors)?,
+        ),
+        RelType::Expand(expand) => (&expand.common, expand_schema(expand, outer, anchors)?),
+        RelType::Window(window) => {
+            // The results of the window functions are appended to the fields
+            // of the input.
+            let mut fields = input_schema(&window.input, "window", outer, anchors)?;
+            for function in &window.window_functions {
+                let output_type = function.output_type.clone().ok_or(RelError::MissingField {
+                    rel: "window",
+                    field: "window function output type",
+                })?;
+                fields.push(concrete_type(output_type, anchors)?);
+            }
+            (&window.common, fields)
+        }
+        RelType::Aggregate(aggregate) => (
+            &aggregate.common,
+            aggregate_schema(aggregate, outer, anchors)?,
+        ),
+        RelType::Set(set) => (&set.common, set_schema(set, outer, anchors)?),
+        RelType::ExtensionSingle(_) | RelType::ExtensionMulti(_) | RelType::ExtensionLeaf(_) => {
+            return Err(SchemaError::Unsupported("extension relations"));
+        }
+        RelType::Reference(_) => {
+            return Err(SchemaError::Unsupported("relation references"));
+        }
+        rel_type => return Err(SchemaError::Unsupported(rel_type_name(rel_type))),
+    };
+
+    Ok(ConcreteType::r#struct(emit(common, fields)?, false))
+}
+
+/// The relations of a type whose output schema can not be derived, for
+/// [SchemaError::Unsupported].
+fn rel_type_name(rel_type: &RelType) -> &'static str {
+    match rel_type {
+        RelType::Write(_) => "write relations",
+        RelType::Ddl(_) => "DDL relations",
+        RelType::Update(_) => "update relations",
+        _ => "relations of this type",
+    }
+}
+
+/// Apply the emit of a relation to its direct output fields.
+fn emit(
+    common: &Option<proto::RelCommon>,
+    fields: Vec<ConcreteType>,
+) -> Result<Vec<ConcreteType>, SchemaError> {
+    match common.as_ref().and_then(|common| common.emit_kind.as_ref()) {
+        Some(EmitKind::Emit(emit)) => emit
+            .output_mapping
+            .iter()
+            .map(|&index| {
+                usize::try_from(index)
+                    .ok()
+                    .and_then(|index| fields.get(index))
+                    .cloned()
+                    .ok_or(SchemaError::EmitOutOfRange {
+                        index,
+                        count: fields.len(),
+                    })
+            })
+            .collect(),
+        Some(EmitKind::Direct(_)) | None => Ok(fields),
+    }
+}
+
+/// Returns the output fields of a required input of a relation.
+fn input_schema(
+    input: &Option<Box<proto::Rel>>,
+    rel: &'static str,
+    outer: &[ConcreteType],
+    anchors: &ExtensionAnchors<'_>,
+) -> Result<Vec<ConcreteType>, SchemaError> {
+    let input = input.as_deref().ok_or(RelError::MissingInput(rel))?;
+    fields(output_schema_with_outer(input, outer, anchors)?)
+}
+
+/// Returns the fields of a struct type.
+fn fields(schema: ConcreteType) -> Result<Vec<ConcreteType>, SchemaError> {
+    match schema.kind {
+        ConcreteTypeKind::Struct(fields) => Ok(fields),
+        ConcreteTypeKind::NamedStruct { fields } => Ok(fields.into_values().collect()),
+        _ => Err(SchemaError::Unsupported(
+            "a relation with a non-struct schema",
+        )),
+    }
+}
+
+/// Returns a field made nullable, as for the side of a join without a match.
+fn nullable(mut field: ConcreteType) -> ConcreteType {
+    field.nullable = true;
+    field
+}
+
+/// The fields of the base schema of a read, with its projection applied.
+fn read_schema(
+    read: &proto::ReadRel,
+    anchors: &ExtensionAnchors<'_>,
+) -> Result<Vec<ConcreteType>, SchemaError> {
+    let base_schema = read.base_schema.as_ref().ok_or(RelError::MissingField {
+        rel: "read",
+        field: "base schema",
+    })?;
+    let r#struct = base_schema.r#struct.clone().ok_or(RelError::MissingField {
+        rel: "read",
+        field: "base schema struct",
+    })?;
+    let fields = fields(concrete_type(
+        proto::Type {
+            kind: Some(proto::r#type::Kind::Struct(r#struct)),
+        },
+        anchors,
+    )?)?;
+
+    // Only top-level field selections are supported.
+    let Some(select) = read
+        .projection
+        .as_ref()
+        .and_then(|mask| mask.select.as_ref())
+    else {
+        return Ok(fields);
+    };
+    select
+        .struct_items
+        .iter()
+        .map(|mask_expression::StructItem { field, child }| {
+            if child.is_some() {
+                return Err(SchemaError::Unsupported("nested read projections"));
+            }
+            usize::try_from(*field)
+                .ok()
+                .and_then(|index| fields.get(index))
+                .cloned()
+                .ok_or(SchemaError::ProjectionOutOfRange {
+                    field: *field,
+                    count: fields.len(),
+                })
+        })
+        .collect()
+}
+
+/// The output fields of a (logical or physical) join, by join type.
+fn join_schema(
+    left: &Option<Box<proto::Rel>>,
+    right: &Option<Box<proto::Rel>>,
+    join_type: JoinType,
+    rel: &'static str,
+    outer: &[ConcreteType],
+    anchors: &ExtensionAnchors<'_>,
+) -> Result<Vec<ConcreteType>, SchemaError> {
+    let left = input_schema(left, rel, outer, anchors)?;
+    let right = input_schema(right, rel, outer, anchors)?;
+    let all_nullable = |fields: Vec<ConcreteType>| fields.into_iter().map(nullable);
+    let mark = || ConcreteType::builtin(BasicBuiltinType::Boolean, true);
+
+    Ok(match join_type {
+        JoinType::Inner => left.into_iter().chain(right).collect(),
+        JoinType::Outer => all_nullable(left).chain(all_nullable(right)).collect(),
+        JoinType::Left | JoinType::LeftSingle => {
+            left.into_iter().chain(all_nullable(right)).collect()
+        }
+        JoinType::Right | JoinType::RightSingle => all_nullable(left).chain(right).collect(),
+        // Semi and anti joins only return the records of one side.
+        JoinType::LeftSemi | JoinType::LeftAnti => left,
+        JoinType::RightSemi | JoinType::RightAnti => right,
+        // Mark joins append whether a record has a match.
+        JoinType::LeftMark => left.into_iter().chain([mark()]).collect(),
+        JoinType::RightMark => right.into_iter().chain([mark()]).collect(),
+        JoinType::Unspecified => unreachable!("join types are specified"),
+    })
+}
+
+/// The output fields of an expand: a field per input field, as defined by the
+/// expand fields (or kept as is beyond them), and the `i64` index of the
+/// duplicate.
+fn expand_schema(
+    expand: &proto::ExpandRel,
+    outer: &[ConcreteType],
+    anchors: &ExtensionAnchors<'_>,
+) -> Result<Vec<ConcreteType>, SchemaError> {
+    let input_fields = input_schema(&expand.input, "expand", outer, anchors)?;
+    let input = ConcreteType::r#struct(input_fields.clone(), false);
+    let expression_type =
+        |expression| expression_type(expression, &input, outer, anchors.registry(), anchors);
+
+    let mut fields = expand
+        .fields
+        .iter()
+        .map(|ExpandField { field_type }| {
+            match field_type.as_ref().ok_or(RelError::MissingField {
+                rel: "expand",
+                field: "field type",
+            })? {
+                FieldType::ConsistentField(expression) => Ok(expression_type(expression)?),
+                // The duplicates have the same type, the field is nullable if
+                // any of them is.
+                FieldType::SwitchingField(switching) => {
+                    let mut types = switching.duplicates.iter().map(expression_type);
+                    let mut field = types.next().ok_or(RelError::MissingField {
+                        rel: "expand",
+                        field: "switching field duplicates",
+                    })??;
+                    for other in types {
+                        field.nullable |= other?.nullable;
+                    }
+                    Ok(field)
+                }
+            }
+        })
+        .collect::<Result<Vec<_>, SchemaError>>()?;
+    fields.extend(input_fields.into_iter().skip(fields.len()));
+    fields.push(ConcreteType::builtin(BasicBuiltinType::I64, false));
+    Ok(fields)
+}
+
+/// The output fields of an aggregate: the grouping expressions, the measures
+/// and, with more than one grouping set, the index of the grouping set.
+fn aggregate_schema(
+    aggregate: &proto::AggregateRel,
+    outer: &[ConcreteType],
+    anchors: &ExtensionAnchors<'_>,
+) -> Result<Vec<ConcreteType>, SchemaError> {
+    let input = ConcreteType::r#struct(
+        input_schema(&aggregate.input, "aggregate", outer, anchors)?,
+        false,
+    );
+
+    // Groupings reference the grouping expressions by index, which can not
+    // be referenced when there are none.
+    let count = aggregate.grouping_expressions.len();
+    if let Some(&reference) = aggregate
+        .groupings
+        .iter()
+        .flat_map(|grouping| &grouping.expression_references)
+        .find(|&&reference| reference as usize >= count)
+    {
+        return Err(RelError::GroupingReference { reference, count }.into());
+    }
+
+    // The grouping expressions, and for each grouping set the expressions it
+    // groups by. Deprecated groupings list their expressions themselves.
+    #[allow(deprecated)]
+    let (expressions, groupings) = if aggregate.grouping_expressions.is_empty() {
+        let mut expressions: Vec<&proto::Expression> = Vec::new();
+        let groupings = aggregate
+            .groupings
+            .iter()
+            .map(|grouping| {
+                grouping
+                    .grouping_expressions
+                    .iter()
+                    .map(|expression| {
+                        expressions
+                            .iter()
+                            .position(|e| *e == expression)
+                            .unwrap_or_else(|| {
+                                expressions.push(expression);
+                                expressions.len() - 1
+                            })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        (expressions, groupings)
+    } else {
+        let groupings = aggregate
+            .groupings
+            .iter()
+            .map(|grouping| {
+                grouping
+                    .expression_references
+                    .iter()
+                    .map(|&reference| reference as usize)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        (aggregate.grouping_expressions.iter().collect(), groupings)
+    };
+
+    // Grouping expressions that are not part of every grouping set are null
+    // for the records of the other sets.
+    let mut fields = expressions
+        .into_iter()
+        .enumerate()
+        .map(|(index, expression)| {
+            let field = expression_type(expression, &input, outer, anchors.registry(), anchors)?;
+            let in_every_set = groupings.iter().all(|grouping| grouping.contains(&index));
+            Ok(if in_every_set { field } else { nullable(field) })
+        })
+        .collect::<Result<Vec<_>, SchemaError>>()?;
+
+    for measure in &aggregate.measures {
+        let output_type = measure
+            .measure
+            .as_ref()
+            .and_then(|function| function.output_type.clone())
+            .ok_or(RelError::MissingField {
+                rel: "aggregate",
+                field: "measure output type",
+            })?;
+        fields.push(concrete_type(output_type, anchors)?);
+    }
+
+    if groupings.len() > 1 {
+        fields.push(ConcreteType::builtin(BasicBuiltinType::I32, false));
+    }
+    Ok(fields)
+}
+
+/// The output fields of a set operation: the fields of the first input, which
+/// are nullable for unions if they are nullable in any input. The fields of
+/// all inputs must have the same types, other than their nullability.
+fn set_schema(
+    set: &proto::SetRel,
+    outer: &[ConcreteType],
+    anchors: &ExtensionAnchors<'_>,
+) -> Result<Vec<ConcreteType>, SchemaError> {
+    let mut inputs = set
+        .inputs
+        .iter()
+        .map(|input| fields(output_schema_with_outer(input, outer, anchors)?))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter();
+    let mut fields = inputs.next().ok_or(RelError::SetInputs(0))?;
+
+    // All inputs must have the same number of fields, of the same types.
+    let union = matches!(
+        SetOp::try_from(set.op),
+        Ok(SetOp::UnionDistinct | SetOp::UnionAll)
+    );
+    for (index, input) in inputs.enumerate() {
+        if input.len() != fields.len() {
+            return Err(SchemaError::SetFieldCount {
+                index: index + 1,
+                expected: fields.len(),
+                actual: input.len(),
+            });
+        }
+        for (field_index, (field, other)) in fields.iter_mut().zip(input).enumerate() {
+            if field.kind != other.kind || field.variation != other.variation {
+                return Err(SchemaError::SetFieldType {
+                    index: index + 1,
+                    field: field_index,
+                    expected: field.clone(),
+                    actual: other,
+                });
+            }
+            if union {
+                field.nullable |= other.nullable;
+            }
+        }
+    }
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parse::{
+            Context,
+            proto::{
+                expression::tests::{anchors, call, i32_literal, i32_type, registry},
+                rel::tests::{field, read_rel},
+            },
+        },
+        proto::{function_argument::ArgType, rel_common},
+    };
+
+    fn i32(nullable: bool) -> ConcreteType {
+        ConcreteType::builtin(BasicBuiltinType::I32, nullable)
+    }
+
+    fn join(r#type: JoinType) -> proto::Rel {
+        proto::Rel {
+            rel_type: Some(RelType::Join(Box::new(proto::JoinRel {
+                left: Some(Box::new(read_rel())),
+                right: Some(Box::new(read_rel())),
+                r#type: r#type as i32,
+                ..Default::default()
+            }))),
+        }
+    }
+
+    #[test]
+    fn project() -> Result<(), SchemaError> {
+        let registry = registry();
+        let ctx = anchors(Some(&registry));
+
+        let add = call(
+            1,
+            vec![
+                ArgType::Enum("ERROR".to_string()),
+                ArgType::Value(field(0)),
+                ArgType::Value(i32_literal(1)),
+            ],
+            i32_type(),
+        );
+        let project = |emit_kind| proto::Rel {
+            rel_type: Some(RelType::Project(Box::new(proto::ProjectRel {
+                common: Some(proto::RelCommon {
+                    emit_kind,
+                    ..Default::default()
+                }),
+                input: Some(Box::new(read_rel())),
+                expressions: vec![add.clone()],
+                ..Default::default()
+            }))),
+        };
+
+        assert_eq!(
+            output_schema(&project(None), &ctx)?,
+            ConcreteType::r#struct(vec![i32(false), i32(false)], false)
+        );
+
+        // The emit selects and reorders the output fields
+        let emit = |output_mapping| Some(EmitKind::Emit(rel_common::Emit { output_mapping }));
+        assert_eq!(
+            output_schema(&project(emit(vec![1, 1, 0])), &ctx)?,
+            ConcreteType::r#struct(vec![i32(false); 3], false)
+        );
+        assert_eq!(
+            output_schema(&project(emit(vec![2])), &ctx),
+            Err(SchemaError::EmitOutOfRange { index: 2, count: 2 })
+        );
+
+        // Parsed relations have the same schema
+        let mut parse_ctx = anchors(Some(&registry));
+        let rel: Rel = parse_ctx.parse(project(None))?;
+        assert_eq!(
+            rel.output_schema(&ctx)?,
+            output_schema(&project(None), &ctx)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn join_types() -> Result<(), SchemaError> {
+        let ctx = ExtensionAnchors::default();
+        let schema = |fields| ConcreteType::r#struct(fields, false);
+
+        assert_eq!(
+            output_schema(&join(JoinType::Inner), &ctx)?,
+            schema(vec![i32(false), i32(false)])
+        );
+        assert_eq!(
+            output_schema(&join(JoinType::Left), &ctx)?,
+            schema(vec![i32(false), i32(true)])
+        );
+        assert_eq!(
+            output_schema(&join(JoinType::Outer), &ctx)?,
+            schema(vec![i32(true), i32(true)])
+        );
+        assert_eq!(
+            output_schema(&join(JoinType::LeftSemi), &ctx)?,
+            schema(vec![i32(false)])
+        );
+        assert_eq!(
+            output_schema(&join(JoinType::RightAnti), &ctx)?,
+            schema(vec![i32(false)])
+        );
+        assert_eq!(
+            output_schema(&join(JoinType::LeftMark), &ctx)?,
+            schema(vec![
+                i32(false),
+                ConcreteType::builtin(BasicBuiltinType::Boolean, true)
+            ])
+        );
+        assert_eq!(
+            output_schema(&join(JoinType::Unspecified), &ctx),
+            Err(SchemaError::Rel(RelError::JoinType(0)))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn physical_joins() -> Result<(), SchemaError> {
+        let ctx = ExtensionAnchors::default();
+
+        // Physical joins have the schema of the logical join of their type
+        let hash_join = proto::Rel {
+            rel_type: Some(RelType::HashJoin(Box::new(proto::HashJoinRel {
+                left: Some(Box::new(read_rel())),
+                right: Some(Box::new(read_rel())),
+                r#type: proto::hash_join_rel::JoinType::Left as i32,
+                ..Default::default()
+            }))),
+        };
+        assert_eq!(
+            output_schema(&hash_join, &ctx)?,
+            output_schema(&join(JoinType::Left), &ctx)?
+        );
+        let merge_join = proto::Rel {
+            rel_type: Some(RelType::MergeJoin(Box::new(proto::MergeJoinRel {
+                left: Some(Box::new(read_rel())),
+                right: Some(Box::new(read_rel())),
+                r#type: proto::merge_join_rel::JoinType::RightSemi as i32,
+                ..Default::default()
+            }))),
+        };
+        assert_eq!(
+            output_schema(&merge_join, &ctx)?,
+            output_schema(&join(JoinType::RightSemi), &ctx)?
+        );
+        let nested_loop_join = proto::Rel {
+            rel_type: Some(RelType::NestedLoopJoin(Box::new(
+                proto::NestedLoopJoinRel {
+                    left: Some(Box::new(read_rel())),
+                    right: Some(Box::new(read_rel())),
+                    ..Default::default()
+                },
+            ))),
+        };
+        assert_eq!(
+            output_schema(&nested_loop_join, &ctx),
+            Err(SchemaError::Rel(RelError::JoinType(0)))
+        );
+
+        let exchange = proto::Rel {
+            rel_type: Some(RelType::Exchange(Box::new(proto::ExchangeRel {
+                input: Some(Box::new(hash_join)),
+                ..Default::default()
+            }))),
+        };
+        assert_eq!(
+            output_schema(&exchange, &ctx)?,
+            ConcreteType::r#struct(vec![i32(false), i32(true)], false)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn window_and_expand() -> Result<(), SchemaError> {
+        use proto::expand_rel::{ExpandField, SwitchingField, expand_field::FieldType};
+
+        let ctx = ExtensionAnchors::default();
+
+        // Window functions are appended to the fields of the input
+        let window = proto::Rel {
+            rel_type: Some(RelType::Window(Box::new(
+                proto::ConsistentPartitionWindowRel {
+                    input: Some(Box::new(read_rel())),
+                    window_functions: vec![
+                        proto::consistent_partition_window_rel::WindowRelFunction {
+                            output_type: Some(proto::Type {
+                                kind: Some(proto::r#type::Kind::I64(proto::r#type::I64 {
+                                    type_variation_reference: 0,
+                                    nullability: proto::r#type::Nullability::Nullable as i32,
+                                })),
+                            }),
+                            ..Default::default()
+                        },
+                    ],
+                    ..Default::default()
+                },
+            ))),
+        };
+        assert_eq!(
+            output_schema(&window, &ctx)?,
+            ConcreteType::r#struct(
+                vec![
+                    i32(false),
+                    ConcreteType::builtin(BasicBuiltinType::I64, true)
+                ],
+                false
+            )
+        );
+
+        // Expand fields define the fields of the input, and the index of the
+        // duplicate is appended
+        let expand = |fields| proto::Rel {
+            rel_type: Some(RelType::Expand(Box::new(proto::ExpandRel {
+                input: Some(Box::new(window.clone())),
+                fields,
+                ..Default::default()
+            }))),
+        };
+        let switching = |duplicates| ExpandField {
+            field_type: Some(FieldType::SwitchingField(SwitchingField { duplicates })),
+        };
+        assert_eq!(
+            output_schema(
+                &expand(vec![switching(vec![i32_literal(1), field(0)])]),
+                &ctx
+            )?,
+            ConcreteType::r#struct(
+                vec![
+                    i32(false),
+                    ConcreteType::builtin(BasicBuiltinType::I64, true),
+                    ConcreteType::builtin(BasicBuiltinType::I64, false)
+                ],
+                false
+            )
+        );
+        assert_eq!(
+            output_schema(&expand(vec![switching(vec![])]), &ctx),
+            Err(SchemaError::Rel(RelError::MissingField {
+                rel: "expand",
+                field: "switching field duplicates"
+            }))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn set_field_count() {
+        let ctx = ExtensionAnchors::default();
+
+        let set = proto::Rel {
+            rel_type: Some(RelType::Set(proto::SetRel {
+                inputs: vec![read_rel(), join(JoinType::Inner)],
+                op: SetOp::UnionAll as i32,
+                ..Default::default()
+            })),
+        };
+        assert_eq!(
+            output_schema(&set, &ctx),
+            Err(SchemaError::SetFieldCount {
+                index: 1,
+                expected: 1,
+                actual: 2
+            })
+        );
+    }
+
+    #[test]
+    fn set_field_type() -> Result<(), SchemaError> {
+        let ctx = ExtensionAnchors::default();
+        let read = |kind| {
+            let mut rel = read_rel();
+            if let Some(RelType::Read(read)) = &mut rel.rel_type {
+                read.base_schema
+                    .as_mut()
+                    .unwrap()
+                    .r#struct
+                    .as_mut()
+                    .unwrap()
+                    .types = vec![proto::Type { kind: Some(kind) }];
+            }
+            rel
+        };
+        let set = |inputs| proto::Rel {
+            rel_type: Some(RelType::Set(proto::SetRel {
+                inputs,
+                op: SetOp::UnionAll as i32,
+                ..Default::default()
+            })),
+        };
+        let i64_kind = proto::r#type::Kind::I64(proto::r#type::I64 {
+            type_variation_reference: 0,
+            nullability: proto::r#type::Nullability::Required as i32,
+        });
+        let nullable_i32_kind = proto::r#type::Kind::I32(proto::r#type::I32 {
+            type_variation_reference: 0,
+            nullability: proto::r#type::Nullability::Nullable as i32,
+        });
+
+        // Only the nullability of the fields may differ
+        assert_eq!(
+            output_schema(&set(vec![read_rel(), read(nullable_i32_kind)]), &ctx)?,
+            ConcreteType::r#struct(vec![i32(true)], false)
+        );
+        assert_eq!(
+            output_schema(&set(vec![read_rel(), read(i64_kind)]), &ctx),
+            Err(SchemaError::SetFieldType {
+                index: 1,
+                field: 0,
+                expected: i32(false),
+                actual: ConcreteType::builtin(BasicBuiltinType::I64, false),
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate() -> Result<(), SchemaError> {
+        let ctx = ExtensionAnchors::default();
+        let aggregate = |groupings: Vec<Vec<u32>>| proto::Rel {
+            rel_type: Some(RelType::Aggregate(Box::new(proto::AggregateRel {
+                input: Some(Box::new(read_rel())),
+                grouping_expressions: vec![field(0)],
+                groupings: groupings
+                    .into_iter()
+                    .map(|expression_references| proto::aggregate_rel::Grouping {
+                        expression_references,
+                        ..Default::default()
+                    })
+                    .collect(),
+                measures: vec![proto::aggregate_rel::Measure {
+                    measure: Some(proto::AggregateFunction {
+                        output_type: Some(i32_type()),
+                        ..Default::default()
+                    }),
+                    filter: None,
+                }],
+                ..Default::default()
+            }))),
+        };
+
+        assert_eq!(
+            output_schema(&aggregate(vec![vec![0]]), &ctx)?,
+            ConcreteType::r#struct(vec![i32(false), i32(false)], false)
+        );
+
+        // With multiple grouping sets, keys missing from a set are nullable
+        // and the grouping set index is appended
+        assert_eq!(
+            output_schema(&aggregate(vec![vec![0], vec![]]), &ctx)?,
+            ConcreteType::r#struct(vec![i32(true), i32(false), i32(false)], false)
+        );
+        assert_eq!(
+            output_schema(&aggregate(vec![vec![1]]), &ctx),
+            Err(SchemaError::Rel(RelError::GroupingReference {
+                reference: 1,
+                count: 1
+            }))
+        );
+
+        // References require grouping expressions
+        let mut rel = aggregate(vec![vec![0]]);
+        if let Some(RelType::Aggregate(aggregate)) = &mut rel.rel_type {
+            aggregate.grouping_expressions.clear();
+        }
+        assert_eq!(
+            output_schema(&rel, &ctx),
+            Err(SchemaError::Rel(RelError::GroupingReference {
+                reference: 0,
+                count: 0
+            }))
+        );
+        Ok(())
+    }
+}
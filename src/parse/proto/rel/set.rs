@@ -0,0 +1,147 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing of [proto::SetRel].
+
+use crate::{
+    parse::{
+        Context, Parse,
+        proto::{
+            ExtensionAnchors,
+            rel::{Rel, RelError},
+        },
+    },
+    proto::{self, set_rel::SetOp},
+};
+
+/// A parsed [proto::SetRel].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SetRel {
+    /// Common fields of relations.
+    common: Option<proto::RelCommon>,
+
+    /// The input relations, at least two.
+    inputs: Vec<Rel>,
+
+    /// The set operation, which is not [SetOp::Unspecified].
+    op: SetOp,
+
+    /// Optional advanced extension.
+    advanced_extension: Option<proto::extensions::AdvancedExtension>,
+}
+
+impl SetRel {
+    /// Returns the common fields of this relation.
+    ///
+    /// See [proto::SetRel::common].
+    pub fn common(&self) -> Option<&proto::RelCommon> {
+        self.common.as_ref()
+    }
+
+    /// Returns the input relations. There are at least two inputs.
+    ///
+    /// See [proto::SetRel::inputs].
+    pub fn inputs(&self) -> &[Rel] {
+        &self.inputs
+    }
+
+    /// Returns the set operation.
+    ///
+    /// See [proto::SetRel::op].
+    pub fn op(&self) -> SetOp {
+        self.op
+    }
+
+    /// Returns the advanced extension of this relation.
+    ///
+    /// See [proto::SetRel::advanced_extension].
+    pub fn advanced_extension(&self) -> Option<&proto::extensions::AdvancedExtension> {
+        self.advanced_extension.as_ref()
+    }
+}
+
+impl Parse<ExtensionAnchors<'_>> for proto::SetRel {
+    type Parsed = SetRel;
+    type Error = RelError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'_>) -> Result<Self::Parsed, Self::Error> {
+        let proto::SetRel {
+            common,
+            inputs,
+            op,
+            advanced_extension,
+        } = self;
+
+        let op = match SetOp::try_from(op) {
+            Ok(SetOp::Unspecified) | Err(_) => return Err(RelError::SetOp(op)),
+            Ok(op) => op,
+        };
+
+        // Set operations combine at least two inputs.
+        if inputs.len() < 2 {
+            return Err(RelError::SetInputs(inputs.len()));
+        }
+
+        Ok(SetRel {
+            common,
+            inputs: inputs
+                .into_iter()
+                .map(|input| ctx.parse(input))
+                .collect::<Result<_, _>>()?,
+            op,
+            advanced_extension,
+        })
+    }
+}
+
+impl From<SetRel> for proto::SetRel {
+    fn from(set: SetRel) -> Self {
+        let SetRel {
+            common,
+            inputs,
+            op,
+            advanced_extension,
+        } = set;
+
+        proto::SetRel {
+            common,
+            inputs: inputs.into_iter().map(Into::into).collect(),
+            op: op.into(),
+            advanced_extension,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::proto::rel::tests::read_rel;
+
+    #[test]
+    fn set_rel() {
+        let mut ctx = ExtensionAnchors::default();
+        let set = proto::SetRel {
+            inputs: vec![read_rel(), read_rel()],
+            op: SetOp::UnionAll.into(),
+            ..Default::default()
+        };
+        let parsed = ctx.parse(set.clone()).unwrap();
+        assert_eq!(parsed.op(), SetOp::UnionAll);
+        assert_eq!(parsed.inputs().len(), 2);
+        assert_eq!(proto::SetRel::from(parsed), set);
+
+        assert_eq!(
+            ctx.parse(proto::SetRel {
+                inputs: vec![read_rel()],
+                ..set.clone()
+            }),
+            Err(RelError::SetInputs(1))
+        );
+        assert_eq!(
+            ctx.parse(proto::SetRel {
+                op: SetOp::Unspecified.into(),
+                ..set
+            }),
+            Err(RelError::SetOp(0))
+        );
+    }
+}
@@ -0,0 +1,230 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing of [proto::SortRel] and [proto::SortField].
+
+use crate::{
+    parse::{
+        Anchor, Parse,
+        proto::{
+            ExtensionAnchors,
+            extensions::ExtensionFunction,
+            rel::{Rel, RelError, parse_expression, parse_input, required},
+        },
+    },
+    proto::{self, sort_field::SortDirection},
+};
+
+/// A parsed [proto::SortRel].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SortRel {
+    /// Common fields of relations.
+    common: Option<proto::RelCommon>,
+
+    /// The input relation.
+    input: Box<Rel>,
+
+    /// The fields to sort by, in order of precedence.
+    sorts: Vec<SortField>,
+
+    /// Optional advanced extension.
+    advanced_extension: Option<proto::extensions::AdvancedExtension>,
+}
+
+impl SortRel {
+    /// Returns the common fields of this relation.
+    ///
+    /// See [proto::SortRel::common].
+    pub fn common(&self) -> Option<&proto::RelCommon> {
+        self.common.as_ref()
+    }
+
+    /// Returns the input relation.
+    ///
+    /// See [proto::SortRel::input].
+    pub fn input(&self) -> &Rel {
+        &self.input
+    }
+
+    /// Returns the fields to sort by.
+    ///
+    /// See [proto::SortRel::sorts].
+    pub fn sorts(&self) -> &[SortField] {
+        &self.sorts
+    }
+
+    /// Returns the advanced extension of this relation.
+    ///
+    /// See [proto::SortRel::advanced_extension].
+    pub fn advanced_extension(&self) -> Option<&proto::extensions::AdvancedExtension> {
+        self.advanced_extension.as_ref()
+    }
+}
+
+/// A parsed [proto::SortField].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SortField {
+    /// The expression to sort by.
+    expr: proto::Expression,
+
+    /// How to sort.
+    kind: SortKind,
+}
+
+impl SortField {
+    /// Returns the expression to sort by.
+    ///
+    /// See [proto::SortField::expr].
+    pub fn expr(&self) -> &proto::Expression {
+        &self.expr
+    }
+
+    /// Returns how to sort.
+    ///
+    /// See [proto::SortField::sort_kind].
+    pub fn kind(&self) -> &SortKind {
+        &self.kind
+    }
+}
+
+/// How to sort a [SortField].
+///
+/// See [proto::sort_field::SortKind].
+#[derive(Clone, Debug, PartialEq)]
+pub enum SortKind {
+    /// A sort direction, which is not [SortDirection::Unspecified].
+    Direction(SortDirection),
+
+    /// A comparison function, declared by the plan.
+    ComparisonFunction(Anchor<ExtensionFunction>),
+}
+
+impl Parse<ExtensionAnchors<'_>> for proto::SortRel {
+    type Parsed = SortRel;
+    type Error = RelError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'_>) -> Result<Self::Parsed, Self::Error> {
+        let proto::SortRel {
+            common,
+            input,
+            sorts,
+            advanced_extension,
+        } = self;
+
+        Ok(SortRel {
+            common,
+            input: parse_input(input, "sort", ctx)?,
+            sorts: sorts
+                .into_iter()
+                .map(|sort| sort.parse(ctx))
+                .collect::<Result<_, _>>()?,
+            advanced_extension,
+        })
+    }
+}
+
+impl From<SortRel> for proto::SortRel {
+    fn from(sort: SortRel) -> Self {
+        let SortRel {
+            common,
+            input,
+            sorts,
+            advanced_extension,
+        } = sort;
+
+        proto::SortRel {
+            common,
+            input: Some(Box::new((*input).into())),
+            sorts: sorts.into_iter().map(Into::into).collect(),
+            advanced_extension,
+        }
+    }
+}
+
+impl Parse<ExtensionAnchors<'_>> for proto::SortField {
+    type Parsed = SortField;
+    type Error = RelError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'_>) -> Result<Self::Parsed, Self::Error> {
+        let proto::SortField { expr, sort_kind } = self;
+
+        let kind = match required(sort_kind, "sort", "sort kind")? {
+            proto::sort_field::SortKind::Direction(direction) => {
+                match SortDirection::try_from(direction) {
+                    Ok(SortDirection::Unspecified) | Err(_) => {
+                        return Err(RelError::SortDirection(direction));
+                    }
+                    Ok(direction) => SortKind::Direction(direction),
+                }
+            }
+            proto::sort_field::SortKind::ComparisonFunctionReference(reference) => {
+                // The comparison function must be declared.
+                let anchor = Anchor::new(reference);
+                ctx.get_extension_function(&anchor)?;
+                SortKind::ComparisonFunction(anchor)
+            }
+        };
+
+        Ok(SortField {
+            expr: parse_expression(required(expr, "sort", "sort expression")?, ctx)?,
+            kind,
+        })
+    }
+}
+
+impl From<SortField> for proto::SortField {
+    fn from(sort_field: SortField) -> Self {
+        let SortField { expr, kind } = sort_field;
+
+        proto::SortField {
+            expr: Some(expr),
+            sort_kind: Some(match kind {
+                SortKind::Direction(direction) => {
+                    proto::sort_field::SortKind::Direction(direction.into())
+                }
+                SortKind::ComparisonFunction(anchor) => {
+                    proto::sort_field::SortKind::ComparisonFunctionReference(anchor.into_inner())
+                }
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{Context as _, context::ContextError, proto::rel::tests::field};
+
+    #[test]
+    fn sort_field() {
+        let mut ctx = ExtensionAnchors::default();
+        let sort_field = proto::SortField {
+            expr: Some(field(0)),
+            sort_kind: Some(proto::sort_field::SortKind::Direction(
+                SortDirection::DescNullsLast.into(),
+            )),
+        };
+        let parsed = ctx.parse(sort_field.clone()).unwrap();
+        assert_eq!(
+            parsed.kind(),
+            &SortKind::Direction(SortDirection::DescNullsLast)
+        );
+        assert_eq!(proto::SortField::from(parsed), sort_field);
+
+        assert_eq!(
+            ctx.parse(proto::SortField {
+                sort_kind: Some(proto::sort_field::SortKind::Direction(0)),
+                ..sort_field.clone()
+            }),
+            Err(RelError::SortDirection(0))
+        );
+        assert_eq!(
+            ctx.parse(proto::SortField {
+                sort_kind: Some(proto::sort_field::SortKind::ComparisonFunctionReference(1)),
+                ..sort_field
+            }),
+            Err(RelError::Context(ContextError::UndefinedExtensionFunction(
+                Anchor::new(1)
+            )))
+        );
+    }
+}
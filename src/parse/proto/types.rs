@@ -0,0 +1,717 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing of [proto::Type].
+//!
+//! Protobuf types are parsed into the [ConcreteType]s used by simple
+//! extensions, so that types in a plan can be checked against function
+//! signatures.
+
+use thiserror::Error;
+
+use crate::{
+    parse::{
+        Anchor, Parse,
+        context::ContextError,
+        proto::ExtensionAnchors,
+        text::simple_extensions::{
+            BasicBuiltinType, ConcreteType, ConcreteTypeKind, ExtensionTypeError, TypeParameter,
+            builtin_from_parts,
+        },
+    },
+    proto::{
+        self,
+        r#type::{Kind, Nullability, parameter},
+    },
+};
+
+/// Parse errors for [proto::Type].
+#[derive(Debug, Error, PartialEq)]
+pub enum TypeError {
+    /// The type kind is missing.
+    #[error("type kind must be specified")]
+    Missing,
+
+    /// The nullability is unspecified or unknown.
+    #[error("type nullability must be nullable or required, got `{0}`")]
+    Nullability(i32),
+
+    /// The element type of a list is missing.
+    #[error("list element type must be specified")]
+    MissingListType,
+
+    /// The key type of a map is missing.
+    #[error("map key type must be specified")]
+    MissingMapKey,
+
+    /// The value type of a map is missing.
+    #[error("map value type must be specified")]
+    MissingMapValue,
+
+    /// A type parameter of a user-defined type is missing.
+    #[error("user-defined type parameter must be specified")]
+    MissingParameter,
+
+    /// A deprecated user-defined type reference is used.
+    #[error("deprecated user-defined type reference `{0}` is not supported")]
+    UserDefinedTypeReference(u32),
+
+    /// The parameters of a built-in type are invalid.
+    #[error(transparent)]
+    InvalidType(#[from] ExtensionTypeError),
+
+    /// Context error
+    #[error(transparent)]
+    Context(#[from] ContextError),
+}
+
+/// Returns whether a type is nullable, rejecting unspecified nullability.
+fn nullable(nullability: i32) -> Result<bool, TypeError> {
+    match Nullability::try_from(nullability) {
+        Ok(Nullability::Nullable) => Ok(true),
+        Ok(Nullability::Required) => Ok(false),
+        _ => Err(TypeError::Nullability(nullability)),
+    }
+}
+
+/// Resolves a type variation reference to the name of the variation. The
+/// reference `0` means no variation.
+fn variation(
+    ctx: &ExtensionAnchors<'_>,
+    type_variation_reference: u32,
+) -> Result<Option<String>, ContextError> {
+    match type_variation_reference {
+        0 => Ok(None),
+        anchor => ctx
+            .get_extension_type_variation(&Anchor::new(anchor))
+            .map(|variation| Some(variation.name().to_string())),
+    }
+}
+
+/// A parsed [proto::Type].
+///
+/// The [ConcreteType] resolves the user-defined types and type variations
+/// that the protobuf type references by anchor.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Type {
+    /// The resolved type.
+    concrete: ConcreteType,
+
+    /// The protobuf type, with the anchors of its references.
+    proto: proto::Type,
+}
+
+impl Type {
+    /// Returns the resolved type.
+    pub fn concrete(&self) -> &ConcreteType {
+        &self.concrete
+    }
+
+    /// Returns the resolved type, dropping the anchors of its references.
+    pub fn into_concrete(self) -> ConcreteType {
+        self.concrete
+    }
+}
+
+impl Parse<ExtensionAnchors<'_>> for proto::Type {
+    type Parsed = Type;
+    type Error = TypeError;
+
+    fn parse(self, ctx: &mut ExtensionAnchors<'_>) -> Result<Self::Parsed, Self::Error> {
+        Ok(Type {
+            concrete: concrete_type(self.clone(), ctx)?,
+            proto: self,
+        })
+    }
+}
+
+impl From<Type> for proto::Type {
+    fn from(r#type: Type) -> Self {
+        r#type.proto
+    }
+}
+
+/// Converts a [proto::Type] to a [ConcreteType], resolving the user-defined
+/// types and type variations it references.
+///
+/// Unlike parsing, this only needs shared access to the anchors.
+pub(crate) fn concrete_type(
+    ty: proto::Type,
+    ctx: &ExtensionAnchors<'_>,
+) -> Result<ConcreteType, TypeError> {
+    let proto::Type { kind } = ty;
+
+    // Built-in types, with the validated parameters of parameterized types.
+    let builtin = ConcreteTypeKind::Builtin;
+    let parameterized = |name: &str, params: &[i64]| -> Result<_, TypeError> {
+        Ok(ConcreteTypeKind::Builtin(builtin_from_parts(name, params)?))
+    };
+
+    // The kind is a required oneof.
+    #[allow(deprecated)]
+    let (kind, type_variation_reference, nullability) = match kind.ok_or(TypeError::Missing)? {
+        Kind::Bool(t) => (
+            builtin(BasicBuiltinType::Boolean),
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::I8(t) => (
+            builtin(BasicBuiltinType::I8),
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::I16(t) => (
+            builtin(BasicBuiltinType::I16),
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::I32(t) => (
+            builtin(BasicBuiltinType::I32),
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::I64(t) => (
+            builtin(BasicBuiltinType::I64),
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::Fp32(t) => (
+            builtin(BasicBuiltinType::Fp32),
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::Fp64(t) => (
+            builtin(BasicBuiltinType::Fp64),
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::String(t) => (
+            builtin(BasicBuiltinType::String),
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::Binary(t) => (
+            builtin(BasicBuiltinType::Binary),
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::Timestamp(t) => (
+            builtin(BasicBuiltinType::Timestamp),
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::TimestampTz(t) => (
+            builtin(BasicBuiltinType::TimestampTz),
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::Date(t) => (
+            builtin(BasicBuiltinType::Date),
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::Time(t) => (
+            builtin(BasicBuiltinType::Time),
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::IntervalYear(t) => (
+            builtin(BasicBuiltinType::IntervalYear),
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::Uuid(t) => (
+            builtin(BasicBuiltinType::Uuid),
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        // The precision defaults to microseconds for backwards
+        // compatibility.
+        Kind::IntervalDay(t) => (
+            parameterized("interval_day", &[t.precision.unwrap_or(6).into()])?,
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::IntervalCompound(t) => (
+            parameterized("interval_compound", &[t.precision.into()])?,
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::FixedChar(t) => (
+            parameterized("fixedchar", &[t.length.into()])?,
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::Varchar(t) => (
+            parameterized("varchar", &[t.length.into()])?,
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::FixedBinary(t) => (
+            parameterized("fixedbinary", &[t.length.into()])?,
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::Decimal(t) => (
+            parameterized("decimal", &[t.precision.into(), t.scale.into()])?,
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::PrecisionTime(t) => (
+            parameterized("precision_time", &[t.precision.into()])?,
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::PrecisionTimestamp(t) => (
+            parameterized("precision_timestamp", &[t.precision.into()])?,
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::PrecisionTimestampTz(t) => (
+            parameterized("precision_timestamp_tz", &[t.precision.into()])?,
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::Struct(t) => (
+            ConcreteTypeKind::Struct(
+                t.types
+                    .into_iter()
+                    .map(|field| concrete_type(field, ctx))
+                    .collect::<Result<_, _>>()?,
+            ),
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::List(t) => (
+            ConcreteTypeKind::List(Box::new(concrete_type(
+                *t.r#type.ok_or(TypeError::MissingListType)?,
+                ctx,
+            )?)),
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        Kind::Map(t) => (
+            ConcreteTypeKind::Map {
+                key: Box::new(concrete_type(*t.key.ok_or(TypeError::MissingMapKey)?, ctx)?),
+                value: Box::new(concrete_type(
+                    *t.value.ok_or(TypeError::MissingMapValue)?,
+                    ctx,
+                )?),
+            },
+            t.type_variation_reference,
+            t.nullability,
+        ),
+        // User-defined types are resolved by their type anchor.
+        Kind::UserDefined(t) => {
+            let extension_type = ctx.get_extension_type(&Anchor::new(t.type_reference))?;
+            (
+                ConcreteTypeKind::Extension {
+                    name: extension_type.name().to_string(),
+                    urn: Some(extension_type.urn().clone()),
+                    parameters: t
+                        .type_parameters
+                        .into_iter()
+                        .map(|parameter| type_parameter(parameter, ctx))
+                        .collect::<Result<_, _>>()?,
+                },
+                t.type_variation_reference,
+                t.nullability,
+            )
+        }
+        Kind::UserDefinedTypeReference(anchor) => {
+            return Err(TypeError::UserDefinedTypeReference(anchor));
+        }
+    };
+
+    Ok(ConcreteType {
+        kind,
+        nullable: nullable(nullability)?,
+        variation: variation(ctx, type_variation_reference)?,
+    })
+}
+
+/// Converts a [ConcreteType] to a [proto::Type], referencing user-defined
+/// types and type variations by the anchors they are declared with in `ctx`.
+///
+/// Field names of named structs are not part of a [proto::Type], and are
+/// dropped.
+pub fn proto_type(
+    concrete: ConcreteType,
+    ctx: &ExtensionAnchors<'_>,
+) -> Result<proto::Type, TypeError> {
+    use proto::r#type as t;
+
+    let ConcreteType {
+        kind,
+        nullable,
+        variation,
+    } = concrete;
+    let nullability = if nullable {
+        Nullability::Nullable
+    } else {
+        Nullability::Required
+    } as i32;
+    // No variation is written as the reference `0`. Type variations are
+    // referenced by name only, so they are looked up in all extensions.
+    let type_variation_reference = match variation {
+        Some(name) => ctx.find_extension_type_variation(None, &name)?.into_inner(),
+        None => 0,
+    };
+
+    // Every kind has a type variation reference and nullability.
+    macro_rules! kind {
+        ($variant:ident($type:ident { $($field:ident $(: $value:expr)?),* $(,)? })) => {
+            Kind::$variant(t::$type {
+                $($field $(: $value)?,)*
+                type_variation_reference,
+                nullability,
+            })
+        };
+    }
+
+    #[allow(deprecated)]
+    let kind = match kind {
+        ConcreteTypeKind::Builtin(builtin) => match builtin {
+            BasicBuiltinType::Boolean => kind!(Bool(Boolean {})),
+            BasicBuiltinType::I8 => kind!(I8(I8 {})),
+            BasicBuiltinType::I16 => kind!(I16(I16 {})),
+            BasicBuiltinType::I32 => kind!(I32(I32 {})),
+            BasicBuiltinType::I64 => kind!(I64(I64 {})),
+            BasicBuiltinType::Fp32 => kind!(Fp32(Fp32 {})),
+            BasicBuiltinType::Fp64 => kind!(Fp64(Fp64 {})),
+            BasicBuiltinType::String => kind!(String(String {})),
+            BasicBuiltinType::Binary => kind!(Binary(Binary {})),
+            BasicBuiltinType::Timestamp => kind!(Timestamp(Timestamp {})),
+            BasicBuiltinType::TimestampTz => kind!(TimestampTz(TimestampTz {})),
+            BasicBuiltinType::Date => kind!(Date(Date {})),
+            BasicBuiltinType::Time => kind!(Time(Time {})),
+            BasicBuiltinType::IntervalYear => kind!(IntervalYear(IntervalYear {})),
+            BasicBuiltinType::Uuid => kind!(Uuid(Uuid {})),
+            BasicBuiltinType::FixedChar { length } => kind!(FixedChar(FixedChar { length })),
+            BasicBuiltinType::VarChar { length } => kind!(Varchar(VarChar { length })),
+            BasicBuiltinType::FixedBinary { length } => {
+                kind!(FixedBinary(FixedBinary { length }))
+            }
+            BasicBuiltinType::Decimal { precision, scale } => {
+                kind!(Decimal(Decimal { precision, scale }))
+            }
+            BasicBuiltinType::PrecisionTime { precision } => {
+                kind!(PrecisionTime(PrecisionTime { precision }))
+            }
+            BasicBuiltinType::PrecisionTimestamp { precision } => {
+                kind!(PrecisionTimestamp(PrecisionTimestamp { precision }))
+            }
+            BasicBuiltinType::PrecisionTimestampTz { precision } => {
+                kind!(PrecisionTimestampTz(PrecisionTimestampTz { precision }))
+            }
+            BasicBuiltinType::IntervalDay { precision } => kind!(IntervalDay(IntervalDay {
+                precision: Some(precision)
+            })),
+            BasicBuiltinType::IntervalCompound { precision } => {
+                kind!(IntervalCompound(IntervalCompound { precision }))
+            }
+        },
+        // User-defined types are referenced by their type anchor.
+        ConcreteTypeKind::Extension {
+            name,
+            urn,
+            parameters,
+        } => kind!(UserDefined(UserDefined {
+            type_reference: ctx.find_extension_type(urn.as_ref(), &name)?.into_inner(),
+            type_parameters: parameters
+                .into_iter()
+                .map(|parameter| proto_type_parameter(parameter, ctx))
+                .collect::<Result<_, _>>()?,
+        })),
+        ConcreteTypeKind::List(element) => Kind::List(Box::new(t::List {
+            r#type: Some(Box::new(proto_type(*element, ctx)?)),
+            type_variation_reference,
+            nullability,
+        })),
+        ConcreteTypeKind::Map { key, value } => Kind::Map(Box::new(t::Map {
+            key: Some(Box::new(proto_type(*key, ctx)?)),
+            value: Some(Box::new(proto_type(*value, ctx)?)),
+            type_variation_reference,
+            nullability,
+        })),
+        ConcreteTypeKind::Struct(fields) => kind!(Struct(Struct {
+            types: fields
+                .into_iter()
+                .map(|field| proto_type(field, ctx))
+                .collect::<Result<_, _>>()?,
+        })),
+        ConcreteTypeKind::NamedStruct { fields } => kind!(Struct(Struct {
+            types: fields
+                .into_values()
+                .map(|field| proto_type(field, ctx))
+                .collect::<Result<_, _>>()?,
+        })),
+    };
+
+    Ok(proto::Type { kind: Some(kind) })
+}
+
+/// Converts a [ConcreteType] without extension declarations: user-defined
+/// types and type variations fail to resolve. Use [proto_type] with an
+/// [ExtensionAnchors] to reference these.
+///
+/// Unlike the conversion of a parsed [Type], this conversion is fallible: a
+/// [proto::Type] references user-defined types and type variations by anchor,
+/// and a [ConcreteType] does not know the anchors they are declared with.
+impl TryFrom<ConcreteType> for proto::Type {
+    type Error = TypeError;
+
+    fn try_from(value: ConcreteType) -> Result<Self, Self::Error> {
+        proto_type(value, &ExtensionAnchors::default())
+    }
+}
+
+/// Converts a [proto::Type] without extension declarations: user-defined
+/// types and type variations fail to resolve. Use an [ExtensionAnchors] parse
+/// context to resolve these.
+impl TryFrom<proto::Type> for ConcreteType {
+    type Error = TypeError;
+
+    fn try_from(value: proto::Type) -> Result<Self, Self::Error> {
+        concrete_type(value, &ExtensionAnchors::default())
+    }
+}
+
+/// Converts a [proto::r#type::Parameter] to a [TypeParameter], see
+/// [concrete_type].
+fn type_parameter(
+    parameter: proto::r#type::Parameter,
+    ctx: &ExtensionAnchors<'_>,
+) -> Result<TypeParameter, TypeError> {
+    let proto::r#type::Parameter { parameter } = parameter;
+
+    // The parameter is a required oneof.
+    Ok(match parameter.ok_or(TypeError::MissingParameter)? {
+        parameter::Parameter::Null(()) => TypeParameter::Null,
+        parameter::Parameter::DataType(t) => TypeParameter::Type(concrete_type(t, ctx)?),
+        parameter::Parameter::Boolean(b) => TypeParameter::Boolean(b),
+        parameter::Parameter::Integer(i) => TypeParameter::Integer(i),
+        parameter::Parameter::Enum(e) => TypeParameter::Enum(e),
+        parameter::Parameter::String(s) => TypeParameter::String(s),
+    })
+}
+
+/// Converts a [TypeParameter] to a [proto::r#type::Parameter], see
+/// [proto_type].
+fn proto_type_parameter(
+    parameter: TypeParameter,
+    ctx: &ExtensionAnchors<'_>,
+) -> Result<proto::r#type::Parameter, TypeError> {
+    Ok(proto::r#type::Parameter {
+        parameter: Some(match parameter {
+            TypeParameter::Null => parameter::Parameter::Null(()),
+            TypeParameter::Type(t) => parameter::Parameter::DataType(proto_type(t, ctx)?),
+            TypeParameter::Boolean(b) => parameter::Parameter::Boolean(b),
+            TypeParameter::Integer(i) => parameter::Parameter::Integer(i),
+            TypeParameter::Enum(e) => parameter::Parameter::Enum(e),
+            TypeParameter::String(s) => parameter::Parameter::String(s),
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::Context;
+    use crate::parse::text::simple_extensions::TypeExpr;
+    use crate::urn::Urn;
+    use proto::extensions::{
+        SimpleExtensionDeclaration, SimpleExtensionUrn,
+        simple_extension_declaration::{self as decl, MappingType},
+    };
+
+    /// Parse a type string into a [ConcreteType].
+    fn concrete(type_str: &str) -> ConcreteType {
+        ConcreteType::try_from(TypeExpr::parse(type_str).unwrap()).unwrap()
+    }
+
+    /// A context declaring the user-defined type `point` (anchor 1) and the
+    /// type variation `dict` (anchor 2).
+    fn ctx() -> ExtensionAnchors<'static> {
+        let mut ctx = ExtensionAnchors::default();
+        ctx.parse(SimpleExtensionUrn {
+            extension_urn_anchor: 1,
+            urn: "extension:substrait-rs:test".to_string(),
+        })
+        .expect("valid simple extension urn");
+        for mapping_type in [
+            MappingType::ExtensionType(decl::ExtensionType {
+                extension_urn_reference: 1,
+                type_anchor: 1,
+                name: "point".to_string(),
+                ..Default::default()
+            }),
+            MappingType::ExtensionTypeVariation(decl::ExtensionTypeVariation {
+                extension_urn_reference: 1,
+                type_variation_anchor: 2,
+                name: "dict".to_string(),
+                ..Default::default()
+            }),
+        ] {
+            ctx.parse(SimpleExtensionDeclaration {
+                mapping_type: Some(mapping_type),
+            })
+            .expect("valid declaration");
+        }
+        ctx
+    }
+
+    #[test]
+    fn round_trip() -> Result<(), TypeError> {
+        for type_str in [
+            "bool",
+            "i8?",
+            "i16",
+            "i32",
+            "i64?",
+            "fp32",
+            "fp64",
+            "string?",
+            "binary",
+            "timestamp",
+            "timestamp_tz",
+            "date",
+            "time",
+            "interval_year",
+            "uuid",
+            "fixedchar<3>",
+            "varchar?<255>",
+            "fixedbinary<16>",
+            "decimal<38, 10>",
+            "precision_time<3>",
+            "precision_timestamp<6>",
+            "precision_timestamp_tz?<9>",
+            "interval_day<6>",
+            "interval_compound<3>",
+            "list<i32?>",
+            "map?<string, list<fp64>>",
+            "struct<i32, string?>",
+        ] {
+            let concrete = concrete(type_str);
+            let proto = proto::Type::try_from(concrete.clone())?;
+            assert_eq!(ConcreteType::try_from(proto)?, concrete, "{type_str}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn user_defined() -> Result<(), TypeError> {
+        let mut ctx = ctx();
+        let proto = proto::Type {
+            kind: Some(Kind::UserDefined(proto::r#type::UserDefined {
+                type_reference: 1,
+                type_variation_reference: 2,
+                nullability: Nullability::Nullable as i32,
+                type_parameters: vec![
+                    proto_type_parameter(TypeParameter::Type(concrete("i32")), &ctx)?,
+                    proto_type_parameter(TypeParameter::Integer(3), &ctx)?,
+                    proto_type_parameter(TypeParameter::Enum("MODE_A".to_string()), &ctx)?,
+                    proto_type_parameter(TypeParameter::Null, &ctx)?,
+                ],
+            })),
+        };
+        let mut point = concrete("u!point?<i32, 3, MODE_A, null>").with_variation("dict");
+        // Unqualified names are looked up among all declared types.
+        assert_eq!(proto_type(point.clone(), &ctx)?, proto);
+
+        // Parsed types are qualified by the URN of their extension.
+        let test_urn: Urn = "extension:substrait-rs:test".parse().unwrap();
+        point.qualify(&|_| Some(test_urn.clone()));
+        assert_eq!(ctx.parse(proto.clone())?.into_concrete(), point);
+        assert_eq!(proto_type(point.clone(), &ctx)?, proto);
+
+        // Variations are looked up by name, and the URN if given.
+        assert_eq!(
+            ctx.find_extension_type_variation(Some(&test_urn), "dict"),
+            Ok(Anchor::new(2))
+        );
+        assert_eq!(
+            ctx.find_extension_type_variation(
+                Some(&"extension:substrait-rs:other".parse().unwrap()),
+                "dict"
+            ),
+            Err(ContextError::UndeclaredExtensionTypeVariation(
+                "dict".to_string()
+            ))
+        );
+
+        // Qualified names only match types of their extension.
+        let mut other = concrete("u!point");
+        other.qualify(&|_| "extension:substrait-rs:other".parse().ok());
+        assert_eq!(
+            proto_type(other, &ctx),
+            Err(TypeError::Context(ContextError::UndeclaredExtensionType(
+                "point".to_string()
+            )))
+        );
+
+        // Without declarations, the names do not resolve.
+        assert_eq!(
+            proto::Type::try_from(point.clone()),
+            Err(TypeError::Context(
+                ContextError::UndeclaredExtensionTypeVariation("dict".to_string())
+            ))
+        );
+        assert_eq!(
+            proto::Type::try_from(concrete("u!point")),
+            Err(TypeError::Context(ContextError::UndeclaredExtensionType(
+                "point".to_string()
+            )))
+        );
+
+        // Without declarations, the references do not resolve.
+        assert_eq!(
+            ConcreteType::try_from(proto),
+            Err(TypeError::Context(ContextError::UndefinedExtensionType(
+                Anchor::new(1)
+            )))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid() {
+        let i32_type = |nullability: i32, type_variation_reference: u32| proto::Type {
+            kind: Some(Kind::I32(proto::r#type::I32 {
+                type_variation_reference,
+                nullability,
+            })),
+        };
+
+        let mut ctx = ctx();
+        assert_eq!(ctx.parse(proto::Type::default()), Err(TypeError::Missing));
+        assert_eq!(
+            ctx.parse(i32_type(Nullability::Unspecified as i32, 0)),
+            Err(TypeError::Nullability(0))
+        );
+        assert_eq!(
+            ctx.parse(i32_type(Nullability::Required as i32, 3)),
+            Err(TypeError::Context(
+                ContextError::UndefinedExtensionTypeVariation(Anchor::new(3))
+            ))
+        );
+        assert!(matches!(
+            ctx.parse(proto::Type {
+                kind: Some(Kind::Decimal(proto::r#type::Decimal {
+                    precision: 10,
+                    scale: 12,
+                    nullability: Nullability::Required as i32,
+                    ..Default::default()
+                })),
+            }),
+            Err(TypeError::InvalidType(
+                ExtensionTypeError::InvalidParameterRange { .. }
+            ))
+        ));
+        assert_eq!(
+            ctx.parse(proto::Type {
+                kind: Some(Kind::List(Box::default())),
+            }),
+            Err(TypeError::MissingListType)
+        );
+    }
+}
@@ -0,0 +1,383 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Aggregate function definitions with validated signatures and resolved types.
+//!
+//! Aggregate function implementations share their arguments, options and
+//! return type with scalar function implementations ([`Impl`]), and add the
+//! properties that describe how the aggregation is computed.
+
+use crate::text::simple_extensions::{
+    AggregateFunction as RawAggregateFunction, AggregateFunctionImplsItem as RawAggregateImpl,
+    Decomposable as RawDecomposable, Intermediate as RawIntermediate, Maxset as RawMaxset,
+    Ordered as RawOrdered, ScalarFunctionImplsItem as RawImpl,
+};
+
+use super::derivation::TypeDerivation;
+use super::diagnostics::FunctionKind;
+use super::extensions::TypeContext;
+use super::scalar_functions::{Impl, ScalarFunctionError, parse_return_type, raw_return_type};
+use thiserror::Error;
+
+/// Errors that can occur when parsing aggregate functions
+#[derive(Debug, Error, PartialEq)]
+pub enum AggregateFunctionError {
+    /// Aggregate function has no implementations
+    #[error("Aggregate function '{name}' must have at least one implementation")]
+    NoImplementations {
+        /// The function name
+        name: String,
+    },
+    /// Invalid maximum set size
+    #[error("Aggregate maxset must be a positive integer, got {value}")]
+    InvalidMaxset {
+        /// The invalid value
+        value: f64,
+    },
+    /// A decomposable aggregation does not declare its intermediate type
+    #[error("Decomposable aggregate implementation must declare an intermediate type")]
+    MissingIntermediate,
+    /// Error parsing the properties shared with scalar function
+    /// implementations
+    #[error(transparent)]
+    Impl(#[from] ScalarFunctionError),
+}
+
+impl AggregateFunctionError {
+    /// Attach the index of the implementation an error occurred in, see
+    /// [`ScalarFunctionError::in_impl`].
+    pub(super) fn in_impl(self, index: usize) -> Self {
+        match self {
+            AggregateFunctionError::Impl(err) => AggregateFunctionError::Impl(err.in_impl(index)),
+            err => err,
+        }
+    }
+}
+
+/// A validated aggregate function definition with one or more implementations
+#[derive(Clone, Debug, PartialEq)]
+pub struct AggregateFunction {
+    /// Function name
+    pub name: String,
+    /// Human-readable description
+    pub description: Option<String>,
+    /// Function implementations (overloads)
+    pub impls: Vec<AggregateImpl>,
+    /// The `metadata` of the aggregate function declaration, merged across
+    /// repeated declarations like [`ScalarFunction::metadata`](super::ScalarFunction::metadata).
+    pub metadata: serde_json::Map<String, serde_json::Value>,
+}
+
+impl AggregateFunction {
+    /// Parse an aggregate function from raw YAML, resolving types with the
+    /// provided context
+    ///
+    /// Implementations using features that are not yet supported are skipped,
+    /// see [`ParseOptions::strict`](super::ParseOptions::strict). Returns
+    /// `None` if all implementations are skipped.
+    pub(super) fn from_raw(
+        raw: RawAggregateFunction,
+        ctx: &mut TypeContext,
+    ) -> Result<Option<Self>, AggregateFunctionError> {
+        if raw.impls.is_empty() {
+            return Err(AggregateFunctionError::NoImplementations { name: raw.name });
+        }
+
+        let mut impls = Vec::new();
+        for (index, impl_) in raw.impls.into_iter().enumerate() {
+            match AggregateImpl::from_raw(impl_, ctx) {
+                Ok(impl_) => impls.push(impl_),
+                Err(AggregateFunctionError::Impl(ScalarFunctionError::NotYetImplemented(
+                    reason,
+                ))) if !ctx.is_strict() => {
+                    ctx.skip_impl(FunctionKind::Aggregate, &raw.name, index, reason);
+                }
+                Err(err) => return Err(err.in_impl(index)),
+            }
+        }
+
+        Ok((!impls.is_empty()).then(|| AggregateFunction {
+            name: raw.name,
+            description: raw.description,
+            impls,
+            metadata: raw.metadata,
+        }))
+    }
+
+    /// Get the implementation with the given signature, i.e. the part of a
+    /// compound function name after the `:` (e.g. `i32` for `sum:i32`).
+    pub fn get_impl(&self, signature: &str) -> Option<&AggregateImpl> {
+        self.impls
+            .iter()
+            .find(|impl_| impl_.impl_.signature() == signature)
+    }
+}
+
+impl From<AggregateFunction> for RawAggregateFunction {
+    fn from(function: AggregateFunction) -> Self {
+        RawAggregateFunction {
+            name: function.name,
+            description: function.description,
+            metadata: function.metadata,
+            impls: function.impls.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// A single aggregate function implementation (overload)
+#[derive(Clone, Debug, PartialEq)]
+pub struct AggregateImpl {
+    /// Arguments, options, return type and other properties shared with
+    /// scalar function implementations
+    pub impl_: Impl,
+    /// The type of the intermediate result of a decomposed aggregation.
+    ///
+    /// Required when the function is [decomposable](Self::decomposable).
+    pub intermediate: Option<TypeDerivation>,
+    /// Whether the aggregation can be split into partial aggregations that
+    /// are combined afterwards.
+    ///
+    /// Defaults to [`Decomposable::None`] per the Substrait spec.
+    pub decomposable: Decomposable,
+    /// Whether the result depends on the order of the input records.
+    ///
+    /// Defaults to `false` per the Substrait spec.
+    pub ordered: bool,
+    /// The maximum number of values returned, for functions that return a set
+    /// of values (e.g. `top_n`).
+    pub maxset: Option<u64>,
+}
+
+impl AggregateImpl {
+    /// Parse an implementation from raw YAML, resolving types with the
+    /// provided context
+    pub(super) fn from_raw(
+        raw: RawAggregateImpl,
+        ctx: &mut TypeContext,
+    ) -> Result<Self, AggregateFunctionError> {
+        let RawAggregateImpl {
+            args,
+            decomposable,
+            deterministic,
+            implementation,
+            intermediate,
+            maxset,
+            nullability,
+            options,
+            ordered,
+            return_,
+            session_dependent,
+            variadic,
+        } = raw;
+
+        // The properties shared with scalar functions are parsed as such.
+        let impl_ = Impl::from_raw(
+            RawImpl {
+                args,
+                options,
+                variadic,
+                session_dependent,
+                deterministic,
+                nullability,
+                return_,
+                implementation,
+            },
+            ctx,
+        )?;
+
+        let intermediate = intermediate
+            .map(|RawIntermediate(t)| parse_return_type(t, ctx))
+            .transpose()?;
+
+        let decomposable = decomposable.map(Into::into).unwrap_or(Decomposable::None);
+        if decomposable != Decomposable::None && intermediate.is_none() {
+            return Err(AggregateFunctionError::MissingIntermediate);
+        }
+
+        let maxset = maxset
+            .map(|RawMaxset(value)| {
+                if value < 1.0 || value.fract() != 0.0 {
+                    return Err(AggregateFunctionError::InvalidMaxset { value });
+                }
+                Ok(value as u64)
+            })
+            .transpose()?;
+
+        Ok(AggregateImpl {
+            impl_,
+            intermediate,
+            decomposable,
+            ordered: ordered.map(|RawOrdered(b)| b).unwrap_or(false),
+            maxset,
+        })
+    }
+}
+
+impl From<AggregateImpl> for RawAggregateImpl {
+    fn from(aggregate_impl: AggregateImpl) -> Self {
+        let AggregateImpl {
+            impl_,
+            intermediate,
+            decomposable,
+            ordered,
+            maxset,
+        } = aggregate_impl;
+        let RawImpl {
+            args,
+            options,
+            variadic,
+            session_dependent,
+            deterministic,
+            nullability,
+            return_,
+            implementation,
+        } = impl_.into();
+
+        RawAggregateImpl {
+            args,
+            decomposable: (decomposable != Decomposable::None).then(|| decomposable.into()),
+            deterministic,
+            implementation,
+            intermediate: intermediate.map(|t| RawIntermediate(raw_return_type(t))),
+            maxset: maxset.map(|value| RawMaxset(value as f64)),
+            nullability,
+            options,
+            ordered: ordered.then_some(RawOrdered(true)),
+            return_,
+            session_dependent,
+            variadic,
+        }
+    }
+}
+
+/// Whether an aggregation can be decomposed into partial aggregations
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Decomposable {
+    /// The aggregation cannot be decomposed
+    None,
+    /// Each partial aggregation produces a single intermediate value, which
+    /// can be combined once
+    One,
+    /// Partial aggregations can be combined any number of times
+    Many,
+}
+
+impl From<RawDecomposable> for Decomposable {
+    fn from(raw: RawDecomposable) -> Self {
+        match raw {
+            RawDecomposable::None => Decomposable::None,
+            RawDecomposable::One => Decomposable::One,
+            RawDecomposable::Many => Decomposable::Many,
+        }
+    }
+}
+
+impl From<Decomposable> for RawDecomposable {
+    fn from(decomposable: Decomposable) -> Self {
+        match decomposable {
+            Decomposable::None => RawDecomposable::None,
+            Decomposable::One => RawDecomposable::One,
+            Decomposable::Many => RawDecomposable::Many,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::text::simple_extensions::{ExtensionFile, SimpleExtensionsError};
+
+    const YAML: &str = r#"
+%YAML 1.2
+---
+urn: extension:example.com:aggregates
+aggregate_functions:
+  - name: sum
+    description: Sum a set of values.
+    impls:
+      - args:
+          - name: x
+            value: i64
+        options:
+          overflow:
+            values: [SILENT, SATURATE, ERROR]
+        nullability: DECLARED_OUTPUT
+        decomposable: MANY
+        intermediate: i64?
+        return: i64?
+      - args:
+          - name: x
+            value: DECIMAL<P, S>
+        nullability: DECLARED_OUTPUT
+        decomposable: MANY
+        intermediate: DECIMAL?<38,S>
+        return: DECIMAL?<38,S>
+  - name: top_n
+    impls:
+      - args:
+          - value: any1
+        ordered: true
+        maxset: 10
+        return: list<any1>
+"#;
+
+    #[test]
+    fn parse_aggregate_functions() {
+        let file = ExtensionFile::read_from_str(YAML).unwrap();
+        let sum = file.extension().get_aggregate_function("sum").unwrap();
+        assert_eq!(sum.description.as_deref(), Some("Sum a set of values."));
+        assert_eq!(sum.impls.len(), 2);
+
+        let sum_i64 = sum.get_impl("i64").unwrap();
+        assert_eq!(sum_i64.decomposable, Decomposable::Many);
+        assert_eq!(
+            sum_i64.intermediate.as_ref().map(ToString::to_string),
+            Some("i64?".to_string())
+        );
+        assert!(!sum_i64.ordered);
+        assert_eq!(sum_i64.maxset, None);
+        assert_eq!(sum_i64.impl_.return_type.to_string(), "i64?");
+
+        let top_n = file.extension().get_aggregate_function("top_n").unwrap();
+        assert_eq!(top_n.impls[0].decomposable, Decomposable::None);
+        assert!(top_n.impls[0].ordered);
+        assert_eq!(top_n.impls[0].maxset, Some(10));
+    }
+
+    #[test]
+    fn round_trip() {
+        let file = ExtensionFile::read_from_str(YAML).unwrap();
+        let raw = file.to_raw();
+        assert_eq!(raw.aggregate_functions.len(), 2);
+
+        let yaml = serde_yaml::to_string(&raw).unwrap();
+        let parsed = ExtensionFile::read_from_str(yaml).unwrap();
+        for name in ["sum", "top_n"] {
+            assert_eq!(
+                parsed.extension().get_aggregate_function(name),
+                file.extension().get_aggregate_function(name)
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_maxset() {
+        let yaml = YAML.replace("maxset: 10", "maxset: 0.5");
+        assert!(matches!(
+            ExtensionFile::read_from_str(yaml),
+            Err(SimpleExtensionsError::AggregateFunctionError(
+                AggregateFunctionError::InvalidMaxset { value }
+            )) if value == 0.5
+        ));
+    }
+
+    #[test]
+    fn missing_intermediate() {
+        let yaml = YAML.replace("intermediate: i64?\n", "");
+        assert!(matches!(
+            ExtensionFile::read_from_str(yaml),
+            Err(SimpleExtensionsError::AggregateFunctionError(
+                AggregateFunctionError::MissingIntermediate
+            ))
+        ));
+    }
+}
@@ -53,6 +53,68 @@ impl ArgumentsItem {
     ) -> Result<Option<String>, ArgumentsItemError> {
         ArgumentsItem::parse_optional_string("description", description)
     }
+
+    /// Returns the short name of this argument, as used in the signature part
+    /// of compound function names (e.g. `i32` in `add:i32_i32`).
+    ///
+    /// Value and type arguments use the short name of their type (e.g. `str`
+    /// for `string`, `dec` for `DECIMAL<P, S>`), enumeration arguments use
+    /// `req`.
+    pub fn shorthand(&self) -> String {
+        match self {
+            // Enumeration arguments are always required.
+            ArgumentsItem::EnumArgument(_) => "req".to_string(),
+            ArgumentsItem::ValueArgument(arg) => match &arg.value {
+                simple_extensions::Type::String(type_str) => type_shorthand(type_str),
+                simple_extensions::Type::Object(_) => "struct".to_string(),
+            },
+            ArgumentsItem::TypeArgument(arg) => type_shorthand(&arg.type_),
+        }
+    }
+}
+
+/// Returns the short name of a type string, as used in the signature part of
+/// compound function names.
+///
+/// Parameters, nullability and variations do not contribute to the short
+/// name, e.g. `DECIMAL<P1, S1>?` becomes `dec`. Type variables (`any1`) map to
+/// `any`, and user-defined types keep their `u!` prefix and the case of their
+/// name.
+fn type_shorthand(type_str: &str) -> String {
+    let type_str = type_str.trim();
+    let end = type_str.find(['<', '?', '[']).unwrap_or(type_str.len());
+    let name = &type_str[..end];
+
+    if name.starts_with("u!") {
+        return name.to_string();
+    }
+    let name = name.to_ascii_lowercase();
+    if name
+        .strip_prefix("any")
+        .is_some_and(|suffix| suffix.chars().all(|c| c.is_ascii_digit()))
+    {
+        return "any".to_string();
+    }
+
+    match name.as_str() {
+        "boolean" => "bool",
+        "string" => "str",
+        "binary" => "vbin",
+        "timestamp" => "ts",
+        "timestamp_tz" => "tstz",
+        "interval_year" => "iyear",
+        "interval_day" => "iday",
+        "interval_compound" => "icompound",
+        "fixedchar" => "fchar",
+        "varchar" => "vchar",
+        "fixedbinary" => "fbin",
+        "decimal" => "dec",
+        "precisiontime" | "precision_time" => "pt",
+        "precision_timestamp" => "pts",
+        "precision_timestamp_tz" => "ptstz",
+        other => other,
+    }
+    .to_string()
 }
 
 impl<C: Context> Parse<C> for simple_extensions::ArgumentsItem {
@@ -240,7 +302,8 @@ pub struct ValueArg {
 
     /// A fully defined type or a type expression.
     ///
-    /// TODO(#452): parse this to a typed representation once type variables are supported.
+    /// Type expressions may contain type variables, and are parsed to a
+    /// [`super::SignatureType`] during overload resolution.
     value: simple_extensions::Type,
 
     /// Whether this argument is required to be a constant for invocation. For
@@ -264,6 +327,13 @@ impl ValueArg {
         self.description.as_ref()
     }
 
+    /// Returns the type of this argument, as a type string or a struct.
+    ///
+    /// See [`simple_extensions::ValueArg::value`].
+    pub fn value(&self) -> &simple_extensions::Type {
+        &self.value
+    }
+
     /// Returns the constant of this argument.
     /// Defaults to `false` if the underlying value is `None`.
     ///
@@ -389,6 +459,27 @@ mod tests {
 
     impl Context for TestContext {}
 
+    #[test]
+    fn type_shorthand() {
+        let cases = [
+            ("i32", "i32"),
+            ("i32?", "i32"),
+            ("boolean", "bool"),
+            ("string", "str"),
+            ("VARCHAR<L1>", "vchar"),
+            ("DECIMAL<P1,S1>", "dec"),
+            ("precision_timestamp_tz<P>?", "ptstz"),
+            ("any1", "any"),
+            ("any", "any"),
+            ("list<any1>", "list"),
+            ("u!geometry", "u!geometry"),
+            ("u!MyType?", "u!MyType"),
+        ];
+        for (type_str, expected) in cases {
+            assert_eq!(super::type_shorthand(type_str), expected, "{type_str}");
+        }
+    }
+
     #[test]
     fn parse_enum_argument() -> Result<(), ArgumentsItemError> {
         let enum_argument =
@@ -0,0 +1,852 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! A builder for authoring extension files: [`ExtensionFileBuilder`].
+//!
+//! The builder fills in the raw text representation of an extension file,
+//! which is validated by [`ExtensionFileBuilder::build`] like a file read
+//! from YAML.
+
+use super::{
+    CustomType, Decomposable, ExtensionFile, NullabilityHandling, SimpleExtensionsError,
+    VariadicBehavior, VariationFunctions, WindowType,
+};
+use crate::text::simple_extensions::{
+    AggregateFunction as RawAggregateFunction, AggregateFunctionImplsItem as RawAggregateImpl,
+    Arguments as RawArguments, ArgumentsItem as RawArgumentsItem,
+    Deterministic as RawDeterministic, EnumOptions as RawEnumOptions,
+    EnumerationArg as RawEnumerationArg, Implementation as RawImplementation,
+    Intermediate as RawIntermediate, Maxset as RawMaxset, Options as RawOptions,
+    OptionsValue as RawOptionsValue, Ordered as RawOrdered, ReturnValue as RawReturnValue,
+    ScalarFunction as RawScalarFunction, ScalarFunctionImplsItem as RawImpl,
+    SessionDependent as RawSessionDependent, SimpleExtensions as RawExtensions,
+    SimpleExtensionsTypeVariationsItem as RawTypeVariation,
+    SimpleExtensionsTypesItem as RawTypeItem, Type as RawType, TypeArg as RawTypeArg,
+    TypeParamDefs as RawTypeParamDefs, TypeParamDefsItem as RawTypeParamDefsItem,
+    TypeParamDefsItemType as RawTypeParamType, ValueArg as RawValueArg,
+    WindowFunction as RawWindowFunction, WindowFunctionImplsItem as RawWindowImpl,
+};
+use crate::urn::Urn;
+use serde_json::{Map, Value};
+
+/// A builder for an [`ExtensionFile`].
+///
+/// ```
+/// # use substrait::parse::text::simple_extensions::ExtensionFileBuilder;
+/// # use std::str::FromStr;
+/// # use substrait::urn::Urn;
+/// let urn = Urn::from_str("extension:example.com:math").unwrap();
+/// let mut builder = ExtensionFileBuilder::new(urn);
+/// builder
+///     .scalar_function("add")
+///     .impl_(["i32", "i32"], "i32")
+///     .impl_(["i64", "i64"], "i64");
+/// let file = builder.build().unwrap();
+/// assert_eq!(file.extension().get_scalar_function("add").unwrap().impls.len(), 2);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ExtensionFileBuilder {
+    /// The extension file, in the raw text representation
+    raw: RawExtensions,
+    /// Dependencies of the extension file, by alias. Aliases are validated
+    /// when building.
+    dependencies: Vec<(String, Urn)>,
+}
+
+impl ExtensionFileBuilder {
+    /// Create a builder for an empty extension file with the given [`Urn`].
+    pub fn new(urn: Urn) -> Self {
+        Self {
+            raw: RawExtensions {
+                urn: urn.to_string(),
+                aggregate_functions: Vec::new(),
+                dependencies: Default::default(),
+                metadata: Default::default(),
+                scalar_functions: Vec::new(),
+                type_variations: Vec::new(),
+                types: Vec::new(),
+                window_functions: Vec::new(),
+            },
+            dependencies: Vec::new(),
+        }
+    }
+
+    /// Declare a dependency on another extension file, referred to by `alias`
+    /// (e.g. as `u!alias.name` for types).
+    pub fn dependency(&mut self, alias: impl Into<String>, urn: Urn) -> &mut Self {
+        self.dependencies.push((alias.into(), urn));
+        self
+    }
+
+    /// Add a metadata entry to the extension file.
+    pub fn metadata(&mut self, key: impl Into<String>, value: Value) -> &mut Self {
+        self.raw.metadata.insert(key.into(), value);
+        self
+    }
+
+    /// Add a type definition, returning a builder to define it.
+    pub fn type_(&mut self, name: impl Into<String>) -> TypeBuilder<'_> {
+        self.raw.types.push(RawTypeItem {
+            name: name.into(),
+            description: None,
+            metadata: Default::default(),
+            parameters: None,
+            structure: None,
+            variadic: None,
+        });
+        TypeBuilder(self.raw.types.last_mut().expect("type was added"))
+    }
+
+    /// Add a scalar function, returning a builder to define its
+    /// implementations.
+    pub fn scalar_function(&mut self, name: impl Into<String>) -> ScalarFunctionBuilder<'_> {
+        self.raw.scalar_functions.push(RawScalarFunction {
+            name: name.into(),
+            description: None,
+            metadata: Default::default(),
+            impls: Vec::new(),
+        });
+        ScalarFunctionBuilder(
+            self.raw
+                .scalar_functions
+                .last_mut()
+                .expect("function was added"),
+        )
+    }
+
+    /// Add an aggregate function, returning a builder to define its
+    /// implementations.
+    pub fn aggregate_function(&mut self, name: impl Into<String>) -> AggregateFunctionBuilder<'_> {
+        self.raw.aggregate_functions.push(RawAggregateFunction {
+            name: name.into(),
+            description: None,
+            metadata: Default::default(),
+            impls: Vec::new(),
+        });
+        AggregateFunctionBuilder(
+            self.raw
+                .aggregate_functions
+                .last_mut()
+                .expect("function was added"),
+        )
+    }
+
+    /// Add a window function, returning a builder to define its
+    /// implementations.
+    pub fn window_function(&mut self, name: impl Into<String>) -> WindowFunctionBuilder<'_> {
+        self.raw.window_functions.push(RawWindowFunction {
+            name: name.into(),
+            description: None,
+            metadata: Default::default(),
+            impls: Vec::new(),
+        });
+        WindowFunctionBuilder(
+            self.raw
+                .window_functions
+                .last_mut()
+                .expect("function was added"),
+        )
+    }
+
+    /// Add a type variation of a parent type (e.g. `i32`), returning a
+    /// builder to define it.
+    pub fn type_variation(
+        &mut self,
+        name: impl Into<String>,
+        parent: impl Into<String>,
+    ) -> TypeVariationBuilder<'_> {
+        self.raw.type_variations.push(RawTypeVariation {
+            name: name.into(),
+            parent: RawType::String(parent.into()),
+            description: None,
+            functions: None,
+            metadata: Default::default(),
+        });
+        TypeVariationBuilder(
+            self.raw
+                .type_variations
+                .last_mut()
+                .expect("type variation was added"),
+        )
+    }
+
+    /// Returns the extension file in the raw text representation. The
+    /// dependency aliases are validated, the rest of the file is not (see
+    /// [`ExtensionFileBuilder::build`]).
+    pub fn to_raw(&self) -> Result<RawExtensions, SimpleExtensionsError> {
+        let mut raw = self.raw.clone();
+        for (alias, urn) in &self.dependencies {
+            let invalid = || SimpleExtensionsError::InvalidDependencyAlias {
+                alias: alias.clone(),
+            };
+            CustomType::validate_name(alias).map_err(|_| invalid())?;
+            let key = alias.parse().map_err(|_| invalid())?;
+            raw.dependencies.insert(key, urn.to_string());
+        }
+        Ok(raw)
+    }
+
+    /// Build the [`ExtensionFile`], validating it like a file read from YAML.
+    pub fn build(&self) -> Result<ExtensionFile, SimpleExtensionsError> {
+        ExtensionFile::create(self.to_raw()?)
+    }
+}
+
+/// A builder for a type definition of an [`ExtensionFileBuilder`].
+#[derive(Debug)]
+pub struct TypeBuilder<'a>(&'a mut RawTypeItem);
+
+impl TypeBuilder<'_> {
+    /// Set the description of the type.
+    pub fn description(self, description: impl Into<String>) -> Self {
+        self.0.description = Some(description.into());
+        self
+    }
+
+    /// Set the structure of the type, as a type string (e.g. `struct<i32,
+    /// i32>`).
+    pub fn structure(self, structure: impl Into<String>) -> Self {
+        self.0.structure = Some(RawType::String(structure.into()));
+        self
+    }
+
+    /// Add a field to the structure of the type, which is a named struct.
+    ///
+    /// Replaces a structure set with [`TypeBuilder::structure`].
+    pub fn field(self, name: impl Into<String>, type_: impl Into<String>) -> Self {
+        if !matches!(self.0.structure, Some(RawType::Object(_))) {
+            self.0.structure = Some(RawType::Object(Map::new()));
+        }
+        if let Some(RawType::Object(fields)) = &mut self.0.structure {
+            fields.insert(name.into(), Value::String(type_.into()));
+        }
+        self
+    }
+
+    /// Add a type parameter (e.g. `T` for `u!box<T>`).
+    pub fn type_parameter(self, name: impl Into<String>) -> Self {
+        self.parameter(name, RawTypeParamType::DataType, None, None)
+    }
+
+    /// Add an integer parameter, with optional bounds (inclusive).
+    ///
+    /// The bounds are written as YAML numbers, which are read back as `f64`:
+    /// bounds beyond ±2^53 are rounded to the nearest representable value.
+    pub fn integer_parameter(
+        self,
+        name: impl Into<String>,
+        min: Option<i64>,
+        max: Option<i64>,
+    ) -> Self {
+        self.parameter(name, RawTypeParamType::Integer, min, max)
+    }
+
+    /// Add a boolean parameter.
+    pub fn boolean_parameter(self, name: impl Into<String>) -> Self {
+        self.parameter(name, RawTypeParamType::Boolean, None, None)
+    }
+
+    /// Add a string parameter.
+    pub fn string_parameter(self, name: impl Into<String>) -> Self {
+        self.parameter(name, RawTypeParamType::String, None, None)
+    }
+
+    /// Add an enumeration parameter, with its possible values.
+    pub fn enum_parameter<S: Into<String>>(
+        self,
+        name: impl Into<String>,
+        options: impl IntoIterator<Item = S>,
+    ) -> Self {
+        let options = RawEnumOptions(options.into_iter().map(Into::into).collect());
+        let this = self.parameter(name, RawTypeParamType::Enumeration, None, None);
+        if let Some(parameter) = this.0.parameters.as_mut().and_then(|p| p.0.last_mut()) {
+            parameter.options = Some(options);
+        }
+        this
+    }
+
+    /// Set whether the last parameter of the type is variadic.
+    pub fn variadic(self, variadic: bool) -> Self {
+        self.0.variadic = Some(variadic);
+        self
+    }
+
+    /// Add a metadata entry to the type.
+    pub fn metadata(self, key: impl Into<String>, value: Value) -> Self {
+        self.0.metadata.insert(key.into(), value);
+        self
+    }
+
+    fn parameter(
+        self,
+        name: impl Into<String>,
+        type_: RawTypeParamType,
+        min: Option<i64>,
+        max: Option<i64>,
+    ) -> Self {
+        let parameters = self
+            .0
+            .parameters
+            .get_or_insert_with(|| RawTypeParamDefs(Vec::new()));
+        parameters.0.push(RawTypeParamDefsItem {
+            name: Some(name.into()),
+            description: None,
+            type_,
+            min: min.map(|min| min as f64),
+            max: max.map(|max| max as f64),
+            options: None,
+            optional: None,
+        });
+        self
+    }
+}
+
+/// A builder for a type variation of an [`ExtensionFileBuilder`].
+#[derive(Debug)]
+pub struct TypeVariationBuilder<'a>(&'a mut RawTypeVariation);
+
+impl TypeVariationBuilder<'_> {
+    /// Set the description of the type variation.
+    pub fn description(self, description: impl Into<String>) -> Self {
+        self.0.description = Some(description.into());
+        self
+    }
+
+    /// Set how the functions of the parent type apply to the type variation.
+    pub fn functions(self, functions: VariationFunctions) -> Self {
+        self.0.functions = Some(functions.into());
+        self
+    }
+
+    /// Add a metadata entry to the type variation.
+    pub fn metadata(self, key: impl Into<String>, value: Value) -> Self {
+        self.0.metadata.insert(key.into(), value);
+        self
+    }
+}
+
+/// A builder for a scalar function of an [`ExtensionFileBuilder`].
+#[derive(Debug)]
+pub struct ScalarFunctionBuilder<'a>(&'a mut RawScalarFunction);
+
+impl<'a> ScalarFunctionBuilder<'a> {
+    /// Set the description of the function.
+    pub fn description(self, description: impl Into<String>) -> Self {
+        self.0.description = Some(description.into());
+        self
+    }
+
+    /// Add a metadata entry to the function.
+    pub fn metadata(self, key: impl Into<String>, value: Value) -> Self {
+        self.0.metadata.insert(key.into(), value);
+        self
+    }
+
+    /// Add an implementation with value arguments of the given types (e.g.
+    /// `["i32", "any1"]`) and return type, returning a builder to define the
+    /// implementation further.
+    pub fn impl_<S: Into<String>>(
+        self,
+        args: impl IntoIterator<Item = S>,
+        return_type: impl Into<String>,
+    ) -> ImplBuilder<'a> {
+        self.0.impls.push(RawImpl {
+            args: value_args(args),
+            options: None,
+            variadic: None,
+            session_dependent: None,
+            deterministic: None,
+            nullability: None,
+            return_: RawReturnValue(RawType::String(return_type.into())),
+            implementation: None,
+        });
+        ImplBuilder(self.0)
+    }
+}
+
+/// A builder for an implementation of a scalar function, returned by
+/// [`ScalarFunctionBuilder::impl_`].
+#[derive(Debug)]
+pub struct ImplBuilder<'a>(&'a mut RawScalarFunction);
+
+/// The methods shared by the builders of implementations of scalar,
+/// aggregate and window functions, whose raw implementations have the same
+/// fields.
+macro_rules! impl_builder_methods {
+    ($builder:ident, $function_builder:ident, $raw_impl:ty) => {
+        impl<'a> $builder<'a> {
+            fn raw(&mut self) -> &mut $raw_impl {
+                self.0.impls.last_mut().expect("implementation was added")
+            }
+
+            /// Name the value arguments of the implementation, in order.
+            ///
+            /// Enumeration and type arguments are named when they are added.
+            /// Fails if the number of names differs from the number of value
+            /// arguments.
+            pub fn arg_names<S: Into<String>>(
+                mut self,
+                names: impl IntoIterator<Item = S>,
+            ) -> Result<Self, SimpleExtensionsError> {
+                let names = names.into_iter().map(Into::into).collect::<Vec<String>>();
+                let args = self
+                    .raw()
+                    .args
+                    .iter_mut()
+                    .flat_map(|args| args.0.iter_mut())
+                    .filter_map(|arg| match arg {
+                        RawArgumentsItem::ValueArg(arg) => Some(arg),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                if args.len() != names.len() {
+                    return Err(SimpleExtensionsError::ArgumentNameCount {
+                        arguments: args.len(),
+                        names: names.len(),
+                    });
+                }
+                for (arg, name) in args.into_iter().zip(names) {
+                    arg.name = Some(name);
+                }
+                Ok(self)
+            }
+
+            /// Add a value argument of the given type.
+            pub fn value_arg(mut self, type_: impl Into<String>) -> Self {
+                let args = value_args([type_]).map_or_else(Vec::new, |args| args.0);
+                self.raw()
+                    .args
+                    .get_or_insert_with(|| RawArguments(Vec::new()))
+                    .0
+                    .extend(args);
+                self
+            }
+
+            /// Add an enumeration argument, with its possible values.
+            pub fn enum_arg<S: Into<String>>(
+                mut self,
+                name: impl Into<String>,
+                options: impl IntoIterator<Item = S>,
+            ) -> Self {
+                let arg = RawArgumentsItem::EnumerationArg(RawEnumerationArg {
+                    name: Some(name.into()),
+                    description: None,
+                    options: RawEnumOptions(options.into_iter().map(Into::into).collect()),
+                });
+                self.raw()
+                    .args
+                    .get_or_insert_with(|| RawArguments(Vec::new()))
+                    .0
+                    .push(arg);
+                self
+            }
+
+            /// Add a type argument, constrained by a type (e.g. `any1`).
+            pub fn type_arg(mut self, name: impl Into<String>, type_: impl Into<String>) -> Self {
+                let arg = RawArgumentsItem::TypeArg(RawTypeArg {
+                    name: Some(name.into()),
+                    description: None,
+                    type_: type_.into(),
+                });
+                self.raw()
+                    .args
+                    .get_or_insert_with(|| RawArguments(Vec::new()))
+                    .0
+                    .push(arg);
+                self
+            }
+
+            /// Add an option of the implementation, with its possible values.
+            pub fn option<S: Into<String>>(
+                mut self,
+                name: impl Into<String>,
+                values: impl IntoIterator<Item = S>,
+            ) -> Self {
+                let options = self
+                    .raw()
+                    .options
+                    .get_or_insert_with(|| RawOptions(Default::default()));
+                options.0.insert(
+                    name.into(),
+                    RawOptionsValue {
+                        description: None,
+                        values: values.into_iter().map(Into::into).collect(),
+                    },
+                );
+                self
+            }
+
+            /// Set the variadic behavior of the last argument.
+            pub fn variadic(mut self, variadic: VariadicBehavior) -> Self {
+                self.raw().variadic = Some(variadic.into());
+                self
+            }
+
+            /// Set whether the output depends on session state.
+            pub fn session_dependent(mut self, session_dependent: bool) -> Self {
+                self.raw().session_dependent = Some(RawSessionDependent(session_dependent));
+                self
+            }
+
+            /// Set whether the implementation is deterministic.
+            pub fn deterministic(mut self, deterministic: bool) -> Self {
+                self.raw().deterministic = Some(RawDeterministic(deterministic));
+                self
+            }
+
+            /// Set how the implementation handles null inputs.
+            pub fn nullability(mut self, nullability: NullabilityHandling) -> Self {
+                self.raw().nullability = Some(nullability.into());
+                self
+            }
+
+            /// Add implementation code for a language (e.g. `sql`).
+            pub fn implementation(
+                mut self,
+                language: impl Into<String>,
+                code: impl Into<String>,
+            ) -> Self {
+                let implementation = self
+                    .raw()
+                    .implementation
+                    .get_or_insert_with(|| RawImplementation(Default::default()));
+                implementation.0.insert(language.into(), code.into());
+                self
+            }
+
+            /// Add another implementation of the function; see
+            /// [`ScalarFunctionBuilder::impl_`].
+            pub fn impl_<S: Into<String>>(
+                self,
+                args: impl IntoIterator<Item = S>,
+                return_type: impl Into<String>,
+            ) -> $builder<'a> {
+                $function_builder(self.0).impl_(args, return_type)
+            }
+        }
+    };
+}
+
+/// The methods shared by the builders of implementations of aggregate and
+/// window functions.
+macro_rules! aggregate_builder_methods {
+    ($builder:ident) => {
+        impl $builder<'_> {
+            /// Set the type of the intermediate result of a decomposed
+            /// aggregation.
+            pub fn intermediate(mut self, type_: impl Into<String>) -> Self {
+                self.raw().intermediate = Some(RawIntermediate(RawType::String(type_.into())));
+                self
+            }
+
+            /// Set whether the aggregation can be decomposed into partial
+            /// aggregations. Decomposable aggregations must also set the
+            /// [intermediate](Self::intermediate) type.
+            pub fn decomposable(mut self, decomposable: Decomposable) -> Self {
+                self.raw().decomposable = Some(decomposable.into());
+                self
+            }
+
+            /// Set whether the result depends on the order of the input
+            /// records.
+            pub fn ordered(mut self, ordered: bool) -> Self {
+                self.raw().ordered = Some(RawOrdered(ordered));
+                self
+            }
+
+            /// Set the maximum number of values returned.
+            pub fn maxset(mut self, maxset: u64) -> Self {
+                self.raw().maxset = Some(RawMaxset(maxset as f64));
+                self
+            }
+        }
+    };
+}
+
+impl_builder_methods!(ImplBuilder, ScalarFunctionBuilder, RawImpl);
+
+/// A builder for an aggregate function of an [`ExtensionFileBuilder`].
+#[derive(Debug)]
+pub struct AggregateFunctionBuilder<'a>(&'a mut RawAggregateFunction);
+
+impl<'a> AggregateFunctionBuilder<'a> {
+    /// Set the description of the function.
+    pub fn description(self, description: impl Into<String>) -> Self {
+        self.0.description = Some(description.into());
+        self
+    }
+
+    /// Add a metadata entry to the function.
+    pub fn metadata(self, key: impl Into<String>, value: Value) -> Self {
+        self.0.metadata.insert(key.into(), value);
+        self
+    }
+
+    /// Add an implementation with value arguments of the given types and
+    /// return type; see [`ScalarFunctionBuilder::impl_`].
+    pub fn impl_<S: Into<String>>(
+        self,
+        args: impl IntoIterator<Item = S>,
+        return_type: impl Into<String>,
+    ) -> AggregateImplBuilder<'a> {
+        self.0.impls.push(RawAggregateImpl {
+            args: value_args(args),
+            options: None,
+            variadic: None,
+            session_dependent: None,
+            deterministic: None,
+            nullability: None,
+            return_: RawReturnValue(RawType::String(return_type.into())),
+            implementation: None,
+            decomposable: None,
+            intermediate: None,
+            maxset: None,
+            ordered: None,
+        });
+        AggregateImplBuilder(self.0)
+    }
+}
+
+/// A builder for an implementation of an aggregate function, returned by
+/// [`AggregateFunctionBuilder::impl_`].
+#[derive(Debug)]
+pub struct AggregateImplBuilder<'a>(&'a mut RawAggregateFunction);
+
+impl_builder_methods!(
+    AggregateImplBuilder,
+    AggregateFunctionBuilder,
+    RawAggregateImpl
+);
+aggregate_builder_methods!(AggregateImplBuilder);
+
+/// A builder for a window function of an [`ExtensionFileBuilder`].
+#[derive(Debug)]
+pub struct WindowFunctionBuilder<'a>(&'a mut RawWindowFunction);
+
+impl<'a> WindowFunctionBuilder<'a> {
+    /// Set the description of the function.
+    pub fn description(self, description: impl Into<String>) -> Self {
+        self.0.description = Some(description.into());
+        self
+    }
+
+    /// Add a metadata entry to the function.
+    pub fn metadata(self, key: impl Into<String>, value: Value) -> Self {
+        self.0.metadata.insert(key.into(), value);
+        self
+    }
+
+    /// Add an implementation with value arguments of the given types and
+    /// return type; see [`ScalarFunctionBuilder::impl_`].
+    pub fn impl_<S: Into<String>>(
+        self,
+        args: impl IntoIterator<Item = S>,
+        return_type: impl Into<String>,
+    ) -> WindowImplBuilder<'a> {
+        self.0.impls.push(RawWindowImpl {
+            args: value_args(args),
+            options: None,
+            variadic: None,
+            session_dependent: None,
+            deterministic: None,
+            nullability: None,
+            return_: RawReturnValue(RawType::String(return_type.into())),
+            implementation: None,
+            decomposable: None,
+            intermediate: None,
+            maxset: None,
+            ordered: None,
+            window_type: None,
+        });
+        WindowImplBuilder(self.0)
+    }
+}
+
+/// A builder for an implementation of a window function, returned by
+/// [`WindowFunctionBuilder::impl_`].
+#[derive(Debug)]
+pub struct WindowImplBuilder<'a>(&'a mut RawWindowFunction);
+
+impl_builder_methods!(WindowImplBuilder, WindowFunctionBuilder, RawWindowImpl);
+aggregate_builder_methods!(WindowImplBuilder);
+
+impl WindowImplBuilder<'_> {
+    /// Set the kind of window the function is evaluated over.
+    pub fn window_type(mut self, window_type: WindowType) -> Self {
+        self.raw().window_type = Some(window_type.into());
+        self
+    }
+}
+
+/// Value arguments of the given types, as raw arguments of an implementation.
+fn value_args<S: Into<String>>(args: impl IntoIterator<Item = S>) -> Option<RawArguments> {
+    let args = args
+        .into_iter()
+        .map(|arg| {
+            RawArgumentsItem::ValueArg(RawValueArg {
+                name: None,
+                description: None,
+                value: RawType::String(arg.into()),
+                constant: None,
+            })
+        })
+        .collect::<Vec<_>>();
+    (!args.is_empty()).then_some(RawArguments(args))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::text::simple_extensions::ExtensionTypeError;
+    use crate::parse::text::simple_extensions::scalar_functions::ScalarFunctionError;
+    use std::str::FromStr;
+
+    #[test]
+    fn build_extension_file() {
+        let urn = Urn::from_str("extension:example.com:builder").unwrap();
+        let mut builder = ExtensionFileBuilder::new(urn.clone());
+        builder
+            .metadata("owner", Value::from("example"))
+            .type_("point")
+            .description("A point")
+            .field("x", "i32")
+            .field("y", "i32");
+        builder
+            .scalar_function("distance")
+            .description("Distance between points")
+            .impl_(["u!point", "u!point"], "fp64")
+            .arg_names(["from", "to"])
+            .expect("two value arguments")
+            .option("metric", ["EUCLIDEAN", "MANHATTAN"])
+            .nullability(NullabilityHandling::DeclaredOutput)
+            .impl_(["u!point"], "fp64")
+            .deterministic(false);
+
+        let file = builder.build().expect("valid extension file");
+        assert_eq!(file.urn(), &urn);
+        assert!(file.get_type("point").is_some());
+        let distance = file
+            .extension()
+            .get_scalar_function("distance")
+            .expect("function exists");
+        assert_eq!(distance.impls.len(), 2);
+        assert_eq!(
+            distance.impls[0].nullability,
+            NullabilityHandling::DeclaredOutput
+        );
+        assert!(!distance.impls[1].deterministic);
+
+        // The built file round-trips through YAML
+        let yaml = file.to_yaml().expect("serializable");
+        let read = ExtensionFile::read_from_str(&yaml).expect("valid YAML");
+        assert_eq!(read.to_raw(), builder.to_raw().unwrap());
+    }
+
+    #[test]
+    fn build_functions_and_variations() {
+        let urn = Urn::from_str("extension:example.com:builder").unwrap();
+        let mut builder = ExtensionFileBuilder::new(urn);
+        builder
+            .type_("vector")
+            .type_parameter("T")
+            .integer_parameter("dimensions", Some(1), None)
+            .enum_parameter("layout", ["DENSE", "SPARSE"]);
+        builder
+            .type_variation("dict", "string")
+            .functions(VariationFunctions::Separate);
+        builder
+            .scalar_function("round")
+            .impl_(Vec::<String>::new(), "i32")
+            .enum_arg("rounding", ["FLOOR", "CEILING"])
+            .value_arg("fp64")
+            .type_arg("target", "any1")
+            .value_arg("i32")
+            .arg_names(["x", "digits"])
+            .expect("two value arguments");
+        builder
+            .aggregate_function("sum")
+            .impl_(["i64"], "i64?")
+            .decomposable(Decomposable::Many)
+            .intermediate("i64?")
+            .impl_(["u!vector<fp64, 3, DENSE>"], "u!vector?<fp64, 3, DENSE>")
+            .ordered(true)
+            .maxset(1);
+        builder
+            .window_function("rank")
+            .impl_(Vec::<String>::new(), "i64")
+            .window_type(WindowType::Partition);
+
+        let file = builder.build().expect("valid extension file");
+        let extension = file.extension();
+        assert_eq!(file.get_type("vector").unwrap().parameters.len(), 3);
+        assert_eq!(
+            extension.get_type_variation("dict").unwrap().functions,
+            VariationFunctions::Separate
+        );
+        let round = extension.get_scalar_function("round").unwrap();
+        assert_eq!(round.impls[0].args.len(), 4);
+        let raw = builder.to_raw().unwrap();
+        let names = raw.scalar_functions[0].impls[0]
+            .args
+            .iter()
+            .flat_map(|args| &args.0)
+            .map(|arg| match arg {
+                RawArgumentsItem::EnumerationArg(arg) => arg.name.as_deref(),
+                RawArgumentsItem::ValueArg(arg) => arg.name.as_deref(),
+                RawArgumentsItem::TypeArg(arg) => arg.name.as_deref(),
+            });
+        assert_eq!(
+            names.collect::<Vec<_>>(),
+            [Some("rounding"), Some("x"), Some("target"), Some("digits")]
+        );
+        let sum = extension.get_aggregate_function("sum").unwrap();
+        assert_eq!(sum.impls[0].decomposable, Decomposable::Many);
+        assert!(sum.impls[1].ordered);
+        assert_eq!(sum.impls[1].maxset, Some(1));
+        let rank = extension.get_window_function("rank").unwrap();
+        assert_eq!(rank.impls[0].window_type, WindowType::Partition);
+
+        // The built file round-trips through YAML
+        let yaml = file.to_yaml().expect("serializable");
+        let read = ExtensionFile::read_from_str(&yaml).expect("valid YAML");
+        assert_eq!(read.get_type("vector"), file.get_type("vector"));
+        assert_eq!(
+            read.extension().get_type_variation("dict"),
+            extension.get_type_variation("dict")
+        );
+        assert_eq!(read.extension().get_scalar_function("round"), Some(round));
+        assert_eq!(read.extension().get_aggregate_function("sum"), Some(sum));
+        assert_eq!(read.extension().get_window_function("rank"), Some(rank));
+    }
+
+    #[test]
+    fn build_invalid_extension_file() {
+        let urn = Urn::from_str("extension:example.com:builder").unwrap();
+        let mut builder = ExtensionFileBuilder::new(urn.clone());
+        builder.scalar_function("broken").impl_(["i32"], "point");
+        assert!(matches!(
+            builder.build(),
+            Err(SimpleExtensionsError::ScalarFunctionError(
+                ScalarFunctionError::TypeError(ExtensionTypeError::UnknownTypeName { name })
+            )) if name == "point"
+        ));
+
+        let mut builder = ExtensionFileBuilder::new(urn.clone());
+        assert!(matches!(
+            builder
+                .scalar_function("add")
+                .impl_(["i32", "i32"], "i32")
+                .arg_names(["x"]),
+            Err(SimpleExtensionsError::ArgumentNameCount {
+                arguments: 2,
+                names: 1
+            })
+        ));
+
+        let mut builder = ExtensionFileBuilder::new(urn.clone());
+        builder.dependency("not an alias", urn);
+        assert!(matches!(
+            builder.build(),
+            Err(SimpleExtensionsError::InvalidDependencyAlias { .. })
+        ));
+    }
+}
@@ -0,0 +1,896 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Type derivation expressions in function return types.
+//!
+//! The return type of a function can be derived from the parameters of its
+//! argument types, using a multiline type derivation:
+//!
+//! ```text
+//! init_scale = max(S1,S2)
+//! init_prec = init_scale + max(P1 - S1, P2 - S2) + 1
+//! prec = min(init_prec, 38)
+//! scale = init_prec > 38 ? max(init_scale - (init_prec - 38), 6) : init_scale
+//! DECIMAL<prec, scale>
+//! ```
+//!
+//! Each line but the last assigns the value of an expression to a name. The
+//! last line is the return type, which may refer to the assigned names and to
+//! the (integer) parameter variables bound by the arguments. Expressions
+//! support integer and boolean literals, arithmetic (`+`, `-`, `*`, `/`),
+//! comparisons, boolean logic (`&&`, `||`, `!`, `and`, `or`, `not`), the
+//! `max` and `min` functions, and ternaries (`cond ? then : else`).
+
+use std::collections::HashMap;
+use std::fmt;
+
+use thiserror::Error;
+
+use super::signature::{BindingError, SignatureType, TypeBindings};
+use super::type_ast::{TypeExpr, TypeParseError};
+use super::types::{ConcreteType, ExtensionTypeError};
+
+/// A parsed return type, with the assignments of a type derivation (if any).
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypeDerivation {
+    /// Assignments evaluated before instantiating the result type
+    assignments: Vec<Assignment>,
+    /// The result type
+    result: SignatureType,
+}
+
+/// An assignment in a [`TypeDerivation`], e.g. `prec = min(init_prec, 38)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Assignment {
+    /// The assigned name
+    pub name: String,
+    /// The assigned expression
+    pub expr: DerivationExpr,
+}
+
+/// An expression in a [`TypeDerivation`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum DerivationExpr {
+    /// Integer literal
+    Integer(i64),
+    /// Boolean literal (`true` or `false`)
+    Boolean(bool),
+    /// Reference to an assigned name or an integer parameter variable
+    Variable(String),
+    /// Unary operation
+    Unary(UnaryOp, Box<DerivationExpr>),
+    /// Binary operation
+    Binary(BinaryOp, Box<DerivationExpr>, Box<DerivationExpr>),
+    /// Function call (`max` or `min`)
+    Call(DerivationFunction, Vec<DerivationExpr>),
+    /// Ternary (`cond ? then : else`)
+    Ternary(
+        Box<DerivationExpr>,
+        Box<DerivationExpr>,
+        Box<DerivationExpr>,
+    ),
+}
+
+/// Unary operators of [`DerivationExpr`]s.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UnaryOp {
+    /// Integer negation - `-`
+    Neg,
+    /// Boolean negation - `!` or `not`
+    Not,
+}
+
+/// Binary operators of [`DerivationExpr`]s.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BinaryOp {
+    /// `+`
+    Add,
+    /// `-`
+    Sub,
+    /// `*`
+    Mul,
+    /// `/` (integer division)
+    Div,
+    /// `==`
+    Eq,
+    /// `!=`
+    Ne,
+    /// `<`
+    Lt,
+    /// `<=`
+    Le,
+    /// `>`
+    Gt,
+    /// `>=`
+    Ge,
+    /// `&&` or `and`
+    And,
+    /// `||` or `or`
+    Or,
+}
+
+/// Functions of [`DerivationExpr`]s.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DerivationFunction {
+    /// The largest of its arguments - `max`
+    Max,
+    /// The smallest of its arguments - `min`
+    Min,
+}
+
+/// The value of an evaluated [`DerivationExpr`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DerivationValue {
+    /// Integer value
+    Integer(i64),
+    /// Boolean value
+    Boolean(bool),
+}
+
+/// Errors that can occur when parsing or evaluating a [`TypeDerivation`].
+#[derive(Debug, Error, PartialEq)]
+pub enum DerivationError {
+    /// The type derivation is empty.
+    #[error("empty type derivation")]
+    Empty,
+    /// A line other than the last is not an assignment.
+    #[error("expected an assignment `name = expression`, got `{0}`")]
+    InvalidAssignment(String),
+    /// An expression contains an unexpected token.
+    #[error("unexpected `{token}` in expression `{expr}`")]
+    UnexpectedToken {
+        /// The unexpected token
+        token: String,
+        /// The expression
+        expr: String,
+    },
+    /// An expression ends unexpectedly.
+    #[error("unexpected end of expression `{0}`")]
+    UnexpectedEnd(String),
+    /// An unknown function is called.
+    #[error("unknown function `{0}`")]
+    UnknownFunction(String),
+    /// The result type cannot be parsed.
+    #[error("invalid result type: {0}")]
+    TypeParse(#[from] TypeParseError),
+    /// The result type is invalid.
+    #[error("invalid result type: {0}")]
+    Type(#[from] ExtensionTypeError),
+    /// A name is neither assigned nor bound.
+    #[error("undefined variable `{0}`")]
+    UndefinedVariable(String),
+    /// A value has the wrong type, e.g. a boolean in an arithmetic operation.
+    #[error("expected {expected} value in `{expr}`")]
+    ValueType {
+        /// The expected value type
+        expected: &'static str,
+        /// The expression
+        expr: String,
+    },
+    /// Integer division by zero.
+    #[error("division by zero in `{0}`")]
+    DivisionByZero(String),
+    /// A function is called without arguments.
+    #[error("function call without arguments in `{0}`")]
+    NoArguments(String),
+    /// Integer overflow.
+    #[error("integer overflow in `{0}`")]
+    Overflow(String),
+    /// The result type cannot be instantiated.
+    #[error(transparent)]
+    Binding(#[from] BindingError),
+}
+
+impl TypeDerivation {
+    /// Parse a return type, which is either a single type or a multiline type
+    /// derivation.
+    pub fn parse(return_type: &str) -> Result<Self, DerivationError> {
+        Self::parse_with(return_type, TypeExpr::parse)
+    }
+
+    /// Parse a return type, parsing the result type with `parse_type`.
+    pub(super) fn parse_with<'a>(
+        return_type: &'a str,
+        parse_type: impl FnOnce(&'a str) -> Result<TypeExpr<'a>, TypeParseError>,
+    ) -> Result<Self, DerivationError> {
+        let mut lines = return_type
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let result = lines.pop().ok_or(DerivationError::Empty)?;
+
+        let assignments = lines
+            .into_iter()
+            .map(|line| {
+                let (name, expr) = split_assignment(line)
+                    .ok_or_else(|| DerivationError::InvalidAssignment(line.to_string()))?;
+                Ok(Assignment {
+                    name: name.to_string(),
+                    expr: DerivationExpr::parse(expr)?,
+                })
+            })
+            .collect::<Result<Vec<_>, DerivationError>>()?;
+
+        let result = SignatureType::try_from(parse_type(result)?)?;
+
+        Ok(TypeDerivation {
+            assignments,
+            result,
+        })
+    }
+
+    /// Returns the assignments of this derivation, which are empty if the
+    /// return type is a single type.
+    pub fn assignments(&self) -> &[Assignment] {
+        &self.assignments
+    }
+
+    /// Returns the result type of this derivation.
+    pub fn result(&self) -> &SignatureType {
+        &self.result
+    }
+
+    /// Evaluate this derivation with the variables bound by the arguments of a
+    /// call, returning the concrete return type.
+    pub fn evaluate(&self, bindings: &TypeBindings) -> Result<ConcreteType, DerivationError> {
+        let mut bindings = bindings.clone();
+        let mut values = HashMap::new();
+        for Assignment { name, expr } in &self.assignments {
+            let value = expr.evaluate(&|name: &str| {
+                values
+                    .get(name)
+                    .copied()
+                    .or_else(|| bindings.get_integer(name).map(DerivationValue::Integer))
+            })?;
+            if let DerivationValue::Integer(i) = value {
+                bindings.bind_integer(name.clone(), i);
+            }
+            values.insert(name.clone(), value);
+        }
+        Ok(bindings.instantiate(&self.result)?)
+    }
+}
+
+impl From<SignatureType> for TypeDerivation {
+    fn from(result: SignatureType) -> Self {
+        TypeDerivation {
+            assignments: Vec::new(),
+            result,
+        }
+    }
+}
+
+impl fmt::Display for TypeDerivation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for Assignment { name, expr } in &self.assignments {
+            writeln!(f, "{name} = {}", Minimal(expr))?;
+        }
+        write!(f, "{}", self.result)
+    }
+}
+
+/// Split an assignment line at its `=`, which must not be part of a
+/// comparison operator.
+fn split_assignment(line: &str) -> Option<(&str, &str)> {
+    let bytes = line.as_bytes();
+    let index = (0..bytes.len()).find(|&i| {
+        bytes[i] == b'='
+            && !matches!(bytes.get(i + 1), Some(b'='))
+            && !matches!(
+                i.checked_sub(1).map(|j| bytes[j]),
+                Some(b'=' | b'!' | b'<' | b'>')
+            )
+    })?;
+    let name = line[..index].trim();
+    let is_name = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    is_name.then(|| (name, line[index + 1..].trim()))
+}
+
+/// A token of a [`DerivationExpr`].
+#[derive(Clone, Debug, PartialEq)]
+enum Token<'a> {
+    Integer(i64),
+    Name(&'a str),
+    Symbol(&'static str),
+}
+
+impl fmt::Display for Token<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Integer(i) => write!(f, "{i}"),
+            Token::Name(name) => f.write_str(name),
+            Token::Symbol(symbol) => f.write_str(symbol),
+        }
+    }
+}
+
+/// Symbols, longest first.
+const SYMBOLS: [&str; 19] = [
+    "==", "!=", "<=", ">=", "&&", "||", "+", "-", "*", "/", "<", ">", "!", "?", ":", "(", ")", ",",
+    "=",
+];
+
+/// Split an expression into tokens.
+fn tokenize(expr: &str) -> Result<Vec<Token<'_>>, DerivationError> {
+    let mut tokens = Vec::new();
+    let mut rest = expr.trim_start();
+    while let Some(c) = rest.chars().next() {
+        let len = if c.is_ascii_digit() {
+            let len = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let value = rest[..len]
+                .parse()
+                .map_err(|_| DerivationError::Overflow(expr.to_string()))?;
+            tokens.push(Token::Integer(value));
+            len
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            tokens.push(Token::Name(&rest[..len]));
+            len
+        } else {
+            let symbol = SYMBOLS
+                .iter()
+                .find(|symbol| rest.starts_with(*symbol))
+                .ok_or_else(|| DerivationError::UnexpectedToken {
+                    token: c.to_string(),
+                    expr: expr.to_string(),
+                })?;
+            tokens.push(Token::Symbol(symbol));
+            symbol.len()
+        };
+        rest = rest[len..].trim_start();
+    }
+    Ok(tokens)
+}
+
+/// A recursive descent parser for [`DerivationExpr`]s.
+struct Parser<'a> {
+    expr: &'a str,
+    tokens: Vec<Token<'a>>,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token<'a>> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Result<Token<'a>, DerivationError> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .cloned()
+            .ok_or_else(|| DerivationError::UnexpectedEnd(self.expr.to_string()))?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn unexpected(&self, token: &Token<'_>) -> DerivationError {
+        DerivationError::UnexpectedToken {
+            token: token.to_string(),
+            expr: self.expr.to_string(),
+        }
+    }
+
+    /// Consume the next token if it is the given symbol or keyword.
+    fn eat(&mut self, symbols: &[&str]) -> bool {
+        let matched = match self.peek() {
+            Some(Token::Symbol(symbol)) => symbols.contains(symbol),
+            Some(Token::Name(name)) => symbols.contains(name),
+            _ => false,
+        };
+        if matched {
+            self.position += 1;
+        }
+        matched
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), DerivationError> {
+        match self.next()? {
+            Token::Symbol(s) if s == symbol => Ok(()),
+            token => Err(self.unexpected(&token)),
+        }
+    }
+
+    /// ternary := or ('?' ternary ':' ternary)?
+    fn ternary(&mut self) -> Result<DerivationExpr, DerivationError> {
+        let condition = self.or()?;
+        if !self.eat(&["?"]) {
+            return Ok(condition);
+        }
+        let then = self.ternary()?;
+        self.expect(":")?;
+        let otherwise = self.ternary()?;
+        Ok(DerivationExpr::Ternary(
+            Box::new(condition),
+            Box::new(then),
+            Box::new(otherwise),
+        ))
+    }
+
+    /// Parse a left-associative chain of binary operations.
+    fn binary(
+        &mut self,
+        operators: &[(&[&str], BinaryOp)],
+        operand: fn(&mut Self) -> Result<DerivationExpr, DerivationError>,
+    ) -> Result<DerivationExpr, DerivationError> {
+        let mut lhs = operand(self)?;
+        'chain: loop {
+            for (symbols, op) in operators {
+                if self.eat(symbols) {
+                    let rhs = operand(self)?;
+                    lhs = DerivationExpr::Binary(*op, Box::new(lhs), Box::new(rhs));
+                    continue 'chain;
+                }
+            }
+            return Ok(lhs);
+        }
+    }
+
+    fn or(&mut self) -> Result<DerivationExpr, DerivationError> {
+        self.binary(&[(&["||", "or"], BinaryOp::Or)], Self::and)
+    }
+
+    fn and(&mut self) -> Result<DerivationExpr, DerivationError> {
+        self.binary(&[(&["&&", "and"], BinaryOp::And)], Self::comparison)
+    }
+
+    fn comparison(&mut self) -> Result<DerivationExpr, DerivationError> {
+        self.binary(
+            &[
+                (&["=="], BinaryOp::Eq),
+                (&["!="], BinaryOp::Ne),
+                (&["<="], BinaryOp::Le),
+                (&[">="], BinaryOp::Ge),
+                (&["<"], BinaryOp::Lt),
+                (&[">"], BinaryOp::Gt),
+            ],
+            Self::additive,
+        )
+    }
+
+    fn additive(&mut self) -> Result<DerivationExpr, DerivationError> {
+        self.binary(
+            &[(&["+"], BinaryOp::Add), (&["-"], BinaryOp::Sub)],
+            Self::multiplicative,
+        )
+    }
+
+    fn multiplicative(&mut self) -> Result<DerivationExpr, DerivationError> {
+        self.binary(
+            &[(&["*"], BinaryOp::Mul), (&["/"], BinaryOp::Div)],
+            Self::unary,
+        )
+    }
+
+    /// unary := ('-' | '!' | 'not') unary | primary
+    fn unary(&mut self) -> Result<DerivationExpr, DerivationError> {
+        if self.eat(&["-"]) {
+            return Ok(DerivationExpr::Unary(UnaryOp::Neg, Box::new(self.unary()?)));
+        }
+        if self.eat(&["!", "not"]) {
+            return Ok(DerivationExpr::Unary(UnaryOp::Not, Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    /// primary := integer | boolean | name | name '(' args ')' | '(' ternary ')'
+    fn primary(&mut self) -> Result<DerivationExpr, DerivationError> {
+        match self.next()? {
+            Token::Integer(i) => Ok(DerivationExpr::Integer(i)),
+            Token::Symbol("(") => {
+                let expr = self.ternary()?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            Token::Name(name) if name.eq_ignore_ascii_case("true") => {
+                Ok(DerivationExpr::Boolean(true))
+            }
+            Token::Name(name) if name.eq_ignore_ascii_case("false") => {
+                Ok(DerivationExpr::Boolean(false))
+            }
+            Token::Name(name) if self.eat(&["("]) => {
+                let function = match name.to_ascii_lowercase().as_str() {
+                    "max" => DerivationFunction::Max,
+                    "min" => DerivationFunction::Min,
+                    _ => return Err(DerivationError::UnknownFunction(name.to_string())),
+                };
+                let mut args = vec![self.ternary()?];
+                while self.eat(&[","]) {
+                    args.push(self.ternary()?);
+                }
+                self.expect(")")?;
+                Ok(DerivationExpr::Call(function, args))
+            }
+            Token::Name(name) => Ok(DerivationExpr::Variable(name.to_string())),
+            token => Err(self.unexpected(&token)),
+        }
+    }
+}
+
+impl DerivationExpr {
+    /// Parse an expression.
+    pub fn parse(expr: &str) -> Result<Self, DerivationError> {
+        let mut parser = Parser {
+            expr,
+            tokens: tokenize(expr)?,
+            position: 0,
+        };
+        let parsed = parser.ternary()?;
+        match parser.peek() {
+            Some(token) => Err(parser.unexpected(token)),
+            None => Ok(parsed),
+        }
+    }
+
+    /// Evaluate this expression, looking up variables with `lookup`.
+    pub fn evaluate(
+        &self,
+        lookup: &impl Fn(&str) -> Option<DerivationValue>,
+    ) -> Result<DerivationValue, DerivationError> {
+        let integer = |expr: &DerivationExpr| match expr.evaluate(lookup)? {
+            DerivationValue::Integer(i) => Ok(i),
+            DerivationValue::Boolean(_) => Err(DerivationError::ValueType {
+                expected: "an integer",
+                expr: expr.to_string(),
+            }),
+        };
+        let boolean = |expr: &DerivationExpr| match expr.evaluate(lookup)? {
+            DerivationValue::Boolean(b) => Ok(b),
+            DerivationValue::Integer(_) => Err(DerivationError::ValueType {
+                expected: "a boolean",
+                expr: expr.to_string(),
+            }),
+        };
+        let overflow = || DerivationError::Overflow(self.to_string());
+
+        Ok(match self {
+            DerivationExpr::Integer(i) => DerivationValue::Integer(*i),
+            DerivationExpr::Boolean(b) => DerivationValue::Boolean(*b),
+            DerivationExpr::Variable(name) => {
+                lookup(name).ok_or_else(|| DerivationError::UndefinedVariable(name.clone()))?
+            }
+            DerivationExpr::Unary(UnaryOp::Neg, expr) => {
+                DerivationValue::Integer(integer(expr)?.checked_neg().ok_or_else(overflow)?)
+            }
+            DerivationExpr::Unary(UnaryOp::Not, expr) => DerivationValue::Boolean(!boolean(expr)?),
+            DerivationExpr::Binary(op @ (BinaryOp::And | BinaryOp::Or), lhs, rhs) => {
+                // Short-circuit evaluation.
+                let lhs = boolean(lhs)?;
+                DerivationValue::Boolean(match op {
+                    BinaryOp::And => lhs && boolean(rhs)?,
+                    _ => lhs || boolean(rhs)?,
+                })
+            }
+            DerivationExpr::Binary(op @ (BinaryOp::Eq | BinaryOp::Ne), lhs, rhs) => {
+                let equal = lhs.evaluate(lookup)? == rhs.evaluate(lookup)?;
+                DerivationValue::Boolean(equal == (*op == BinaryOp::Eq))
+            }
+            DerivationExpr::Binary(op, lhs, rhs) => {
+                let (lhs, rhs) = (integer(lhs)?, integer(rhs)?);
+                match op {
+                    BinaryOp::Add => {
+                        DerivationValue::Integer(lhs.checked_add(rhs).ok_or_else(overflow)?)
+                    }
+                    BinaryOp::Sub => {
+                        DerivationValue::Integer(lhs.checked_sub(rhs).ok_or_else(overflow)?)
+                    }
+                    BinaryOp::Mul => {
+                        DerivationValue::Integer(lhs.checked_mul(rhs).ok_or_else(overflow)?)
+                    }
+                    BinaryOp::Div if rhs == 0 => {
+                        return Err(DerivationError::DivisionByZero(self.to_string()));
+                    }
+                    BinaryOp::Div => {
+                        DerivationValue::Integer(lhs.checked_div(rhs).ok_or_else(overflow)?)
+                    }
+                    BinaryOp::Lt => DerivationValue::Boolean(lhs < rhs),
+                    BinaryOp::Le => DerivationValue::Boolean(lhs <= rhs),
+                    BinaryOp::Gt => DerivationValue::Boolean(lhs > rhs),
+                    BinaryOp::Ge => DerivationValue::Boolean(lhs >= rhs),
+                    BinaryOp::Eq | BinaryOp::Ne | BinaryOp::And | BinaryOp::Or => {
+                        unreachable!("handled above")
+                    }
+                }
+            }
+            DerivationExpr::Call(function, args) => {
+                let values = args.iter().map(integer).collect::<Result<Vec<_>, _>>()?;
+                let value = match function {
+                    DerivationFunction::Max => values.into_iter().max(),
+                    DerivationFunction::Min => values.into_iter().min(),
+                };
+                DerivationValue::Integer(
+                    value.ok_or_else(|| DerivationError::NoArguments(self.to_string()))?,
+                )
+            }
+            DerivationExpr::Ternary(condition, then, otherwise) => {
+                if boolean(condition)? {
+                    then.evaluate(lookup)?
+                } else {
+                    otherwise.evaluate(lookup)?
+                }
+            }
+        })
+    }
+}
+
+impl fmt::Display for BinaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Eq => "==",
+            BinaryOp::Ne => "!=",
+            BinaryOp::Lt => "<",
+            BinaryOp::Le => "<=",
+            BinaryOp::Gt => ">",
+            BinaryOp::Ge => ">=",
+            BinaryOp::And => "&&",
+            BinaryOp::Or => "||",
+        })
+    }
+}
+
+impl BinaryOp {
+    /// The binding strength of this operator, higher binds tighter.
+    fn precedence(self) -> u8 {
+        match self {
+            BinaryOp::Or => 1,
+            BinaryOp::And => 2,
+            BinaryOp::Eq
+            | BinaryOp::Ne
+            | BinaryOp::Lt
+            | BinaryOp::Le
+            | BinaryOp::Gt
+            | BinaryOp::Ge => 3,
+            BinaryOp::Add | BinaryOp::Sub => 4,
+            BinaryOp::Mul | BinaryOp::Div => 5,
+        }
+    }
+}
+
+impl DerivationExpr {
+    /// The binding strength of the outermost operation of this expression,
+    /// higher binds tighter.
+    fn precedence(&self) -> u8 {
+        match self {
+            DerivationExpr::Ternary(..) => 0,
+            DerivationExpr::Binary(op, ..) => op.precedence(),
+            DerivationExpr::Unary(..) => 6,
+            DerivationExpr::Integer(_)
+            | DerivationExpr::Boolean(_)
+            | DerivationExpr::Variable(_)
+            | DerivationExpr::Call(..) => 7,
+        }
+    }
+
+    /// Write this expression as an operand that binds at least as tight as
+    /// `precedence`, adding parentheses only when needed.
+    fn fmt_operand(&self, f: &mut fmt::Formatter<'_>, precedence: u8) -> fmt::Result {
+        if self.precedence() < precedence {
+            return write!(f, "({})", Minimal(self));
+        }
+        match self {
+            DerivationExpr::Integer(_)
+            | DerivationExpr::Boolean(_)
+            | DerivationExpr::Variable(_) => write!(f, "{self}"),
+            DerivationExpr::Unary(op, expr) => {
+                f.write_str(match op {
+                    UnaryOp::Neg => "-",
+                    UnaryOp::Not => "!",
+                })?;
+                expr.fmt_operand(f, self.precedence())
+            }
+            DerivationExpr::Binary(op, lhs, rhs) => {
+                // Binary operators are left-associative.
+                lhs.fmt_operand(f, op.precedence())?;
+                write!(f, " {op} ")?;
+                rhs.fmt_operand(f, op.precedence() + 1)
+            }
+            DerivationExpr::Call(function, args) => {
+                let name = match function {
+                    DerivationFunction::Max => "max",
+                    DerivationFunction::Min => "min",
+                };
+                let args = args
+                    .iter()
+                    .map(|arg| Minimal(arg).to_string())
+                    .collect::<Vec<_>>();
+                write!(f, "{name}({})", args.join(", "))
+            }
+            DerivationExpr::Ternary(condition, then, otherwise) => {
+                condition.fmt_operand(f, self.precedence() + 1)?;
+                write!(f, " ? {} : {}", Minimal(then), Minimal(otherwise))
+            }
+        }
+    }
+}
+
+/// Displays a [`DerivationExpr`] with parentheses only where the precedence
+/// of the operators requires them, as written in a [`TypeDerivation`].
+struct Minimal<'a>(&'a DerivationExpr);
+
+impl fmt::Display for Minimal<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt_operand(f, 0)
+    }
+}
+
+impl fmt::Display for DerivationExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DerivationExpr::Integer(i) => write!(f, "{i}"),
+            DerivationExpr::Boolean(b) => write!(f, "{b}"),
+            DerivationExpr::Variable(name) => f.write_str(name),
+            DerivationExpr::Unary(UnaryOp::Neg, expr) => write!(f, "-{expr}"),
+            DerivationExpr::Unary(UnaryOp::Not, expr) => write!(f, "!{expr}"),
+            DerivationExpr::Binary(op, lhs, rhs) => write!(f, "({lhs} {op} {rhs})"),
+            DerivationExpr::Call(function, args) => {
+                let name = match function {
+                    DerivationFunction::Max => "max",
+                    DerivationFunction::Min => "min",
+                };
+                let args = args.iter().map(ToString::to_string).collect::<Vec<_>>();
+                write!(f, "{name}({})", args.join(", "))
+            }
+            DerivationExpr::Ternary(condition, then, otherwise) => {
+                write!(f, "({condition} ? {then} : {otherwise})")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DECIMAL_ADD: &str = "
+init_scale = max(S1,S2)
+init_prec = init_scale + max(P1 - S1, P2 - S2) + 1
+min_scale = min(init_scale, 6)
+delta = init_prec - 38
+prec = min(init_prec, 38)
+scale_after_borrow = max(init_scale - delta, min_scale)
+scale = init_prec > 38 ? scale_after_borrow : init_scale
+DECIMAL<prec, scale>
+";
+
+    fn concrete(type_str: &str) -> ConcreteType {
+        ConcreteType::try_from(TypeExpr::parse(type_str).unwrap()).unwrap()
+    }
+
+    fn evaluate(expr: &str) -> Result<DerivationValue, DerivationError> {
+        DerivationExpr::parse(expr)?.evaluate(&|name| match name {
+            "x" => Some(DerivationValue::Integer(10)),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn parse_derivation() {
+        let derivation = TypeDerivation::parse(DECIMAL_ADD).unwrap();
+        assert_eq!(derivation.assignments().len(), 7);
+        assert_eq!(derivation.assignments()[0].name, "init_scale");
+        assert_eq!(derivation.result().to_string(), "decimal<prec, scale>");
+
+        let derivation = TypeDerivation::parse("i32?").unwrap();
+        assert!(derivation.assignments().is_empty());
+        assert_eq!(derivation.to_string(), "i32?");
+    }
+
+    #[test]
+    fn display_derivation() {
+        let display = |expr| Minimal(&DerivationExpr::parse(expr).unwrap()).to_string();
+        assert_eq!(display("(1 + 2) * 3"), "(1 + 2) * 3");
+        assert_eq!(display("1 + (2 * 3)"), "1 + 2 * 3");
+        assert_eq!(display("x - (3 - 2)"), "x - (3 - 2)");
+        assert_eq!(display("(x - 3) - 2"), "x - 3 - 2");
+        assert_eq!(display("-(x + 1)"), "-(x + 1)");
+        assert_eq!(display("not (a and b) or c"), "!(a && b) || c");
+        assert_eq!(
+            display("(a ? 1 : 2) > 0 ? x : y ? 3 : 4"),
+            "(a ? 1 : 2) > 0 ? x : y ? 3 : 4"
+        );
+        assert_eq!(display("max(S1,S2)"), "max(S1, S2)");
+
+        let derivation = TypeDerivation::parse(DECIMAL_ADD).unwrap();
+        assert_eq!(
+            derivation.to_string(),
+            DECIMAL_ADD
+                .trim()
+                .replace("S1,S2", "S1, S2")
+                .replace("DECIMAL", "decimal")
+        );
+    }
+
+    #[test]
+    fn evaluate_derivation() {
+        let derivation = TypeDerivation::parse(DECIMAL_ADD).unwrap();
+        let mut bindings = TypeBindings::default();
+        for (name, value) in [("P1", 10), ("S1", 2), ("P2", 5), ("S2", 4)] {
+            bindings.bind_integer(name, value);
+        }
+        assert_eq!(
+            derivation.evaluate(&bindings),
+            Ok(concrete("DECIMAL<13, 4>"))
+        );
+
+        // Precision overflow borrows from the scale.
+        for (name, value) in [("P1", 38), ("S1", 10), ("P2", 38), ("S2", 10)] {
+            bindings.bind_integer(name, value);
+        }
+        assert_eq!(
+            derivation.evaluate(&bindings),
+            Ok(concrete("DECIMAL<38, 9>"))
+        );
+
+        assert_eq!(
+            TypeDerivation::parse(DECIMAL_ADD)
+                .unwrap()
+                .evaluate(&TypeBindings::default()),
+            Err(DerivationError::UndefinedVariable("S1".to_string()))
+        );
+    }
+
+    #[test]
+    fn evaluate_expressions() {
+        let integer = |i| Ok(DerivationValue::Integer(i));
+        let boolean = |b| Ok(DerivationValue::Boolean(b));
+
+        assert_eq!(evaluate("1 + 2 * 3"), integer(7));
+        assert_eq!(evaluate("(1 + 2) * 3"), integer(9));
+        assert_eq!(evaluate("x - 3 - 2"), integer(5));
+        assert_eq!(evaluate("-x / 3"), integer(-3));
+        assert_eq!(evaluate("max(1, x, 3) + min(4, 5)"), integer(14));
+        assert_eq!(evaluate("x > 5 && !(x == 3) || false"), boolean(true));
+        assert_eq!(evaluate("not true or x <= 9"), boolean(false));
+        assert_eq!(evaluate("x != 10 ? 1 : x >= 10 ? 2 : 3"), integer(2));
+
+        assert_eq!(
+            evaluate("y + 1"),
+            Err(DerivationError::UndefinedVariable("y".to_string()))
+        );
+        assert_eq!(
+            evaluate("x / 0"),
+            Err(DerivationError::DivisionByZero("(x / 0)".to_string()))
+        );
+        assert!(matches!(
+            evaluate("x + true"),
+            Err(DerivationError::ValueType { .. })
+        ));
+        assert_eq!(
+            evaluate("abs(x)"),
+            Err(DerivationError::UnknownFunction("abs".to_string()))
+        );
+        assert_eq!(
+            evaluate("x +"),
+            Err(DerivationError::UnexpectedEnd("x +".to_string()))
+        );
+        assert_eq!(
+            DerivationExpr::Call(DerivationFunction::Max, Vec::new()).evaluate(&|_| None),
+            Err(DerivationError::NoArguments("max()".to_string()))
+        );
+        assert!(matches!(
+            evaluate("x y"),
+            Err(DerivationError::UnexpectedToken { token, .. }) if token == "y"
+        ));
+    }
+
+    #[test]
+    fn invalid_assignment() {
+        assert_eq!(
+            TypeDerivation::parse("x == 1\ni32"),
+            Err(DerivationError::InvalidAssignment("x == 1".to_string()))
+        );
+        assert_eq!(TypeDerivation::parse("\n  \n"), Err(DerivationError::Empty));
+    }
+}
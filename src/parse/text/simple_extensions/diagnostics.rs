@@ -0,0 +1,254 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Options for parsing extension files: [`ParseOptions`], and the
+//! [`ParseWarning`]s reported while parsing.
+
+use std::fmt;
+
+/// Options for parsing an [`ExtensionFile`](super::ExtensionFile).
+///
+/// The default options parse strictly according to the specification.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParseOptions {
+    /// Accept references to types declared in the same file without the `u!`
+    /// prefix, reporting a [`ParseWarning::UnprefixedTypeReference`] for each
+    /// referenced type instead of failing.
+    pub lenient: bool,
+    /// Fail on function implementations using features that are not yet
+    /// supported (function types, `func<...>`) with a
+    /// [`SimpleExtensionsError::NotYetImplemented`](super::SimpleExtensionsError::NotYetImplemented),
+    /// instead of skipping them with a [`ParseWarning::SkippedImpl`].
+    pub strict: bool,
+}
+
+impl ParseOptions {
+    /// Options that accept the deviations from the specification described
+    /// on the fields of [`ParseOptions`].
+    pub fn lenient() -> Self {
+        Self {
+            lenient: true,
+            ..Default::default()
+        }
+    }
+
+    /// Options that fail on function implementations that would otherwise be
+    /// skipped.
+    pub fn strict() -> Self {
+        Self {
+            strict: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// The kind of a function defined in an extension file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FunctionKind {
+    /// A scalar function
+    Scalar,
+    /// An aggregate function
+    Aggregate,
+    /// A window function
+    Window,
+}
+
+impl fmt::Display for FunctionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FunctionKind::Scalar => f.write_str("scalar"),
+            FunctionKind::Aggregate => f.write_str("aggregate"),
+            FunctionKind::Window => f.write_str("window"),
+        }
+    }
+}
+
+/// A problem found while parsing an extension file, which did not prevent it
+/// from loading.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseWarning {
+    /// A type declared in the file was referenced without the `u!` prefix
+    UnprefixedTypeReference {
+        /// The name of the referenced type
+        name: String,
+    },
+    /// An implementation of a function uses a feature that is not yet
+    /// supported, and was skipped. Functions without other implementations
+    /// are left out.
+    SkippedImpl {
+        /// The kind of the function
+        kind: FunctionKind,
+        /// The name of the function
+        name: String,
+        /// The index of the implementation in the function
+        index: usize,
+        /// The feature that is not yet supported
+        reason: String,
+    },
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseWarning::UnprefixedTypeReference { name } => {
+                write!(f, "type `{name}` referenced without the `u!` prefix")
+            }
+            ParseWarning::SkippedImpl {
+                kind,
+                name,
+                index,
+                reason,
+            } => write!(
+                f,
+                "skipped implementation {index} of {kind} function `{name}`: not yet implemented: {reason}"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::text::simple_extensions::extensions::TypeContext;
+    use crate::parse::text::simple_extensions::{ExtensionFile, SimpleExtensionsError, TypeExpr};
+    use crate::text::simple_extensions::SimpleExtensions as RawExtensions;
+
+    #[test]
+    fn resolves_unprefixed_types() {
+        let cases = [
+            ("point", "u!point", vec!["point"]),
+            ("point?", "u!point?", vec!["point"]),
+            ("list<point>", "list<u!point>", vec!["point"]),
+            (
+                "map<point, line?>",
+                "map<u!point, u!line?>",
+                vec!["line", "point"],
+            ),
+            ("u!point", "u!point", vec![]),
+            ("i32[point]", "i32[point]", vec![]),
+            ("u!box<'point'>", "u!box<'point'>", vec![]),
+            ("pointer", "pointer", vec![]),
+            ("i64", "i64", vec![]),
+        ];
+        for (input, expected, referenced) in cases {
+            let mut ctx = TypeContext::new(&ParseOptions::lenient());
+            ctx.declare_types(["point", "line", "box"]);
+            let type_expr = ctx.parse_type(input).expect("parse succeeds");
+            let expected_expr = TypeExpr::parse(expected).expect("parse succeeds");
+            assert_eq!(type_expr, expected_expr, "resolving {input}");
+            let warnings = referenced
+                .into_iter()
+                .map(|name| ParseWarning::UnprefixedTypeReference {
+                    name: name.to_string(),
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(ctx.take_warnings(), warnings, "references in {input}");
+        }
+
+        // Bare names are only resolved when parsing leniently.
+        let mut ctx = TypeContext::default();
+        ctx.declare_types(["point"]);
+        assert_eq!(
+            ctx.parse_type("point"),
+            TypeExpr::parse("point"),
+            "strict parse"
+        );
+    }
+
+    #[test]
+    fn skipped_impls() {
+        let mut ctx = TypeContext::default();
+        ctx.skip_impl(
+            FunctionKind::Aggregate,
+            "sum",
+            2,
+            "decimal math".to_string(),
+        );
+        let warnings = ctx.take_warnings();
+        assert_eq!(
+            warnings,
+            vec![ParseWarning::SkippedImpl {
+                kind: FunctionKind::Aggregate,
+                name: "sum".to_string(),
+                index: 2,
+                reason: "decimal math".to_string(),
+            }]
+        );
+        assert_eq!(
+            warnings[0].to_string(),
+            "skipped implementation 2 of aggregate function `sum`: not yet implemented: decimal math"
+        );
+    }
+
+    const YAML_FUNCTION_TYPES: &str = r#"
+%YAML 1.2
+---
+urn: extension:example.com:lambdas
+scalar_functions:
+  - name: length
+    impls:
+      - args:
+          - value: list<any1>
+        return: i64
+  - name: transform
+    impls:
+      - args:
+          - value: list<any1>
+        return: list<any1>
+      - args:
+          - value: list<any1>
+          - value: func<any1 -> any2>
+        return: list<any2>
+aggregate_functions:
+  - name: reduce
+    impls:
+      - args:
+          - value: any1
+          - value: FUNC<any1, any1 -> any1>
+        return: any1
+"#;
+
+    #[test]
+    fn skipped_function_types() {
+        let raw: RawExtensions = serde_yaml::from_str(YAML_FUNCTION_TYPES).unwrap();
+        let reason = "function types (`func<...>`)".to_string();
+        let transform = ParseWarning::SkippedImpl {
+            kind: FunctionKind::Scalar,
+            name: "transform".to_string(),
+            index: 1,
+            reason: reason.clone(),
+        };
+        let reduce = ParseWarning::SkippedImpl {
+            kind: FunctionKind::Aggregate,
+            name: "reduce".to_string(),
+            index: 0,
+            reason,
+        };
+
+        // By default, the implementations using function types are skipped
+        // with a warning. Functions without other implementations are left
+        // out.
+        let file = ExtensionFile::create(raw.clone()).unwrap();
+        assert_eq!(file.warnings(), [transform.clone(), reduce]);
+        assert!(file.extension().get_scalar_function("length").is_some());
+        let transform_fn = file.extension().get_scalar_function("transform").unwrap();
+        assert_eq!(transform_fn.impls.len(), 1);
+        assert!(transform_fn.get_impl("list").is_some());
+        assert!(file.extension().get_aggregate_function("reduce").is_none());
+
+        // In strict mode, the first skipped implementation is an error.
+        let error = ExtensionFile::create_with_options(raw, &ParseOptions::strict()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "implementation 1 of scalar function `transform`: not yet implemented: function types (`func<...>`)"
+        );
+        assert!(matches!(
+            error,
+            SimpleExtensionsError::NotYetImplemented {
+                kind: FunctionKind::Scalar,
+                name,
+                index: 1,
+                ..
+            } if name == "transform"
+        ));
+    }
+}
@@ -2,17 +2,27 @@
 
 //! Validated simple extensions: [`SimpleExtensions`].
 //!
-//! Both type definitions and scalar function definitions are supported.
-//! Aggregate functions (see #447) and window functions (see #446) are not yet supported.
+//! Type definitions, type variation definitions and scalar, aggregate and
+//! window function definitions are supported.
 
 use indexmap::IndexMap;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashSet};
 use std::str::FromStr;
 
-use super::{SimpleExtensionsError, scalar_functions::ScalarFunction, types::CustomType};
+use super::{
+    FunctionKind, ParseOptions, ParseWarning, SimpleExtensionsError,
+    aggregate_functions::{AggregateFunction, AggregateFunctionError},
+    scalar_functions::{ScalarFunction, ScalarFunctionError},
+    type_ast::{TypeExpr, TypeExprParam, TypeParseError},
+    type_variations::TypeVariation,
+    types::{CustomType, ExtensionTypeError, TypeParameter},
+    window_functions::{WindowFunction, WindowFunctionError},
+};
 use crate::{
     parse::{Context, Parse},
-    text::simple_extensions::SimpleExtensions as RawExtensions,
+    text::simple_extensions::{
+        SimpleExtensions as RawExtensions, SimpleExtensionsDependenciesKey as RawDependencyAlias,
+    },
     urn::Urn,
 };
 
@@ -20,15 +30,28 @@ use crate::{
 ///
 /// This structure stores and provides access to the individual objects defined
 /// in an [`ExtensionFile`](super::file::ExtensionFile); [`SimpleExtensions`]
-/// represents the contents of an extensions file.
+/// represents the contents of an extensions file. Definitions are kept in the
+/// order they are declared in.
 #[derive(Clone, Debug, Default)]
 pub struct SimpleExtensions {
     /// Types defined in this extension file
-    types: HashMap<String, CustomType>,
+    types: IndexMap<String, CustomType>,
+    /// Type variations defined in this extension file
+    type_variations: IndexMap<String, TypeVariation>,
     /// Scalar functions defined in this extension file
-    ///
-    /// TODO: Add support for window functions (issue #446) and aggregate functions (issue #447)
-    scalar_functions: HashMap<String, ScalarFunction>,
+    scalar_functions: IndexMap<String, ScalarFunction>,
+    /// Aggregate functions defined in this extension file
+    aggregate_functions: IndexMap<String, AggregateFunction>,
+    /// Window functions defined in this extension file
+    window_functions: IndexMap<String, WindowFunction>,
+    /// Dependencies of this extension file: URNs of other extension files, by
+    /// alias
+    dependencies: IndexMap<RawDependencyAlias, Urn>,
+    /// Types of dependencies referenced in this extension file, as
+    /// `alias.name`
+    dependency_types: BTreeSet<String>,
+    /// Additional metadata of the extension file
+    metadata: serde_json::Map<String, serde_json::Value>,
 }
 
 impl SimpleExtensions {
@@ -55,9 +78,49 @@ impl SimpleExtensions {
         self.types.values()
     }
 
-    /// Consume the parsed extension and return its types.
-    pub(crate) fn into_types(self) -> HashMap<String, CustomType> {
-        self.types
+    /// Add a type variation to the context. Name must be unique.
+    pub fn add_type_variation(
+        &mut self,
+        type_variation: TypeVariation,
+    ) -> Result<(), SimpleExtensionsError> {
+        use indexmap::map::Entry;
+        match self.type_variations.entry(type_variation.name.clone()) {
+            Entry::Occupied(_) => Err(SimpleExtensionsError::DuplicateTypeVariationName {
+                name: type_variation.name,
+            }),
+            Entry::Vacant(e) => {
+                e.insert(type_variation);
+                Ok(())
+            }
+        }
+    }
+
+    /// Get a type variation by name from the context
+    pub fn get_type_variation(&self, name: &str) -> Option<&TypeVariation> {
+        self.type_variations.get(name)
+    }
+
+    /// Get an iterator over all type variations in the context
+    pub fn type_variations(&self) -> impl Iterator<Item = &TypeVariation> {
+        self.type_variations.values()
+    }
+
+    /// Check the parameters of references to types defined in this extension,
+    /// in type structures and type variation parents, against the parameter
+    /// definitions of those types.
+    fn validate_type_parameters(&self) -> Result<(), ExtensionTypeError> {
+        let mut result = Ok(());
+        let mut validate = |name: &str, parameters: &[TypeParameter]| {
+            if let Some(custom_type) = self.types.get(name).filter(|_| result.is_ok()) {
+                result = custom_type.validate_parameters(parameters);
+            }
+        };
+        let structures = self.types.values().filter_map(|t| t.structure.as_ref());
+        let parents = self.type_variations.values().map(|v| &v.parent);
+        for concrete in structures.chain(parents) {
+            concrete.visit_extensions(&mut validate);
+        }
+        result
     }
 
     /// Add a scalar function to the context, merging with existing functions of the same name.
@@ -67,7 +130,7 @@ impl SimpleExtensions {
     ///
     /// See: https://github.com/substrait-io/substrait/issues/931
     pub(super) fn add_scalar_function(&mut self, scalar_function: ScalarFunction) {
-        use std::collections::hash_map::Entry;
+        use indexmap::map::Entry;
         match self.scalar_functions.entry(scalar_function.name.clone()) {
             Entry::Vacant(e) => {
                 e.insert(scalar_function);
@@ -81,11 +144,13 @@ impl SimpleExtensions {
     /// Merge a new scalar function into an existing one.
     ///
     /// Unions the implementations. Keeps the existing description if present,
-    /// otherwise uses the new description.
+    /// otherwise uses the new description. Metadata entries of the existing
+    /// function take precedence.
     // TODO: Reject conflicting implementations instead of blindly merging.
     fn merge_scalar_function(existing: &mut ScalarFunction, new: ScalarFunction) {
         existing.impls.extend(new.impls);
         existing.description = existing.description.take().or(new.description);
+        merge_metadata(&mut existing.metadata, new.metadata);
     }
 
     /// Get a scalar function by name
@@ -97,18 +162,267 @@ impl SimpleExtensions {
     pub fn scalar_functions(&self) -> impl Iterator<Item = &ScalarFunction> {
         self.scalar_functions.values()
     }
+
+    /// Add an aggregate function to the context, merging with existing
+    /// functions of the same name.
+    ///
+    /// Implementations are merged like those of scalar functions; see
+    /// [`SimpleExtensions::add_scalar_function`].
+    pub(super) fn add_aggregate_function(&mut self, aggregate_function: AggregateFunction) {
+        use indexmap::map::Entry;
+        match self
+            .aggregate_functions
+            .entry(aggregate_function.name.clone())
+        {
+            Entry::Vacant(e) => {
+                e.insert(aggregate_function);
+            }
+            Entry::Occupied(mut e) => {
+                let existing = e.get_mut();
+                existing.impls.extend(aggregate_function.impls);
+                existing.description = existing
+                    .description
+                    .take()
+                    .or(aggregate_function.description);
+                merge_metadata(&mut existing.metadata, aggregate_function.metadata);
+            }
+        }
+    }
+
+    /// Get an aggregate function by name
+    pub fn get_aggregate_function(&self, name: &str) -> Option<&AggregateFunction> {
+        self.aggregate_functions.get(name)
+    }
+
+    /// Get an iterator over all aggregate functions
+    pub fn aggregate_functions(&self) -> impl Iterator<Item = &AggregateFunction> {
+        self.aggregate_functions.values()
+    }
+
+    /// Add a window function to the context, merging with existing functions
+    /// of the same name.
+    ///
+    /// Implementations are merged like those of scalar functions; see
+    /// [`SimpleExtensions::add_scalar_function`].
+    pub(super) fn add_window_function(&mut self, window_function: WindowFunction) {
+        use indexmap::map::Entry;
+        match self.window_functions.entry(window_function.name.clone()) {
+            Entry::Vacant(e) => {
+                e.insert(window_function);
+            }
+            Entry::Occupied(mut e) => {
+                let existing = e.get_mut();
+                existing.impls.extend(window_function.impls);
+                existing.description = existing.description.take().or(window_function.description);
+                merge_metadata(&mut existing.metadata, window_function.metadata);
+            }
+        }
+    }
+
+    /// Get a window function by name
+    pub fn get_window_function(&self, name: &str) -> Option<&WindowFunction> {
+        self.window_functions.get(name)
+    }
+
+    /// Get an iterator over all window functions
+    pub fn window_functions(&self) -> impl Iterator<Item = &WindowFunction> {
+        self.window_functions.values()
+    }
+
+    /// Add a dependency on another extension file, by alias. Types of the
+    /// dependency are referenced as `u!alias.name`.
+    pub(super) fn add_dependency(&mut self, alias: RawDependencyAlias, urn: Urn) {
+        self.dependencies.insert(alias, urn);
+    }
+
+    /// Get the URN of a dependency by alias
+    pub fn get_dependency(&self, alias: &str) -> Option<&Urn> {
+        self.dependencies
+            .iter()
+            .find(|(dependency, _)| dependency.as_str() == alias)
+            .map(|(_, urn)| urn)
+    }
+
+    /// Get an iterator over the dependencies, as pairs of alias and URN
+    pub fn dependencies(&self) -> impl Iterator<Item = (&str, &Urn)> {
+        self.dependencies
+            .iter()
+            .map(|(alias, urn)| (alias.as_str(), urn))
+    }
+
+    /// Get the additional metadata of the extension file
+    pub fn metadata(&self) -> &serde_json::Map<String, serde_json::Value> {
+        &self.metadata
+    }
+
+    /// Get an iterator over the types of dependencies referenced in this
+    /// extension, as `alias.name`. These are resolved by the
+    /// [`Registry`](super::Registry).
+    pub(super) fn dependency_types(&self) -> impl Iterator<Item = &str> {
+        self.dependency_types.iter().map(String::as_str)
+    }
+}
+
+/// Add the metadata entries of a merged definition that are not already
+/// present.
+fn merge_metadata(
+    existing: &mut serde_json::Map<String, serde_json::Value>,
+    new: serde_json::Map<String, serde_json::Value>,
+) {
+    for (key, value) in new {
+        existing.entry(key).or_insert(value);
+    }
 }
 
-/// resolved or unresolved.
+/// The error for an implementation of a function that failed to parse with
+/// `error`. Implementations using features that are not yet supported are
+/// only errors in strict mode, see [`SimpleExtensionsError::NotYetImplemented`];
+/// other errors are converted with `other`.
+fn impl_error(
+    kind: FunctionKind,
+    name: String,
+    error: ScalarFunctionError,
+    other: impl FnOnce(ScalarFunctionError) -> SimpleExtensionsError,
+) -> SimpleExtensionsError {
+    match error {
+        ScalarFunctionError::ImplNotYetImplemented { index, reason } => {
+            SimpleExtensionsError::NotYetImplemented {
+                kind,
+                name,
+                index,
+                reason,
+            }
+        }
+        error => other(error),
+    }
+}
+
+/// A context for parsing types, tracking which types have been seen, and
+/// which are referenced, resolved or unresolved.
 #[derive(Debug, Default)]
 pub(crate) struct TypeContext {
     /// Types that have been seen so far, now resolved.
     known: HashSet<String>,
     /// Types that have been linked to, not yet resolved.
     linked: HashSet<String>,
+    /// Aliases of the dependencies of the extension file.
+    dependencies: HashSet<String>,
+    /// Types of dependencies that have been linked to, as `alias.name`. These
+    /// are resolved across extension files, by the registry.
+    dependency_types: BTreeSet<String>,
+    /// Whether bare names may refer to types declared in the extension file,
+    /// see [`ParseOptions::lenient`].
+    lenient: bool,
+    /// Types declared in the extension file, when parsing leniently.
+    declared: HashSet<String>,
+    /// Declared types referenced without the `u!` prefix.
+    unprefixed: BTreeSet<String>,
+    /// Whether implementations using features that are not yet supported are
+    /// errors, see [`ParseOptions::strict`].
+    strict: bool,
+    /// Warnings reported while parsing.
+    warnings: Vec<ParseWarning>,
 }
 
 impl TypeContext {
+    /// Create a context for parsing with the given options
+    pub(super) fn new(options: &ParseOptions) -> Self {
+        Self {
+            lenient: options.lenient,
+            strict: options.strict,
+            ..Default::default()
+        }
+    }
+
+    /// Take the warnings reported while parsing: a warning for each declared
+    /// type referenced without the `u!` prefix, in name order, followed by
+    /// the other warnings in the order they were reported.
+    pub(super) fn take_warnings(&mut self) -> Vec<ParseWarning> {
+        std::mem::take(&mut self.unprefixed)
+            .into_iter()
+            .map(|name| ParseWarning::UnprefixedTypeReference { name })
+            .chain(std::mem::take(&mut self.warnings))
+            .collect()
+    }
+
+    /// Whether implementations using features that are not yet supported are
+    /// errors rather than skipped.
+    pub(super) fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Record an implementation skipped because it uses a feature that is not
+    /// yet supported.
+    pub(super) fn skip_impl(
+        &mut self,
+        kind: FunctionKind,
+        name: &str,
+        index: usize,
+        reason: String,
+    ) {
+        self.warnings.push(ParseWarning::SkippedImpl {
+            kind,
+            name: name.to_string(),
+            index,
+            reason,
+        });
+    }
+
+    /// Record the types declared in the extension file, which may be
+    /// referenced without the `u!` prefix when parsing leniently.
+    pub(super) fn declare_types<'a>(&mut self, names: impl IntoIterator<Item = &'a str>) {
+        if self.lenient {
+            self.declared.extend(names.into_iter().map(str::to_string));
+        }
+    }
+
+    /// Parse a type string, marking the extension types it references as
+    /// linked.
+    ///
+    /// When parsing leniently, bare names of types declared in the extension
+    /// file refer to those types, as if they had the `u!` prefix.
+    pub(super) fn parse_type<'a>(
+        &mut self,
+        type_str: &'a str,
+    ) -> Result<TypeExpr<'a>, TypeParseError> {
+        let mut type_expr = TypeExpr::parse(type_str)?;
+        if !self.declared.is_empty() {
+            self.resolve_unprefixed(&mut type_expr);
+        }
+        type_expr.visit_references(&mut |name| self.linked(name));
+        Ok(type_expr)
+    }
+
+    /// Turn bare names of declared types into extension type references,
+    /// recording them for [`ParseWarning::UnprefixedTypeReference`] warnings.
+    fn resolve_unprefixed(&mut self, type_expr: &mut TypeExpr<'_>) {
+        match type_expr {
+            TypeExpr::Simple(name, params, nullable, variation)
+                if self.declared.contains(*name) =>
+            {
+                self.unprefixed.insert(name.to_string());
+                *type_expr =
+                    TypeExpr::UserDefined(*name, std::mem::take(params), *nullable, *variation);
+            }
+            _ => {}
+        }
+
+        let (TypeExpr::Simple(_, params, ..) | TypeExpr::UserDefined(_, params, ..)) = type_expr
+        else {
+            return;
+        };
+        for param in params {
+            match param {
+                TypeExprParam::Type(type_expr) => self.resolve_unprefixed(type_expr),
+                TypeExprParam::Enum(name) if self.declared.contains(*name) => {
+                    self.unprefixed.insert(name.to_string());
+                    *param = TypeExprParam::Type(TypeExpr::UserDefined(*name, vec![], false, None));
+                }
+                _ => {}
+            }
+        }
+    }
+
     /// Mark a type as found
     pub fn found(&mut self, name: &str) {
         self.linked.remove(name);
@@ -117,8 +431,16 @@ impl TypeContext {
 
     /// Mark a type as linked to - some other type or function references it,
     /// but we haven't seen it.
+    ///
+    /// Names of the form `alias.name` with a declared dependency alias refer
+    /// to types of that dependency.
     pub fn linked(&mut self, name: &str) {
-        if !self.known.contains(name) {
+        if name
+            .split_once('.')
+            .is_some_and(|(alias, _)| self.dependencies.contains(alias))
+        {
+            self.dependency_types.insert(name.to_string());
+        } else if !self.known.contains(name) {
             self.linked.insert(name.to_string());
         }
     }
@@ -135,26 +457,75 @@ impl Parse<TypeContext> for RawExtensions {
         let RawExtensions {
             urn,
             types,
+            type_variations,
             scalar_functions,
-            ..
+            aggregate_functions,
+            window_functions,
+            dependencies,
+            metadata,
         } = self;
         let urn = Urn::from_str(&urn)?;
-        let mut extension = SimpleExtensions::default();
+        let mut extension = SimpleExtensions {
+            metadata,
+            ..Default::default()
+        };
 
+        for (alias, dependency) in dependencies {
+            ctx.dependencies.insert(alias.to_string());
+            extension.add_dependency(alias, Urn::from_str(&dependency)?);
+        }
+
+        ctx.declare_types(types.iter().map(|type_item| type_item.name.as_str()));
         for type_item in types {
             let custom_type = Parse::parse(type_item, ctx)?;
             extension.add_type(&custom_type)?;
         }
 
+        for variation_item in type_variations {
+            let type_variation = Parse::parse(variation_item, ctx)?;
+            extension.add_type_variation(type_variation)?;
+        }
+
+        extension.validate_type_parameters()?;
+
         for scalar_fn in scalar_functions {
+            let name = scalar_fn.name.clone();
             match ScalarFunction::from_raw(scalar_fn, ctx) {
-                Ok(parsed_fn) => {
+                Ok(Some(parsed_fn)) => {
                     extension.add_scalar_function(parsed_fn);
                 }
-                Err(super::scalar_functions::ScalarFunctionError::NotYetImplemented(_)) => {
-                    // Skip functions with unimplemented features (e.g., type derivations)
-                    // These will be supported in a future update
-                    continue;
+                Ok(None) => {}
+                Err(e) => return Err(impl_error(FunctionKind::Scalar, name, e, Into::into)),
+            }
+        }
+
+        for aggregate_fn in aggregate_functions {
+            let name = aggregate_fn.name.clone();
+            match AggregateFunction::from_raw(aggregate_fn, ctx) {
+                Ok(Some(parsed_fn)) => {
+                    extension.add_aggregate_function(parsed_fn);
+                }
+                Ok(None) => {}
+                Err(AggregateFunctionError::Impl(e)) => {
+                    return Err(impl_error(FunctionKind::Aggregate, name, e, |e| {
+                        AggregateFunctionError::Impl(e).into()
+                    }));
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        for window_fn in window_functions {
+            let name = window_fn.name.clone();
+            match WindowFunction::from_raw(window_fn, ctx) {
+                Ok(Some(parsed_fn)) => {
+                    extension.add_window_function(parsed_fn);
+                }
+                Ok(None) => {}
+                Err(WindowFunctionError::Aggregate(AggregateFunctionError::Impl(e))) => {
+                    return Err(impl_error(FunctionKind::Window, name, e, |e| {
+                        AggregateFunctionError::Impl(e).into()
+                    }));
                 }
                 Err(e) => return Err(e.into()),
             }
@@ -163,31 +534,44 @@ impl Parse<TypeContext> for RawExtensions {
         if let Some(missing) = ctx.linked.iter().next() {
             // TODO: Track originating type(s) to improve this error message.
             return Err(super::SimpleExtensionsError::UnresolvedTypeReference {
+                urn,
                 type_name: missing.clone(),
             });
         }
+        extension.dependency_types = std::mem::take(&mut ctx.dependency_types);
 
         Ok((urn, extension))
     }
 }
 
+/// Definitions are written in the order they were declared in. Functions
+/// declared more than once have their implementations merged when parsed, and
+/// are written once, at the position of their first declaration.
 impl From<(Urn, SimpleExtensions)> for RawExtensions {
     fn from((urn, extension): (Urn, SimpleExtensions)) -> Self {
-        let types = extension
-            .into_types()
-            .into_values()
-            .map(Into::into)
-            .collect();
+        let SimpleExtensions {
+            types,
+            type_variations,
+            scalar_functions,
+            aggregate_functions,
+            window_functions,
+            dependencies,
+            metadata,
+            ..
+        } = extension;
 
         RawExtensions {
             urn: urn.to_string(),
-            aggregate_functions: vec![],
-            dependencies: IndexMap::new(),
-            metadata: Default::default(),
-            scalar_functions: vec![],
-            type_variations: vec![],
-            types,
-            window_functions: vec![],
+            aggregate_functions: aggregate_functions.into_values().map(Into::into).collect(),
+            dependencies: dependencies
+                .into_iter()
+                .map(|(alias, urn)| (alias, urn.to_string()))
+                .collect(),
+            metadata,
+            scalar_functions: scalar_functions.into_values().map(Into::into).collect(),
+            type_variations: type_variations.into_values().map(Into::into).collect(),
+            types: types.into_values().map(Into::into).collect(),
+            window_functions: window_functions.into_values().map(Into::into).collect(),
         }
     }
 }
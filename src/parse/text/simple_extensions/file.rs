@@ -1,11 +1,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use super::{CustomType, SimpleExtensions, SimpleExtensionsError};
+use super::{CustomType, ParseOptions, ParseWarning, SimpleExtensions, SimpleExtensionsError};
 use crate::parse::Parse;
 use crate::parse::text::simple_extensions::extensions::TypeContext;
 use crate::text::simple_extensions::SimpleExtensions as RawExtensions;
 use crate::urn::Urn;
 use std::io::Read;
+use std::path::PathBuf;
+use thiserror::Error;
 
 /// A parsed and validated [`RawExtensions`]: a simple extensions file.
 ///
@@ -17,24 +19,56 @@ pub struct ExtensionFile {
     pub(crate) urn: Urn,
     /// The extension data containing types and eventually functions
     pub(crate) extension: SimpleExtensions,
+    /// Warnings reported while parsing the extension file
+    pub(crate) warnings: Vec<ParseWarning>,
 }
 
 impl ExtensionFile {
     /// Create a new, empty [`ExtensionFile`] with an empty set of [`SimpleExtensions`].
     pub fn empty(urn: Urn) -> Self {
         let extension = SimpleExtensions::default();
-        Self { urn, extension }
+        Self {
+            urn,
+            extension,
+            warnings: Vec::new(),
+        }
     }
 
     /// Create an [`ExtensionFile`] from raw simple extension data.
+    ///
+    /// Function implementations using features that are not yet supported are
+    /// skipped, and reported as [`warnings`](Self::warnings).
     pub fn create(extensions: RawExtensions) -> Result<Self, SimpleExtensionsError> {
+        Self::create_with_options(extensions, &ParseOptions::default())
+    }
+
+    /// Create an [`ExtensionFile`] from raw simple extension data, with the
+    /// given [`ParseOptions`].
+    ///
+    /// Deviations from the specification accepted by the options, and function
+    /// implementations skipped because they use features that are not yet
+    /// supported, are reported as [`warnings`](Self::warnings).
+    pub fn create_with_options(
+        extensions: RawExtensions,
+        options: &ParseOptions,
+    ) -> Result<Self, SimpleExtensionsError> {
         // Parse all types (may contain unresolved Extension(String) references)
-        let mut ctx = TypeContext::default();
+        let mut ctx = TypeContext::new(options);
         let (urn, extension) = Parse::parse(extensions, &mut ctx)?;
+        let warnings = ctx.take_warnings();
 
-        // TODO: Use ctx.known/ctx.linked to validate unresolved references and cross-file links.
+        // Types of dependencies are resolved across files by the `Registry`.
 
-        Ok(Self { urn, extension })
+        Ok(Self {
+            urn,
+            extension,
+            warnings,
+        })
+    }
+
+    /// Returns the warnings reported while parsing this extension file.
+    pub fn warnings(&self) -> &[ParseWarning] {
+        &self.warnings
     }
 
     /// Get a type by name
@@ -60,7 +94,7 @@ impl ExtensionFile {
     /// Convert the parsed extension file back into the raw text representation
     /// by value.
     pub fn into_raw(self) -> RawExtensions {
-        let ExtensionFile { urn, extension } = self;
+        let ExtensionFile { urn, extension, .. } = self;
         RawExtensions::from((urn, extension))
     }
 
@@ -70,6 +104,11 @@ impl ExtensionFile {
         RawExtensions::from((self.urn.clone(), self.extension.clone()))
     }
 
+    /// Serialize the extension file to YAML.
+    pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.to_raw())
+    }
+
     /// Read an extension file from a reader.
     /// - `reader`: any [`Read`] instance with the YAML content
     ///
@@ -86,11 +125,22 @@ impl ExtensionFile {
     }
 }
 
+/// An error loading an extension file, with the path of the file.
+#[derive(Debug, Error)]
+#[error("{}: {error}", path.display())]
+pub struct ExtensionFileError {
+    /// The path of the extension file
+    pub path: PathBuf,
+    /// The error loading the extension file
+    pub error: SimpleExtensionsError,
+}
+
 // Parsing and conversion implementations are defined on `SimpleExtensions` in `extensions.rs`.
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parse::text::simple_extensions::ConcreteType;
     use crate::parse::text::simple_extensions::types::ParameterConstraint as RawParameterType;
 
     const YAML_PARAM_TEST: &str = r#"
@@ -115,6 +165,98 @@ types:
     structure: List<Map<string, u!MissingType>>
 "#;
 
+    const YAML_UNPREFIXED: &str = r#"
+%YAML 1.2
+---
+urn: extension:example.com:unprefixed
+types:
+  - name: point
+    structure:
+      latitude: i32
+      longitude: i32
+  - name: line
+    structure:
+      start: point
+      end: point
+scalar_functions:
+  - name: midpoint
+    impls:
+      - args:
+          - value: line
+        return: point?
+"#;
+
+    const YAML_FUNCTIONS: &str = r#"
+%YAML 1.2
+---
+urn: extension:example.com:functions
+metadata:
+  owner: example
+dependencies:
+  geo: extension:example.com:geometry
+types:
+  - name: point
+    structure:
+      x: i32
+      y: i32
+    metadata:
+      since: 1
+type_variations:
+  - name: compact
+    parent: u!point
+    metadata:
+      encoding: packed
+scalar_functions:
+  - name: add
+    description: Add two values
+    metadata:
+      category: arithmetic
+    impls:
+      - args:
+          - name: x
+            value: i32
+          - name: y
+            value: i32
+        options:
+          rounding:
+            values: [TIE_TO_EVEN, TRUNCATE]
+          overflow:
+            description: Behavior when the result does not fit
+            values: [SILENT, SATURATE, ERROR]
+        nullability: DECLARED_OUTPUT
+        return: i32?
+        implementation:
+          sql: x + y
+          cpp: x + y
+      - args:
+          - value: any1
+        variadic:
+          min: 1
+        sessionDependent: true
+        deterministic: false
+        return: any1
+      - args:
+          - value: decimal<P1, S1>
+          - value: decimal<P2, S2>
+        return: |-
+          init_scale = max(S1, S2)
+          init_prec = init_scale + max(P1 - S1, P2 - S2) + 1
+          prec = min(init_prec, 38)
+          scale = init_prec > 38 ? max(init_scale - (init_prec - 38), 6) : init_scale
+          decimal<prec, scale>
+  - name: concat
+    impls:
+      - args:
+          - value: string
+        variadic:
+          max: 8
+          parameterConsistency: CONSISTENT
+        return: string
+  - name: origin
+    impls:
+      - return: u!point
+"#;
+
     #[test]
     fn yaml_round_trip_integer_param_bounds() {
         let deserialized: RawExtensions = serde_yaml::from_str(YAML_PARAM_TEST).expect("parse ok");
@@ -146,10 +288,113 @@ types:
             .expect_err("expected unresolved type reference error");
 
         match err {
-            SimpleExtensionsError::UnresolvedTypeReference { type_name } => {
+            SimpleExtensionsError::UnresolvedTypeReference { type_name, .. } => {
                 assert_eq!(type_name, "MissingType");
             }
             other => panic!("unexpected error type: {other:?}"),
         }
     }
+
+    #[test]
+    fn lenient_unprefixed_type_references() {
+        let raw: RawExtensions = serde_yaml::from_str(YAML_UNPREFIXED).expect("parse ok");
+        assert!(ExtensionFile::create(raw.clone()).is_err());
+
+        let ext = ExtensionFile::create_with_options(raw, &ParseOptions::lenient())
+            .expect("lenient create ok");
+        assert_eq!(
+            ext.warnings(),
+            [
+                ParseWarning::UnprefixedTypeReference {
+                    name: "line".to_string()
+                },
+                ParseWarning::UnprefixedTypeReference {
+                    name: "point".to_string()
+                },
+            ]
+        );
+        let line = ext.get_type("line").expect("type exists");
+        assert_eq!(
+            line.structure.as_ref().map(ToString::to_string).as_deref(),
+            Some("{start: u!point, end: u!point}")
+        );
+
+        // Argument types are resolved like the other types of the file.
+        let midpoint = ext
+            .extension()
+            .get_scalar_function("midpoint")
+            .expect("function exists");
+        let resolution = midpoint
+            .resolve(&[ConcreteType::extension("line".to_string(), false)])
+            .expect("resolve ok");
+        assert_eq!(resolution.return_type.to_string(), "u!point?");
+    }
+
+    #[test]
+    fn yaml_round_trip_functions_and_metadata() {
+        let deserialized: RawExtensions = serde_yaml::from_str(YAML_FUNCTIONS).expect("parse ok");
+        let ext = ExtensionFile::create(deserialized.clone()).expect("create ok");
+        assert_eq!(ext.extension().metadata().len(), 1);
+
+        let back = ext.to_raw();
+        assert_eq!(back, deserialized);
+        assert_eq!(
+            ExtensionFile::create(back.clone())
+                .expect("create ok")
+                .to_raw(),
+            deserialized
+        );
+
+        // Maps compare equal regardless of their order, so compare the
+        // emitted YAML as well.
+        assert_eq!(
+            serde_yaml::to_string(&back).unwrap(),
+            serde_yaml::to_string(&deserialized).unwrap()
+        );
+    }
+
+    const YAML_UNSORTED: &str = r#"
+%YAML 1.2
+---
+urn: extension:example.com:unsorted
+types:
+  - name: segment
+  - name: point
+scalar_functions:
+  - name: subtract
+    impls:
+      - args:
+          - value: i32
+          - value: i32
+        return: i32
+  - name: add
+    impls:
+      - args:
+          - value: i32
+          - value: i32
+        return: i32
+  - name: subtract
+    impls:
+      - args:
+          - value: i64
+          - value: i64
+        return: i64
+"#;
+
+    #[test]
+    fn yaml_round_trip_declaration_order() {
+        let deserialized: RawExtensions = serde_yaml::from_str(YAML_UNSORTED).expect("parse ok");
+        let back = ExtensionFile::create(deserialized)
+            .expect("create ok")
+            .to_raw();
+
+        let type_names = back.types.iter().map(|t| t.name.as_str());
+        assert_eq!(type_names.collect::<Vec<_>>(), ["segment", "point"]);
+
+        // Functions declared more than once are merged into their first
+        // declaration.
+        let function_names = back.scalar_functions.iter().map(|f| f.name.as_str());
+        assert_eq!(function_names.collect::<Vec<_>>(), ["subtract", "add"]);
+        assert_eq!(back.scalar_functions[0].impls.len(), 2);
+    }
 }
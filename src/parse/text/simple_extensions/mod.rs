@@ -7,33 +7,75 @@
 //! crate:
 //! * [`ExtensionFile`] – a fully validated extension document (URN plus its
 //!   definitions).
+//! * [`ParseOptions`] – options for parsing extension files, such as lenient
+//!   parsing that reports [`ParseWarning`]s instead of failing, and the
+//!   strict mode that fails on skipped function implementations.
 //! * [`SimpleExtensions`] – the validated objects declared by a single
 //!   extension file.
 //! * [`CustomType`] / [`ConcreteType`] – type definitions and resolved type
 //!   structures used when checking function signatures.
+//! * [`TypeVariation`] – type variation definitions, referred to by types as
+//!   `i32[dict_encoded]`.
+//! * [`SignatureType`] – types in function signatures, which may contain type
+//!   variables bound via [`TypeBindings`], and [`TypeDerivation`]s of return
+//!   types computed from those bindings.
 //! * [`ScalarFunction`] – validated scalar function definitions with resolved
-//!   signatures.
+//!   signatures, and [`Resolution`] of calls to their overloads.
+//! * [`AggregateFunction`] / [`WindowFunction`] – validated aggregate and
+//!   window function definitions.
+//! * [`ExtensionFileBuilder`] – a builder for authoring extension files from
+//!   Rust, validated like files read from YAML.
 //! * [`Registry`] – a reusable lookup structure that stores validated extension
 //!   files and exposes typed access to their contents.
 
 use thiserror::Error;
 
+mod aggregate_functions;
 pub mod argument;
+mod builder;
+mod derivation;
+mod diagnostics;
 mod extensions;
 mod file;
 mod registry;
+mod resolution;
 mod scalar_functions;
+mod signature;
 mod type_ast;
+mod type_variations;
 mod types;
+mod window_functions;
 
+pub use aggregate_functions::{AggregateFunction, AggregateImpl, Decomposable};
+pub use builder::{
+    AggregateFunctionBuilder, AggregateImplBuilder, ExtensionFileBuilder, ImplBuilder,
+    ScalarFunctionBuilder, TypeBuilder, TypeVariationBuilder, WindowFunctionBuilder,
+    WindowImplBuilder,
+};
+pub use derivation::{
+    Assignment, BinaryOp, DerivationError, DerivationExpr, DerivationFunction, DerivationValue,
+    TypeDerivation, UnaryOp,
+};
+pub use diagnostics::{FunctionKind, ParseOptions, ParseWarning};
 pub use extensions::SimpleExtensions;
-pub use file::ExtensionFile;
-pub use registry::Registry;
+pub use file::{ExtensionFile, ExtensionFileError};
+pub use registry::{DuplicateUrnPolicy, Registry, RegistryBuilder};
+pub use resolution::{Rejection, RejectionReason, Resolution, ResolutionError};
+pub(crate) use scalar_functions::split_compound_name;
 pub use scalar_functions::{
-    Impl as ScalarFunctionImpl, NullabilityHandling, Options, ScalarFunction, VariadicBehavior,
+    FunctionOption, Impl as ScalarFunctionImpl, NullabilityHandling, Options, ScalarFunction,
+    VariadicBehavior,
+};
+pub use signature::{
+    BindingError, SignatureParameter, SignatureType, SignatureTypeKind, TypeBindings,
 };
 pub use type_ast::TypeExpr;
-pub use types::{ConcreteType, CustomType, ExtensionTypeError};
+pub use type_variations::{TypeVariation, VariationFunctions};
+pub(crate) use types::builtin_from_parts;
+pub use types::{
+    BasicBuiltinType, ConcreteType, ConcreteTypeKind, CustomType, ExtensionTypeError, TypeParameter,
+};
+pub use window_functions::{WindowFunction, WindowImpl, WindowType};
 
 use crate::urn::Urn;
 
@@ -49,26 +91,86 @@ pub enum SimpleExtensionsError {
     /// Scalar function error
     #[error("Scalar function error: {0}")]
     ScalarFunctionError(#[from] scalar_functions::ScalarFunctionError),
+    /// Aggregate function error
+    #[error("Aggregate function error: {0}")]
+    AggregateFunctionError(#[from] aggregate_functions::AggregateFunctionError),
+    /// Window function error
+    #[error("Window function error: {0}")]
+    WindowFunctionError(#[from] window_functions::WindowFunctionError),
     /// Failed to parse SimpleExtensions YAML
     #[error("YAML parse error: {0}")]
     YamlParse(#[from] serde_yaml::Error),
     /// I/O error while reading extension content
     #[error("io error: {0}")]
     Io(#[from] std::io::Error),
+    /// Extension files failed to load, with the error of each failing file
+    #[error("failed to load extension files:{}", display_file_errors(.0))]
+    ExtensionFiles(Vec<ExtensionFileError>),
     /// Invalid URN provided
     #[error("invalid urn")]
     InvalidUrn(#[from] crate::urn::InvalidUrn),
-    /// Unresolved type reference in structure field
-    #[error("Type '{type_name}' referenced in structure not found")]
+    /// Unresolved type reference
+    #[error("Type '{type_name}' referenced in extension `{urn}` not found")]
     UnresolvedTypeReference {
+        /// The URN of the extension referencing the type
+        urn: Urn,
         /// The type name that could not be resolved
         type_name: String,
         // TODO: the location in the file where this came from would be nice
     },
+    /// A dependency alias is not a valid identifier
+    #[error("invalid dependency alias `{alias}`")]
+    InvalidDependencyAlias {
+        /// The invalid alias
+        alias: String,
+    },
+    /// The number of argument names differs from the number of value
+    /// arguments of an implementation, see [`ImplBuilder::arg_names`]
+    #[error("{names} argument names given for {arguments} value arguments")]
+    ArgumentNameCount {
+        /// The number of value arguments of the implementation
+        arguments: usize,
+        /// The number of argument names
+        names: usize,
+    },
+    /// A dependency of an extension is not loaded in the registry
+    #[error("extension `{urn}` depends on `{dependency}` (as `{alias}`), which is not loaded")]
+    MissingDependency {
+        /// The URN of the extension declaring the dependency
+        urn: Urn,
+        /// The alias of the dependency
+        alias: String,
+        /// The URN of the missing dependency
+        dependency: Urn,
+    },
+    /// An implementation of a function uses a feature that is not yet
+    /// supported, while parsing in strict mode
+    #[error("implementation {index} of {kind} function `{name}`: not yet implemented: {reason}")]
+    NotYetImplemented {
+        /// The kind of the function
+        kind: FunctionKind,
+        /// The name of the function
+        name: String,
+        /// The index of the implementation in the function
+        index: usize,
+        /// The feature that is not yet supported
+        reason: String,
+    },
     /// Duplicate type definition within the same extension
     #[error("duplicate type definition for `{name}`")]
     DuplicateTypeName {
         /// The repeated type name
         name: String,
     },
+    /// Duplicate type variation definition within the same extension
+    #[error("duplicate type variation definition for `{name}`")]
+    DuplicateTypeVariationName {
+        /// The repeated type variation name
+        name: String,
+    },
+}
+
+/// Display the errors of extension files, one per line.
+fn display_file_errors(errors: &[ExtensionFileError]) -> String {
+    errors.iter().map(|error| format!("\n  {error}")).collect()
 }
@@ -4,44 +4,136 @@
 //!
 //! This module provides registries for Substrait extensions:
 //! - **Global Registry**: Immutable, reusable across plans, URI+name based lookup
-//! - **Local Registry**: Per-plan, anchor-based, references Global Registry (TODO)
+//! - **Local Registry**: Per-plan, anchor-based, references Global Registry
+//!   (see [`ExtensionAnchors`](crate::parse::proto::ExtensionAnchors))
 //!
-//! Currently only type definitions are supported. Function parsing will be added in a future update.
+//! Registries hold the type, type variation and function definitions of the
+//! extensions, and resolve scalar function calls against them.
 //!
 //! This module is only available when the `parse` feature is enabled.
 
 use std::collections::{HashMap, hash_map::Entry};
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
 
-use super::{ExtensionFile, SimpleExtensions, SimpleExtensionsError, types::CustomType};
+use super::{
+    ConcreteType, ExtensionFile, ExtensionFileError, ParseWarning, RejectionReason, Resolution,
+    ResolutionError, ScalarFunctionImpl, SimpleExtensions, SimpleExtensionsError,
+    VariationFunctions, split_compound_name, types::CustomType,
+};
 use crate::urn::Urn;
 
 /// Extension Registry that manages Substrait extensions
 ///
 /// This registry is immutable and reusable across multiple plans.
-/// It provides URN + name based lookup for extension types. Function parsing will be added in a future update.
+/// It provides URN + name based lookup for extension types, type variations
+/// and functions, and overload resolution of scalar function calls.
 #[derive(Debug)]
 pub struct Registry {
     /// Pre-validated extension files
     extensions: HashMap<Urn, SimpleExtensions>,
+    /// Warnings reported while parsing the extension files, by URN
+    warnings: HashMap<Urn, Vec<ParseWarning>>,
 }
 
 impl Registry {
     /// Create a new Global Registry from validated extension files.
     ///
-    /// Any duplicate URNs will raise an error.
+    /// Any duplicate URNs will raise an error. The dependencies of all
+    /// extension files must be included, and types referenced through them
+    /// (`u!alias.name`) must exist.
     pub fn new<I: IntoIterator<Item = ExtensionFile>>(
         extensions: I,
     ) -> Result<Self, SimpleExtensionsError> {
-        let mut map = HashMap::new();
-        for ExtensionFile { urn, extension } in extensions {
-            match map.entry(urn.clone()) {
-                Entry::Occupied(_) => return Err(SimpleExtensionsError::DuplicateUrn(urn)),
-                Entry::Vacant(entry) => {
-                    entry.insert(extension);
+        extensions
+            .into_iter()
+            .try_fold(
+                RegistryBuilder::default(),
+                RegistryBuilder::add_extension_file,
+            )?
+            .build()
+    }
+
+    /// Create an empty [RegistryBuilder].
+    pub fn builder() -> RegistryBuilder {
+        RegistryBuilder::default()
+    }
+
+    /// Create a [RegistryBuilder] with the extension files of this registry,
+    /// to add more extension files on top (e.g. of
+    /// [Registry::from_core_extensions]).
+    pub fn into_builder(self) -> RegistryBuilder {
+        RegistryBuilder {
+            extensions: self.extensions,
+            warnings: self.warnings,
+            ..Default::default()
+        }
+    }
+
+    /// Merge two registries. Extension files with the same URN in both
+    /// registries raise an error; use a [RegistryBuilder] with a
+    /// [DuplicateUrnPolicy] to override them instead.
+    pub fn merge(self, other: Registry) -> Result<Self, SimpleExtensionsError> {
+        self.into_builder().add_registry(other)?.build()
+    }
+
+    /// Create a new Global Registry from all extension files (`*.yaml` and
+    /// `*.yml`) in a
+    /// directory and its subdirectories. Symbolic links to directories are
+    /// not followed.
+    ///
+    /// Every file is loaded with [ExtensionFile::read]; if any of them fail,
+    /// the errors of all failing files are returned, with their paths (see
+    /// [SimpleExtensionsError::ExtensionFiles]). The loaded files are combined
+    /// with [Registry::new].
+    pub fn from_dir(path: impl AsRef<Path>) -> Result<Self, SimpleExtensionsError> {
+        let mut paths = Vec::new();
+        let mut errors = Vec::new();
+        collect_yaml_files(path.as_ref(), &mut paths, &mut errors);
+        paths.sort();
+
+        let mut extensions = Vec::with_capacity(paths.len());
+        for path in paths {
+            match File::open(&path)
+                .map_err(Into::into)
+                .and_then(|file| ExtensionFile::read(BufReader::new(file)))
+            {
+                Ok(extension) => extensions.push(extension),
+                Err(error) => errors.push(ExtensionFileError { path, error }),
+            }
+        }
+
+        if errors.is_empty() {
+            Self::new(extensions)
+        } else {
+            Err(SimpleExtensionsError::ExtensionFiles(errors))
+        }
+    }
+
+    /// Check that the dependencies of all extension files are loaded, and that
+    /// the types referenced through them exist.
+    fn validate_dependencies(&self) -> Result<(), SimpleExtensionsError> {
+        for (urn, extension) in &self.extensions {
+            for (alias, dependency) in extension.dependencies() {
+                if !self.extensions.contains_key(dependency) {
+                    return Err(SimpleExtensionsError::MissingDependency {
+                        urn: urn.clone(),
+                        alias: alias.to_string(),
+                        dependency: dependency.clone(),
+                    });
+                }
+            }
+            for name in extension.dependency_types() {
+                if self.resolve_type(urn, name).is_none() {
+                    return Err(SimpleExtensionsError::UnresolvedTypeReference {
+                        urn: urn.clone(),
+                        type_name: name.to_string(),
+                    });
                 }
             }
         }
-        Ok(Self { extensions: map })
+        Ok(())
     }
 
     /// Get an iterator over all extension files in this registry
@@ -49,38 +141,56 @@ impl Registry {
         self.extensions.iter()
     }
 
+    /// Get an iterator over the warnings reported while parsing the extension
+    /// files in this registry (see [ExtensionFile::warnings]), with the URN of
+    /// the extension file they were reported for.
+    pub fn warnings(&self) -> impl Iterator<Item = (&Urn, &ParseWarning)> {
+        self.warnings
+            .iter()
+            .flat_map(|(urn, warnings)| warnings.iter().map(move |warning| (urn, warning)))
+    }
+
     /// Create a Global Registry from the built-in core extensions.
     ///
-    /// Most core extensions are included. Some are skipped due to bugs in the upstream
-    /// YAML files (see <https://github.com/substrait-io/substrait/issues/935>).
+    /// Core extensions are parsed with [`ParseOptions::lenient`](super::ParseOptions::lenient()), as some of
+    /// the upstream YAML files reference their own types without the `u!`
+    /// prefix (see <https://github.com/substrait-io/substrait/issues/935>).
+    /// The warnings for these are available from [Registry::warnings].
     #[cfg(feature = "extensions")]
     pub fn from_core_extensions() -> Self {
+        use super::ParseOptions;
         use crate::extensions::EXTENSIONS;
 
         // Parse the core extensions from the raw extensions format to the parsed format
-        let extensions: HashMap<Urn, SimpleExtensions> = EXTENSIONS
-            .iter()
-            .filter_map(|(orig_urn, simple_extensions)| {
-                // Skip specific core extensions that have bugs (missing u! prefix on type references).
-                // Most core extensions are included; only these problematic ones are filtered out.
-                // See: https://github.com/substrait-io/substrait/issues/935
-                let urn_str = orig_urn.to_string();
-                if urn_str == "extension:io.substrait:extension_types" ||
-                   urn_str == "extension:io.substrait:unknown" {
-                    return None;
-                }
-
-                let ExtensionFile { urn, extension } = ExtensionFile::create(simple_extensions.clone())
-                    .unwrap_or_else(|err| panic!("Core extensions should be valid, but failed to create extension file for {orig_urn}: {err}"));
-                debug_assert_eq!(orig_urn, &urn);
-                Some((urn, extension))
-            })
-            .collect();
+        let options = ParseOptions::lenient();
+        let mut extensions = HashMap::new();
+        let mut warnings = HashMap::new();
+        for (orig_urn, simple_extensions) in EXTENSIONS.iter() {
+            let file = ExtensionFile::create_with_options(simple_extensions.clone(), &options)
+                .unwrap_or_else(|err| panic!("Core extensions should be valid, but failed to create extension file for {orig_urn}: {err}"));
+            let ExtensionFile {
+                urn,
+                extension,
+                warnings: file_warnings,
+            } = file;
+            debug_assert_eq!(orig_urn, &urn);
+            if !file_warnings.is_empty() {
+                warnings.insert(urn.clone(), file_warnings);
+            }
+            extensions.insert(urn, extension);
+        }
 
-        Self { extensions }
+        let registry = Self {
+            extensions,
+            warnings,
+        };
+        registry.validate_dependencies().unwrap_or_else(|err| {
+            panic!("Core extension dependencies should be included, but failed to resolve: {err}")
+        });
+        registry
     }
 
-    fn get_extension(&self, urn: &Urn) -> Option<&SimpleExtensions> {
+    pub(crate) fn get_extension(&self, urn: &Urn) -> Option<&SimpleExtensions> {
         self.extensions.get(urn)
     }
 
@@ -89,20 +199,296 @@ impl Registry {
         self.get_extension(urn)?.get_type(name)
     }
 
+    /// Resolve a type referenced in the extension file with the given URN.
+    ///
+    /// Names of the form `alias.name` refer to a type of the dependency with
+    /// that alias; other names refer to types of the extension itself. Returns
+    /// the URN of the extension defining the type, and the type.
+    pub fn resolve_type(&self, urn: &Urn, name: &str) -> Option<(&Urn, &CustomType)> {
+        let extension = self.get_extension(urn)?;
+        let (urn, name) = match name
+            .split_once('.')
+            .and_then(|(alias, name)| Some((extension.get_dependency(alias)?, name)))
+        {
+            Some((dependency, name)) => (dependency, name),
+            None => (urn, name),
+        };
+        let (urn, extension) = self.extensions.get_key_value(urn)?;
+        Some((urn, extension.get_type(name)?))
+    }
+
+    /// Get a type variation by URN and name
+    pub fn get_type_variation(&self, urn: &Urn, name: &str) -> Option<&super::TypeVariation> {
+        self.get_extension(urn)?.get_type_variation(name)
+    }
+
     /// Get a scalar function by URN and name.
     ///
-    /// TODO: Add support for retrieving functions by their full signature shorthand
-    /// (e.g., "add:i32_i32").
+    /// See [Registry::get_scalar_function_impl] to look up an implementation
+    /// by its compound name (e.g., "add:i32_i32").
     pub fn get_scalar_function(&self, urn: &Urn, name: &str) -> Option<&super::ScalarFunction> {
         self.get_extension(urn)?.get_scalar_function(name)
     }
+
+    /// Get a scalar function implementation by URN and compound name (e.g.,
+    /// "add:i32_i32").
+    ///
+    /// A plain function name (e.g., "add") only resolves when the function has
+    /// exactly one implementation.
+    pub fn get_scalar_function_impl(
+        &self,
+        urn: &Urn,
+        compound_name: &str,
+    ) -> Option<&ScalarFunctionImpl> {
+        let (name, _) = split_compound_name(compound_name);
+        self.get_scalar_function(urn, name)?
+            .get_impl_by_compound_name(compound_name)
+    }
+
+    /// Get an aggregate function by URN and name.
+    pub fn get_aggregate_function(
+        &self,
+        urn: &Urn,
+        name: &str,
+    ) -> Option<&super::AggregateFunction> {
+        self.get_extension(urn)?.get_aggregate_function(name)
+    }
+
+    /// Get a window function by URN and name.
+    pub fn get_window_function(&self, urn: &Urn, name: &str) -> Option<&super::WindowFunction> {
+        self.get_extension(urn)?.get_window_function(name)
+    }
+
+    /// Resolve a call of a scalar function by URN and name, selecting the
+    /// implementation that matches the given argument types.
+    ///
+    /// See [ScalarFunction::resolve](super::ScalarFunction::resolve). Type
+    /// variations of the arguments are looked up in the same extension file:
+    /// variations with [separate](super::VariationFunctions::Separate)
+    /// functions only match declared types of that variation. Extension types
+    /// of the signatures are qualified by the URN of the extension defining
+    /// them (see [Registry::resolve_type]), so that they match the types of
+    /// plans.
+    pub fn resolve_scalar_function(
+        &self,
+        urn: &Urn,
+        name: &str,
+        arguments: &[ConcreteType],
+    ) -> Result<Resolution<'_>, ResolutionError> {
+        let unknown = || ResolutionError::UnknownFunction {
+            name: name.to_string(),
+        };
+        let extension = self.get_extension(urn).ok_or_else(unknown)?;
+        extension
+            .get_scalar_function(name)
+            .ok_or_else(unknown)?
+            .resolve_with_variations(
+                arguments,
+                |variation| self.variation_functions(extension, variation),
+                |type_name| self.type_urn(urn, type_name),
+            )
+    }
+
+    /// Returns the return type of a call of an implementation of a function
+    /// defined by the extension with the given URN, like
+    /// [Registry::resolve_scalar_function] for a single implementation.
+    pub fn resolve_call(
+        &self,
+        urn: &Urn,
+        implementation: &ScalarFunctionImpl,
+        arguments: &[ConcreteType],
+    ) -> Result<ConcreteType, RejectionReason> {
+        let extension = self.get_extension(urn);
+        implementation.matches(
+            arguments,
+            &|variation| {
+                extension.map_or(VariationFunctions::Inherits, |extension| {
+                    self.variation_functions(extension, variation)
+                })
+            },
+            &|type_name| self.type_urn(urn, type_name),
+        )
+    }
+
+    /// Returns how the functions of a parent type apply to a type variation
+    /// of an extension.
+    fn variation_functions(
+        &self,
+        extension: &SimpleExtensions,
+        variation: &str,
+    ) -> VariationFunctions {
+        extension
+            .get_type_variation(variation)
+            .map_or(VariationFunctions::Inherits, |v| v.functions)
+    }
+
+    /// Returns the URN of the extension defining a type referenced in the
+    /// extension with the given URN, see [Registry::resolve_type].
+    fn type_urn(&self, urn: &Urn, name: &str) -> Option<Urn> {
+        self.resolve_type(urn, name).map(|(urn, _)| urn.clone())
+    }
+}
+
+/// How a [RegistryBuilder] handles an extension file with a URN that was
+/// already added.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DuplicateUrnPolicy {
+    /// Raise [SimpleExtensionsError::DuplicateUrn]
+    #[default]
+    Error,
+    /// Replace the existing extension file with the new one
+    Replace,
+    /// Keep the existing extension file, ignoring the new one
+    Keep,
+}
+
+/// A builder for a [Registry], combining extension files and other
+/// registries.
+///
+/// ```
+/// # use substrait::parse::text::simple_extensions::{DuplicateUrnPolicy, ExtensionFile, Registry, SimpleExtensionsError};
+/// # fn build(
+/// #     core: Registry,
+/// #     extension_file: ExtensionFile,
+/// # ) -> Result<Registry, SimpleExtensionsError> {
+/// core.into_builder()
+///     .duplicate_urn_policy(DuplicateUrnPolicy::Replace)
+///     .add_extension_file(extension_file)?
+///     .build()
+/// # }
+/// ```
+///
+/// Dependencies between the extension files are checked when the registry is
+/// built, so extension files can be added in any order.
+#[derive(Debug, Default)]
+pub struct RegistryBuilder {
+    /// The extension files added so far
+    extensions: HashMap<Urn, SimpleExtensions>,
+    /// Warnings reported while parsing the extension files added so far
+    warnings: HashMap<Urn, Vec<ParseWarning>>,
+    /// How to handle extension files with a URN that was already added
+    duplicate_urn_policy: DuplicateUrnPolicy,
+}
+
+impl RegistryBuilder {
+    /// Set how extension files with a URN that was already added are handled.
+    /// Defaults to [DuplicateUrnPolicy::Error].
+    pub fn duplicate_urn_policy(mut self, policy: DuplicateUrnPolicy) -> Self {
+        self.duplicate_urn_policy = policy;
+        self
+    }
+
+    /// Add an extension file.
+    pub fn add_extension_file(
+        mut self,
+        extension_file: ExtensionFile,
+    ) -> Result<Self, SimpleExtensionsError> {
+        let ExtensionFile {
+            urn,
+            extension,
+            warnings,
+        } = extension_file;
+        self.insert(urn, extension, warnings)?;
+        Ok(self)
+    }
+
+    /// Add all extension files of a registry.
+    pub fn add_registry(mut self, registry: Registry) -> Result<Self, SimpleExtensionsError> {
+        let Registry {
+            extensions,
+            mut warnings,
+        } = registry;
+        for (urn, extension) in extensions {
+            let warnings = warnings.remove(&urn).unwrap_or_default();
+            self.insert(urn, extension, warnings)?;
+        }
+        Ok(self)
+    }
+
+    /// Insert an extension file and its warnings, applying the duplicate URN
+    /// policy.
+    fn insert(
+        &mut self,
+        urn: Urn,
+        extension: SimpleExtensions,
+        warnings: Vec<ParseWarning>,
+    ) -> Result<(), SimpleExtensionsError> {
+        match (self.extensions.entry(urn), self.duplicate_urn_policy) {
+            (Entry::Vacant(entry), _) => {
+                self.warnings.insert(entry.key().clone(), warnings);
+                entry.insert(extension);
+            }
+            (Entry::Occupied(entry), DuplicateUrnPolicy::Error) => {
+                return Err(SimpleExtensionsError::DuplicateUrn(entry.key().clone()));
+            }
+            (Entry::Occupied(mut entry), DuplicateUrnPolicy::Replace) => {
+                self.warnings.insert(entry.key().clone(), warnings);
+                entry.insert(extension);
+            }
+            (Entry::Occupied(_), DuplicateUrnPolicy::Keep) => {}
+        }
+        Ok(())
+    }
+
+    /// Build the [Registry], checking that the dependencies of all extension
+    /// files are included.
+    pub fn build(self) -> Result<Registry, SimpleExtensionsError> {
+        let mut warnings = self.warnings;
+        warnings.retain(|_, warnings| !warnings.is_empty());
+        let registry = Registry {
+            extensions: self.extensions,
+            warnings,
+        };
+        registry.validate_dependencies()?;
+        Ok(registry)
+    }
+}
+
+/// Collect the paths of all `*.yaml` and `*.yml` files in a directory and its
+/// subdirectories. Directories that can't be read are recorded as errors.
+///
+/// Symbolic links to directories are not followed, so that links back to a
+/// parent directory don't recurse endlessly.
+fn collect_yaml_files(dir: &Path, paths: &mut Vec<PathBuf>, errors: &mut Vec<ExtensionFileError>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) => {
+            errors.push(ExtensionFileError {
+                path: dir.to_path_buf(),
+                error: error.into(),
+            });
+            return;
+        }
+    };
+    for entry in entries {
+        let (path, file_type) = match entry.and_then(|entry| Ok((entry.path(), entry.file_type()?)))
+        {
+            Ok(entry) => entry,
+            Err(error) => {
+                errors.push(ExtensionFileError {
+                    path: dir.to_path_buf(),
+                    error: error.into(),
+                });
+                continue;
+            }
+        };
+        if file_type.is_dir() {
+            collect_yaml_files(&path, paths, errors);
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension == "yaml" || extension == "yml")
+        {
+            paths.push(path);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{ExtensionFile, Registry};
+    use super::{ConcreteType, DuplicateUrnPolicy, ExtensionFile, ParseWarning, Registry};
     use crate::parse::text::simple_extensions::{
-        SimpleExtensionsError, scalar_functions::ScalarFunctionError, types::ExtensionTypeError,
+        ParseOptions, SimpleExtensionsError, scalar_functions::ScalarFunctionError,
+        types::ExtensionTypeError,
     };
     use crate::text::simple_extensions::{SimpleExtensions, SimpleExtensionsTypesItem};
     use crate::urn::Urn;
@@ -177,6 +563,421 @@ mod tests {
         }
     }
 
+    const YAML_GEOMETRY: &str = r#"
+%YAML 1.2
+---
+urn: extension:example.com:geometry
+types:
+  - name: point
+    structure:
+      x: fp64
+      y: fp64
+"#;
+
+    const YAML_SHAPES: &str = r#"
+%YAML 1.2
+---
+urn: extension:example.com:shapes
+dependencies:
+  geo: extension:example.com:geometry
+types:
+  - name: segment
+    structure:
+      start: u!geo.point
+      end: u!geo.point
+scalar_functions:
+  - name: length
+    impls:
+      - args:
+          - value: u!segment
+        return: fp64
+  - name: midpoint
+    impls:
+      - args:
+          - value: u!segment
+        return: u!geo.point
+  - name: distance
+    impls:
+      - args:
+          - value: u!geo.point
+          - value: u!geo.point
+        return: fp64
+"#;
+
+    #[test]
+    fn test_dependencies() {
+        let geometry = ExtensionFile::read_from_str(YAML_GEOMETRY).unwrap();
+        let shapes = ExtensionFile::read_from_str(YAML_SHAPES).unwrap();
+        let geometry_urn = geometry.urn().clone();
+        let shapes_urn = shapes.urn().clone();
+        assert_eq!(
+            shapes.extension().get_dependency("geo"),
+            Some(&geometry_urn)
+        );
+        assert_eq!(
+            shapes.to_raw().dependencies.values().collect::<Vec<_>>(),
+            ["extension:example.com:geometry"]
+        );
+
+        let registry = Registry::new(vec![geometry, shapes]).unwrap();
+        let (urn, point) = registry.resolve_type(&shapes_urn, "geo.point").unwrap();
+        assert_eq!((urn, point.name.as_str()), (&geometry_urn, "point"));
+        let (urn, _) = registry.resolve_type(&shapes_urn, "segment").unwrap();
+        assert_eq!(urn, &shapes_urn);
+        assert!(registry.resolve_type(&shapes_urn, "point").is_none());
+
+        // The dependency must be loaded in the registry.
+        let shapes = ExtensionFile::read_from_str(YAML_SHAPES).unwrap();
+        assert!(matches!(
+            Registry::new(vec![shapes]),
+            Err(SimpleExtensionsError::MissingDependency { urn, alias, dependency })
+                if urn == shapes_urn && alias == "geo" && dependency == geometry_urn
+        ));
+
+        // Types referenced through dependencies must exist.
+        let geometry = ExtensionFile::read_from_str(YAML_GEOMETRY).unwrap();
+        let shapes =
+            ExtensionFile::read_from_str(YAML_SHAPES.replace("u!geo.point", "u!geo.line")).unwrap();
+        assert!(matches!(
+            Registry::new(vec![geometry, shapes]),
+            Err(SimpleExtensionsError::UnresolvedTypeReference { urn, type_name })
+                if urn == shapes_urn && type_name == "geo.line"
+        ));
+
+        // Undeclared aliases do not resolve.
+        assert!(matches!(
+            ExtensionFile::read_from_str(YAML_SHAPES.replace("u!geo.point", "u!geom.point")),
+            Err(SimpleExtensionsError::UnresolvedTypeReference { type_name, .. }) if type_name == "geom.point"
+        ));
+    }
+
+    #[test]
+    fn test_resolve_dependency_types() {
+        let geometry = ExtensionFile::read_from_str(YAML_GEOMETRY).unwrap();
+        let shapes = ExtensionFile::read_from_str(YAML_SHAPES).unwrap();
+        let geometry_urn = geometry.urn().clone();
+        let shapes_urn = shapes.urn().clone();
+        let registry = Registry::new(vec![geometry, shapes]).unwrap();
+
+        // Types of plans are named as defined, qualified by their extension.
+        let plan_type = |urn: &Urn, name: &str| {
+            let mut concrete = ConcreteType::extension(name.to_string(), false);
+            concrete.qualify(&|_| Some(urn.clone()));
+            concrete
+        };
+        let point = plan_type(&geometry_urn, "point");
+        let segment = plan_type(&shapes_urn, "segment");
+
+        let resolution = registry
+            .resolve_scalar_function(&shapes_urn, "midpoint", std::slice::from_ref(&segment))
+            .unwrap();
+        assert_eq!(resolution.return_type, point);
+        let resolution = registry
+            .resolve_scalar_function(&shapes_urn, "distance", &[point.clone(), point.clone()])
+            .unwrap();
+        assert_eq!(resolution.return_type.to_string(), "fp64");
+
+        // Types with the same name from other extensions do not match.
+        let other_point = plan_type(&shapes_urn, "point");
+        assert!(
+            registry
+                .resolve_scalar_function(&shapes_urn, "distance", &[point, other_point])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_builder() {
+        let geometry = || ExtensionFile::read_from_str(YAML_GEOMETRY).unwrap();
+        let shapes = || ExtensionFile::read_from_str(YAML_SHAPES).unwrap();
+        let geometry_urn = geometry().urn().clone();
+        let shapes_urn = shapes().urn().clone();
+
+        // Dependencies may be added after the extension files using them.
+        let registry = Registry::builder()
+            .add_extension_file(shapes())
+            .unwrap()
+            .add_extension_file(geometry())
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(registry.extensions().count(), 2);
+
+        // Duplicate URNs raise an error by default.
+        let registry = Registry::new([geometry()]).unwrap();
+        assert!(matches!(
+            registry.into_builder().add_extension_file(geometry()),
+            Err(SimpleExtensionsError::DuplicateUrn(urn)) if urn == geometry_urn
+        ));
+
+        // Replace the geometry extension with a version defining more types.
+        let extended = ExtensionFile::read_from_str(format!(
+            "{YAML_GEOMETRY}  - name: line\n    structure: \"list<u!point>\"\n"
+        ))
+        .unwrap();
+        let registry = Registry::new([geometry()])
+            .unwrap()
+            .into_builder()
+            .duplicate_urn_policy(DuplicateUrnPolicy::Replace)
+            .add_extension_file(extended)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(registry.get_type(&geometry_urn, "line").is_some());
+
+        // Keep the existing extension instead.
+        let registry = registry
+            .into_builder()
+            .duplicate_urn_policy(DuplicateUrnPolicy::Keep)
+            .add_extension_file(geometry())
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(registry.get_type(&geometry_urn, "line").is_some());
+
+        // Missing dependencies are detected when building.
+        let builder = Registry::builder().add_extension_file(shapes()).unwrap();
+        assert!(matches!(
+            builder.build(),
+            Err(SimpleExtensionsError::MissingDependency { urn, .. }) if urn == shapes_urn
+        ));
+    }
+
+    #[test]
+    fn test_warnings() {
+        let raw = serde_yaml::from_str(&YAML_SHAPES.replace("value: u!segment", "value: segment"))
+            .unwrap();
+        let shapes = ExtensionFile::create_with_options(raw, &ParseOptions::lenient()).unwrap();
+        let shapes_urn = shapes.urn().clone();
+        let geometry = ExtensionFile::read_from_str(YAML_GEOMETRY).unwrap();
+
+        // Warnings of the extension files are kept.
+        let registry = Registry::new([shapes, geometry]).unwrap();
+        let warning = ParseWarning::UnprefixedTypeReference {
+            name: "segment".to_string(),
+        };
+        assert_eq!(
+            registry.warnings().collect::<Vec<_>>(),
+            [(&shapes_urn, &warning)]
+        );
+
+        // Replacing an extension file replaces its warnings.
+        let registry = registry
+            .into_builder()
+            .duplicate_urn_policy(DuplicateUrnPolicy::Replace)
+            .add_extension_file(ExtensionFile::read_from_str(YAML_SHAPES).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(registry.warnings().count(), 0);
+    }
+
+    #[test]
+    fn test_merge() {
+        let geometry =
+            Registry::new(vec![ExtensionFile::read_from_str(YAML_GEOMETRY).unwrap()]).unwrap();
+
+        let other =
+            Registry::new(vec![extension_file("extension:example.com:other", &["a"])]).unwrap();
+        let merged = geometry.merge(other).unwrap();
+        assert_eq!(merged.extensions().count(), 2);
+
+        let duplicate =
+            Registry::new(vec![extension_file("extension:example.com:other", &["b"])]).unwrap();
+        assert!(matches!(
+            merged.merge(duplicate),
+            Err(SimpleExtensionsError::DuplicateUrn(urn)) if urn.to_string() == "extension:example.com:other"
+        ));
+    }
+
+    #[test]
+    fn test_from_dir() {
+        let dir = TempDir::new("substrait-registry");
+        let dir = dir.path();
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        // Both `.yaml` and `.yml` files are loaded.
+        std::fs::write(dir.join("geometry.yaml"), YAML_GEOMETRY).unwrap();
+        std::fs::write(dir.join("nested").join("shapes.yml"), YAML_SHAPES).unwrap();
+        std::fs::write(dir.join("notes.txt"), "not an extension").unwrap();
+
+        let registry = Registry::from_dir(dir).unwrap();
+        assert_eq!(registry.extensions().count(), 2);
+
+        // Symbolic links to directories are not followed.
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(dir, dir.join("nested").join("parent")).unwrap();
+            let registry = Registry::from_dir(dir).unwrap();
+            assert_eq!(registry.extensions().count(), 2);
+        }
+
+        // Errors are reported for every failing file.
+        std::fs::write(dir.join("invalid.yaml"), "urn: [").unwrap();
+        std::fs::write(
+            dir.join("nested").join("unresolved.yaml"),
+            YAML_GEOMETRY
+                .replace("geometry", "unresolved")
+                .replace("fp64", "u!missing"),
+        )
+        .unwrap();
+        let result = Registry::from_dir(dir);
+        let Err(SimpleExtensionsError::ExtensionFiles(errors)) = result else {
+            panic!("expected extension file errors, got {result:?}");
+        };
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].path, dir.join("invalid.yaml"));
+        assert!(matches!(
+            errors[0].error,
+            SimpleExtensionsError::YamlParse(_)
+        ));
+        assert_eq!(errors[1].path, dir.join("nested").join("unresolved.yaml"));
+        assert!(matches!(
+            &errors[1].error,
+            SimpleExtensionsError::UnresolvedTypeReference { type_name, .. } if type_name == "missing"
+        ));
+    }
+
+    /// A temporary directory, removed when dropped (also when a test fails).
+    struct TempDir(std::path::PathBuf);
+
+    impl TempDir {
+        /// Create a new, empty directory, unique to the calling test.
+        fn new(prefix: &str) -> Self {
+            use std::sync::atomic::{AtomicUsize, Ordering};
+
+            static COUNTER: AtomicUsize = AtomicUsize::new(0);
+            let path = std::env::temp_dir().join(format!(
+                "{prefix}-{}-{}",
+                std::process::id(),
+                COUNTER.fetch_add(1, Ordering::Relaxed)
+            ));
+            // Remove leftovers of an earlier run with the same process id.
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(&path).unwrap();
+            TempDir(path)
+        }
+
+        fn path(&self) -> &std::path::Path {
+            &self.0
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_from_dir_missing() {
+        let temp_dir = TempDir::new("substrait-registry-missing");
+        let dir = temp_dir.path().join("missing");
+        assert!(!dir.exists());
+        assert!(matches!(
+            Registry::from_dir(&dir),
+            Err(SimpleExtensionsError::ExtensionFiles(errors))
+                if errors.len() == 1 && errors[0].path == dir
+        ));
+    }
+
+    const YAML_COMPOUND_NAMES: &str = r#"
+%YAML 1.2
+---
+urn: extension:example.com:compound
+scalar_functions:
+  - name: add
+    impls:
+      - args:
+          - name: overflow
+            options: [SILENT, SATURATE, ERROR]
+          - value: i32
+          - value: i32
+        return: i32
+      - args:
+          - value: string
+          - value: VARCHAR<L1>
+        return: string
+  - name: is_null
+    impls:
+      - args:
+          - value: boolean?
+        return: boolean
+"#;
+
+    #[test]
+    fn test_scalar_function_impl_lookup() {
+        let file = ExtensionFile::read_from_str(YAML_COMPOUND_NAMES).unwrap();
+        let urn = file.urn().clone();
+        let registry = Registry::new([file]).unwrap();
+
+        let cases = vec![
+            ("add:req_i32_i32", Some("i32")),
+            ("add:str_vchar", Some("string")),
+            ("add:i32_i32", None),
+            ("add", None),
+            ("is_null", Some("bool")),
+            ("is_null:bool", Some("bool")),
+            ("missing:i32", None),
+        ];
+
+        for (compound_name, expected) in cases {
+            assert_eq!(
+                registry
+                    .get_scalar_function_impl(&urn, compound_name)
+                    .map(|impl_| impl_.return_type.to_string()),
+                expected.map(ToString::to_string),
+                "unexpected lookup result for {compound_name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_compound_name_round_trip() {
+        let file = ExtensionFile::read_from_str(YAML_COMPOUND_NAMES).unwrap();
+        let urn = file.urn().clone();
+        let registry = Registry::new([file]).unwrap();
+
+        let add = registry.get_scalar_function(&urn, "add").unwrap();
+        let compound_names: Vec<_> = add
+            .impls
+            .iter()
+            .map(|impl_| impl_.compound_name(&add.name))
+            .collect();
+        assert_eq!(compound_names, ["add:req_i32_i32", "add:str_vchar"]);
+
+        for (impl_, compound_name) in add.impls.iter().zip(compound_names) {
+            assert_eq!(
+                registry.get_scalar_function_impl(&urn, &compound_name),
+                Some(impl_)
+            );
+        }
+    }
+
+    #[test]
+    fn test_aggregate_function_lookup() {
+        let file = ExtensionFile::read_from_str(
+            r#"
+urn: extension:example.com:aggregates
+aggregate_functions:
+  - name: count
+    impls:
+      - args:
+          - value: any
+        decomposable: MANY
+        intermediate: i64
+        return: i64
+"#,
+        )
+        .unwrap();
+        let urn = file.urn().clone();
+        let registry = Registry::new([file]).unwrap();
+
+        let count = registry.get_aggregate_function(&urn, "count").unwrap();
+        assert_eq!(count.impls[0].impl_.compound_name(&count.name), "count:any");
+        assert!(registry.get_aggregate_function(&urn, "sum").is_none());
+        assert!(registry.get_scalar_function(&urn, "count").is_none());
+    }
+
     #[cfg(feature = "extensions")]
     #[test]
     fn test_from_core_extensions() {
@@ -199,14 +1000,63 @@ mod tests {
         let type_via_registry = registry.get_type(&urn, "geometry");
         assert!(type_via_registry.is_some());
 
-        // Verify extension_types is skipped due to u! prefix bug (substrait#935)
+        // Aggregate functions are loaded
+        let arithmetic_urn = Urn::from_str("extension:io.substrait:functions_arithmetic").unwrap();
+        for name in ["sum", "avg"] {
+            assert!(
+                registry
+                    .get_aggregate_function(&arithmetic_urn, name)
+                    .is_some(),
+                "Should find aggregate function '{name}'"
+            );
+        }
+        let generic_urn =
+            Urn::from_str("extension:io.substrait:functions_aggregate_generic").unwrap();
+        assert!(
+            registry
+                .get_aggregate_function(&generic_urn, "count")
+                .is_some()
+        );
+
+        // Window functions are loaded
+        for name in ["rank", "lead", "lag"] {
+            assert!(
+                registry
+                    .get_window_function(&arithmetic_urn, name)
+                    .is_some(),
+                "Should find window function '{name}'"
+            );
+        }
+
+        // extension_types and unknown reference their own types without the
+        // u! prefix (substrait#935), and load in lenient mode
         let extension_types_urn = Urn::from_str("extension:io.substrait:extension_types").unwrap();
+        assert!(registry.get_type(&extension_types_urn, "point").is_some());
+        let unknown_urn = Urn::from_str("extension:io.substrait:unknown").unwrap();
+        assert!(registry.get_type(&unknown_urn, "unknown").is_some());
         assert!(
-            registry.get_extension(&extension_types_urn).is_none(),
-            "extension_types should be skipped due to missing u! prefix bug"
+            registry
+                .warnings()
+                .any(|(urn, _)| urn == &extension_types_urn)
         );
     }
 
+    /// [Registry::from_core_extensions] panics if the dependencies of the core
+    /// extensions are not satisfied, so check them separately.
+    #[cfg(feature = "extensions")]
+    #[test]
+    fn test_core_extension_dependencies() {
+        use crate::extensions::EXTENSIONS;
+
+        let options = ParseOptions::lenient();
+        let files = EXTENSIONS.iter().map(|(urn, simple_extensions)| {
+            ExtensionFile::create_with_options(simple_extensions.clone(), &options)
+                .unwrap_or_else(|err| panic!("invalid core extension {urn}: {err}"))
+        });
+        let registry = Registry::new(files);
+        assert!(registry.is_ok(), "{registry:?}");
+    }
+
     #[test]
     fn test_unknown_type_without_prefix_fails() {
         use crate::text::simple_extensions;
@@ -334,7 +1184,7 @@ mod tests {
         );
 
         match result {
-            Err(SimpleExtensionsError::UnresolvedTypeReference { type_name }) => {
+            Err(SimpleExtensionsError::UnresolvedTypeReference { type_name, .. }) => {
                 assert_eq!(type_name, "rectangle");
             }
             other => panic!("Expected UnresolvedTypeReference error, got {:?}", other),
@@ -345,13 +1195,14 @@ mod tests {
     #[test]
     fn test_scalar_function_parses_completely() {
         use super::super::{
+            SignatureType,
             argument::ArgumentsItem,
-            scalar_functions::{Impl, NullabilityHandling, Options},
+            scalar_functions::{FunctionOption, Impl, NullabilityHandling, Options},
             types::*,
         };
         use crate::parse::Parse;
         use crate::text::simple_extensions;
-        use std::collections::HashMap;
+        use indexmap::IndexMap;
 
         let registry = Registry::from_core_extensions();
         let functions_arithmetic_urn =
@@ -371,6 +1222,11 @@ mod tests {
 
         // Create the expected first implementation (i8 + i8 -> i8)
         let mut ctx = super::super::extensions::TypeContext::default();
+        let i8 = SignatureType::from(ConcreteType {
+            kind: ConcreteTypeKind::Builtin(BasicBuiltinType::I8),
+            nullable: false,
+            variation: None,
+        });
         let expected_impl = Impl {
             args: vec![
                 ArgumentsItem::ValueArgument(
@@ -394,15 +1250,19 @@ mod tests {
                     .unwrap(),
                 ),
             ],
+            argument_types: vec![i8.clone(), i8.clone()],
             options: Options({
-                let mut map = HashMap::new();
+                let mut map = IndexMap::new();
                 map.insert(
                     "overflow".to_string(),
-                    vec![
-                        "SILENT".to_string(),
-                        "SATURATE".to_string(),
-                        "ERROR".to_string(),
-                    ],
+                    FunctionOption {
+                        description: None,
+                        values: vec![
+                            "SILENT".to_string(),
+                            "SATURATE".to_string(),
+                            "ERROR".to_string(),
+                        ],
+                    },
                 );
                 map
             }),
@@ -410,11 +1270,8 @@ mod tests {
             session_dependent: false,
             deterministic: true,
             nullability: NullabilityHandling::Mirror,
-            return_type: ConcreteType {
-                kind: ConcreteTypeKind::Builtin(BasicBuiltinType::I8),
-                nullable: false,
-            },
-            implementation: HashMap::new(),
+            return_type: i8.into(),
+            implementation: IndexMap::new(),
         };
 
         assert_eq!(&add.impls[0], &expected_impl);
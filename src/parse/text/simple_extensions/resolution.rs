@@ -0,0 +1,737 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Overload resolution for scalar functions.
+//!
+//! Given the types of the (value) arguments of a function call, this selects
+//! the matching [`Impl`] of a [`ScalarFunction`] and computes the return type
+//! of the call, honouring variadic behavior and nullability handling. Type
+//! variables in the signature are bound by unifying the declared types with
+//! the argument types, and the return type is instantiated from the bindings.
+//!
+//! Declared types without a type variation accept arguments of any variation,
+//! except for variations with [`VariationFunctions::Separate`] functions when
+//! resolving through the [`Registry`](super::Registry).
+
+use thiserror::Error;
+
+use super::argument::ArgumentsItem;
+use super::scalar_functions::{
+    Impl, NullabilityHandling, ParameterConsistency, ScalarFunction, VariadicBehavior,
+};
+use super::signature::{SignatureType, SignatureTypeKind, TypeBindings};
+use super::type_ast::TypeExpr;
+use super::type_variations::VariationFunctions;
+use super::types::ConcreteType;
+use crate::text::simple_extensions::Type as RawType;
+use crate::urn::Urn;
+
+/// The outcome of resolving a function call against the implementations of a
+/// [`ScalarFunction`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Resolution<'a> {
+    /// The chosen implementation.
+    pub impl_: &'a Impl,
+    /// The compound name of the chosen implementation (e.g. `add:i32_i32`).
+    pub compound_name: String,
+    /// The return type of the call, with nullability derived from the
+    /// arguments according to the [`NullabilityHandling`] of the chosen
+    /// implementation.
+    pub return_type: ConcreteType,
+    /// The implementations that were not chosen, and why.
+    pub rejected: Vec<Rejection>,
+}
+
+/// An implementation that was rejected during overload resolution.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rejection {
+    /// The compound name of the rejected implementation (e.g. `add:i8_i8`).
+    pub compound_name: String,
+    /// Why the implementation was rejected.
+    pub reason: RejectionReason,
+}
+
+/// Why an implementation was rejected during overload resolution.
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum RejectionReason {
+    /// The number of arguments does not match a non-variadic implementation.
+    #[error("expected {expected} arguments, got {actual}")]
+    ArgumentCount {
+        /// The number of value arguments of the implementation
+        expected: usize,
+        /// The number of arguments of the call
+        actual: usize,
+    },
+    /// Too few arguments for a variadic implementation.
+    #[error("expected at least {min} arguments, got {actual}")]
+    TooFewArguments {
+        /// The minimum number of arguments
+        min: usize,
+        /// The number of arguments of the call
+        actual: usize,
+    },
+    /// Too many arguments for a variadic implementation.
+    #[error("expected at most {max} arguments, got {actual}")]
+    TooManyArguments {
+        /// The maximum number of arguments
+        max: usize,
+        /// The number of arguments of the call
+        actual: usize,
+    },
+    /// An argument type does not match the declared type.
+    #[error("argument {index}: expected `{expected}`, got `{actual}`")]
+    ArgumentType {
+        /// The (0-based) position of the argument
+        index: usize,
+        /// The declared type
+        expected: Box<SignatureType>,
+        /// The type of the argument of the call
+        actual: Box<ConcreteType>,
+    },
+    /// The declared type of an argument is not supported by overload
+    /// resolution.
+    #[error("argument {index}: unsupported declared type `{type_str}`")]
+    UnsupportedArgumentType {
+        /// The (0-based) position of the argument
+        index: usize,
+        /// The declared type
+        type_str: String,
+    },
+    /// A variadic argument type differs from the first variadic argument,
+    /// while the implementation requires consistent types.
+    #[error(
+        "argument {index}: expected `{expected}` (consistent variadic arguments), got `{actual}`"
+    )]
+    InconsistentVariadic {
+        /// The (0-based) position of the argument
+        index: usize,
+        /// The type of the first variadic argument of the call
+        expected: Box<ConcreteType>,
+        /// The type of the argument of the call
+        actual: Box<ConcreteType>,
+    },
+    /// The return type cannot be derived from the argument types, e.g.
+    /// because it refers to a type variable that is not bound by the
+    /// arguments.
+    #[error("cannot derive return type `{return_type}`: {reason}")]
+    ReturnType {
+        /// The declared return type
+        return_type: String,
+        /// Why the return type cannot be derived
+        reason: String,
+    },
+    /// The implementation matches, but an earlier implementation was chosen.
+    #[error("an earlier implementation `{chosen}` also matches")]
+    Superseded {
+        /// The compound name of the chosen implementation
+        chosen: String,
+    },
+}
+
+/// Errors that can occur during overload resolution.
+#[derive(Debug, Error, PartialEq)]
+pub enum ResolutionError {
+    /// The function is not defined.
+    #[error("unknown function `{name}`")]
+    UnknownFunction {
+        /// The function name
+        name: String,
+    },
+    /// None of the implementations match the arguments.
+    #[error("no implementation of `{name}` matches arguments ({})", display_types(.arguments))]
+    NoMatchingImpl {
+        /// The function name
+        name: String,
+        /// The argument types of the call
+        arguments: Vec<ConcreteType>,
+        /// The rejected implementations, and why
+        rejected: Vec<Rejection>,
+    },
+}
+
+fn display_types(types: &[ConcreteType]) -> String {
+    types
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl ScalarFunction {
+    /// Resolve a call of this function with the given argument types.
+    ///
+    /// Only value arguments take part in resolution: enumeration and type
+    /// arguments of the implementations are skipped. Implementations are
+    /// tried in declaration order, and the first match is chosen.
+    ///
+    /// All type variations of the arguments are assumed to inherit the
+    /// functions of their parent type, and extension types are compared by
+    /// name, as the extension defining them is not known.
+    pub fn resolve(&self, arguments: &[ConcreteType]) -> Result<Resolution<'_>, ResolutionError> {
+        self.resolve_with_variations(arguments, |_| VariationFunctions::Inherits, |_| None)
+    }
+
+    /// Resolve a call of this function, looking up how the functions of the
+    /// parent type apply to the type variations of the arguments, and the URN
+    /// of the extension defining each extension type of the signatures.
+    pub(super) fn resolve_with_variations(
+        &self,
+        arguments: &[ConcreteType],
+        variation_functions: impl Fn(&str) -> VariationFunctions,
+        type_urn: impl Fn(&str) -> Option<Urn>,
+    ) -> Result<Resolution<'_>, ResolutionError> {
+        let mut chosen: Option<(&Impl, String, ConcreteType)> = None;
+        let mut rejected = Vec::new();
+
+        for impl_ in &self.impls {
+            let compound_name = impl_.compound_name(&self.name);
+            let reason = match (
+                impl_.matches(arguments, &variation_functions, &type_urn),
+                &chosen,
+            ) {
+                (Ok(return_type), None) => {
+                    chosen = Some((impl_, compound_name, return_type));
+                    continue;
+                }
+                (Ok(_), Some((_, chosen, _))) => RejectionReason::Superseded {
+                    chosen: chosen.clone(),
+                },
+                (Err(reason), _) => reason,
+            };
+            rejected.push(Rejection {
+                compound_name,
+                reason,
+            });
+        }
+
+        match chosen {
+            Some((impl_, compound_name, return_type)) => Ok(Resolution {
+                impl_,
+                compound_name,
+                return_type,
+                rejected,
+            }),
+            None => Err(ResolutionError::NoMatchingImpl {
+                name: self.name.clone(),
+                arguments: arguments.to_vec(),
+                rejected,
+            }),
+        }
+    }
+}
+
+impl Impl {
+    /// Check the number of arguments of a call of this implementation.
+    ///
+    /// Unlike overload resolution, this counts arguments of all kinds, as
+    /// passed positionally in protobuf function calls.
+    pub fn check_argument_count(&self, count: usize) -> Result<(), RejectionReason> {
+        check_count(&self.args, self.variadic.as_ref(), count)
+    }
+
+    /// Check whether this implementation accepts the given argument types,
+    /// returning the return type of the call on success.
+    ///
+    /// The extension types of the signature are qualified by the URN returned
+    /// by `type_urn`, see [`ConcreteType::qualify`].
+    pub(super) fn matches(
+        &self,
+        arguments: &[ConcreteType],
+        variation_functions: &impl Fn(&str) -> VariationFunctions,
+        type_urn: &impl Fn(&str) -> Option<Urn>,
+    ) -> Result<ConcreteType, RejectionReason> {
+        // Only value arguments are passed as data. The declared types are
+        // parsed from the arguments if the implementation was constructed
+        // without them.
+        let parameters = self
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                ArgumentsItem::ValueArgument(arg) => Some(arg.value()),
+                _ => None,
+            })
+            .enumerate()
+            .map(|(index, value)| {
+                let mut declared = match self.argument_types.get(index) {
+                    Some(declared) => declared.clone(),
+                    None => declared_type(index, value)?,
+                };
+                declared.qualify(type_urn);
+                Ok(declared)
+            })
+            .collect::<Result<Vec<_>, RejectionReason>>()?;
+
+        check_count(&parameters, self.variadic.as_ref(), arguments.len())?;
+
+        let consistency = self
+            .variadic
+            .as_ref()
+            .and_then(|variadic| variadic.parameter_consistency.as_ref());
+
+        // The last parameter is repeated for variadic functions. Inconsistent
+        // variadic arguments bind the type variables of the variadic
+        // parameter independently, starting from the bindings of the fixed
+        // arguments. Only the variables that all variadic arguments bind to
+        // the same value are bound for the return type: a return type that
+        // refers to a variable bound to different types (e.g. `any1` of
+        // `pick(i32, i64)`) cannot be derived.
+        let fixed = parameters.len().saturating_sub(1);
+        let mut bindings = TypeBindings::default();
+        let mut fixed_bindings: Option<TypeBindings> = None;
+        let mut variadic_bindings: Option<TypeBindings> = None;
+        let mut first_variadic: Option<&ConcreteType> = None;
+        for (index, actual) in arguments.iter().enumerate() {
+            let expected = &parameters[index.min(parameters.len() - 1)];
+            let variadic = self.variadic.is_some() && index >= fixed;
+            let accepted = if variadic && consistency == Some(&ParameterConsistency::Inconsistent) {
+                let mut argument_bindings = fixed_bindings
+                    .get_or_insert_with(|| bindings.clone())
+                    .clone();
+                let accepted = self.accepts(expected, actual, &mut argument_bindings);
+                match &mut variadic_bindings {
+                    Some(variadic_bindings) => variadic_bindings.retain_common(&argument_bindings),
+                    None => variadic_bindings = Some(argument_bindings),
+                }
+                accepted
+            } else {
+                self.accepts(expected, actual, &mut bindings)
+            };
+            // Functions of the parent type do not apply to variations with
+            // separate functions, unless the declared type is generic.
+            let separate = expected.variation.is_none()
+                && !matches!(
+                    expected.kind,
+                    SignatureTypeKind::Any | SignatureTypeKind::Variable(_)
+                )
+                && actual.variation.as_deref().is_some_and(|variation| {
+                    variation_functions(variation) == VariationFunctions::Separate
+                });
+            if !accepted || separate {
+                return Err(RejectionReason::ArgumentType {
+                    index,
+                    expected: Box::new(expected.clone()),
+                    actual: Box::new(actual.clone()),
+                });
+            }
+
+            if variadic && consistency == Some(&ParameterConsistency::Consistent) {
+                match first_variadic {
+                    Some(first) if first.kind != actual.kind => {
+                        return Err(RejectionReason::InconsistentVariadic {
+                            index,
+                            expected: Box::new(first.clone()),
+                            actual: Box::new(actual.clone()),
+                        });
+                    }
+                    Some(_) => {}
+                    None => first_variadic = Some(actual),
+                }
+            }
+        }
+
+        let bindings = variadic_bindings.unwrap_or(bindings);
+        let mut return_type = self.call_return_type(&bindings, arguments)?;
+        return_type.qualify(type_urn);
+        Ok(return_type)
+    }
+
+    /// Check whether a declared parameter type accepts an argument type,
+    /// binding the type variables of the declared type.
+    ///
+    /// With [`NullabilityHandling::Discrete`] the declared nullability must
+    /// accept the argument nullability, otherwise only the types are compared.
+    fn accepts(
+        &self,
+        expected: &SignatureType,
+        actual: &ConcreteType,
+        bindings: &mut TypeBindings,
+    ) -> bool {
+        let nullability = match self.nullability {
+            NullabilityHandling::Mirror | NullabilityHandling::DeclaredOutput => true,
+            NullabilityHandling::Discrete => {
+                expected.nullable
+                    || !actual.nullable
+                    || matches!(expected.kind, SignatureTypeKind::Any)
+            }
+        };
+        nullability && bindings.unify(expected, actual)
+    }
+
+    /// Returns the return type of a call with the given (matching) arguments.
+    ///
+    /// The declared return type is derived from the type variables bound by
+    /// the arguments. With [`NullabilityHandling::Mirror`] the return type
+    /// is nullable if any argument is nullable, otherwise the declared
+    /// nullability is used.
+    fn call_return_type(
+        &self,
+        bindings: &TypeBindings,
+        arguments: &[ConcreteType],
+    ) -> Result<ConcreteType, RejectionReason> {
+        let mut return_type =
+            self.return_type
+                .evaluate(bindings)
+                .map_err(|error| RejectionReason::ReturnType {
+                    return_type: self.return_type.to_string(),
+                    reason: error.to_string(),
+                })?;
+        if self.nullability == NullabilityHandling::Mirror {
+            return_type.nullable |= arguments.iter().any(|argument| argument.nullable);
+        }
+        Ok(return_type)
+    }
+}
+
+/// Resolve the declared type of a value argument.
+fn declared_type(index: usize, value: &RawType) -> Result<SignatureType, RejectionReason> {
+    let unsupported =
+        |type_str: String| RejectionReason::UnsupportedArgumentType { index, type_str };
+    match value {
+        RawType::String(type_str) => TypeExpr::parse(type_str)
+            .ok()
+            .and_then(|type_expr| SignatureType::try_from(type_expr).ok())
+            .ok_or_else(|| unsupported(type_str.clone())),
+        RawType::Object(_) => Err(unsupported("struct".to_string())),
+    }
+}
+
+/// Check the number of arguments against the declared parameters.
+fn check_count<T>(
+    parameters: &[T],
+    variadic: Option<&VariadicBehavior>,
+    actual: usize,
+) -> Result<(), RejectionReason> {
+    match variadic {
+        Some(VariadicBehavior { min, max, .. }) if !parameters.is_empty() => {
+            let fixed = parameters.len() - 1;
+            let min = fixed + min.unwrap_or(0) as usize;
+            if actual < min {
+                return Err(RejectionReason::TooFewArguments { min, actual });
+            }
+            if let Some(max) = max
+                .map(|max| fixed + max as usize)
+                .filter(|max| actual > *max)
+            {
+                return Err(RejectionReason::TooManyArguments { max, actual });
+            }
+            Ok(())
+        }
+        _ if parameters.len() != actual => Err(RejectionReason::ArgumentCount {
+            expected: parameters.len(),
+            actual,
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::text::simple_extensions::{ExtensionFile, Registry};
+    use crate::urn::Urn;
+
+    const YAML: &str = r#"
+%YAML 1.2
+---
+urn: extension:example.com:resolution
+type_variations:
+  - name: dict
+    parent: i32
+  - name: sorted
+    parent: i64
+    functions: SEPARATE
+scalar_functions:
+  - name: add
+    impls:
+      - args:
+          - name: overflow
+            options: [SILENT, SATURATE, ERROR]
+          - value: i32
+          - value: i32
+        return: i32
+      - args:
+          - value: i64
+          - value: i64
+        return: i64
+  - name: concat
+    impls:
+      - args:
+          - value: string
+        variadic:
+          min: 1
+          max: 3
+          parameterConsistency: CONSISTENT
+        return: string
+  - name: pick
+    impls:
+      - args:
+          - value: any1
+        variadic:
+          min: 1
+          parameterConsistency: INCONSISTENT
+        return: any1
+  - name: is_null
+    impls:
+      - args:
+          - value: i32?
+        nullability: DECLARED_OUTPUT
+        return: boolean
+  - name: strict
+    impls:
+      - args:
+          - value: i32
+        nullability: DISCRETE
+        return: i32?
+  - name: coalesce
+    impls:
+      - args:
+          - value: any1
+        variadic:
+          min: 2
+          parameterConsistency: CONSISTENT
+        nullability: DECLARED_OUTPUT
+        return: any1
+  - name: round
+    impls:
+      - args:
+          - value: DECIMAL<P1, S1>
+          - value: i32
+        return: DECIMAL<P1, 0>
+  - name: first
+    impls:
+      - args:
+          - value: list<any1>
+        return: any1?
+  - name: norm
+    impls:
+      - args:
+          - value:
+              x: i32
+              y: i32
+        return: i32
+  - name: multiply
+    impls:
+      - args:
+          - value: decimal<P1,S1>
+          - value: decimal<P2,S2>
+        return: |-
+          init_scale = S1 + S2
+          init_prec = P1 + P2 + 1
+          prec = min(init_prec, 38)
+          scale = init_prec > 38 ? max(init_scale - (init_prec - 38), min(init_scale, 6)) : init_scale
+          DECIMAL<prec, scale>
+"#;
+
+    fn registry() -> (Urn, Registry) {
+        let file = ExtensionFile::read_from_str(YAML).unwrap();
+        let urn = file.urn().clone();
+        (urn, Registry::new([file]).unwrap())
+    }
+
+    fn parse(type_str: &str) -> ConcreteType {
+        ConcreteType::try_from(TypeExpr::parse(type_str).unwrap()).unwrap()
+    }
+
+    fn types(type_strs: &[&str]) -> Vec<ConcreteType> {
+        type_strs.iter().map(|type_str| parse(type_str)).collect()
+    }
+
+    #[test]
+    fn resolve_overload() {
+        let (urn, registry) = registry();
+
+        let resolution = registry
+            .resolve_scalar_function(&urn, "add", &types(&["i64", "i64?"]))
+            .unwrap();
+        assert_eq!(resolution.compound_name, "add:i64_i64");
+        assert_eq!(resolution.return_type, parse("i64?"));
+        assert_eq!(
+            resolution.rejected,
+            vec![Rejection {
+                compound_name: "add:req_i32_i32".to_string(),
+                reason: RejectionReason::ArgumentType {
+                    index: 0,
+                    expected: Box::new(parse("i32").into()),
+                    actual: Box::new(parse("i64")),
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn resolve_variadic() {
+        let (urn, registry) = registry();
+        let resolve = |args: &[&str]| {
+            registry
+                .resolve_scalar_function(&urn, "concat", &types(args))
+                .map(|resolution| resolution.return_type)
+        };
+
+        assert_eq!(resolve(&["string", "string"]), Ok(parse("string")));
+        assert_eq!(
+            resolve(&["string", "string?", "string"]),
+            Ok(parse("string?"))
+        );
+
+        // Inconsistent variadic arguments bind the return type variable only
+        // if they all bind it to the same type.
+        let pick = |args: &[&str]| {
+            registry
+                .resolve_scalar_function(&urn, "pick", &types(args))
+                .map(|resolution| resolution.return_type)
+        };
+        assert_eq!(pick(&["i32", "i32?"]), Ok(parse("i32?")));
+        assert!(matches!(
+            pick(&["i32", "i64"]),
+            Err(ResolutionError::NoMatchingImpl { rejected, .. })
+                if matches!(rejected[0].reason, RejectionReason::ReturnType { .. })
+        ));
+
+        let rejected = |reason| ResolutionError::NoMatchingImpl {
+            name: "concat".to_string(),
+            arguments: vec![],
+            rejected: vec![Rejection {
+                compound_name: "concat:str".to_string(),
+                reason,
+            }],
+        };
+        assert_eq!(
+            resolve(&[]),
+            Err(rejected(RejectionReason::TooFewArguments {
+                min: 1,
+                actual: 0
+            }))
+        );
+        assert!(matches!(
+            resolve(&["string"; 4]),
+            Err(ResolutionError::NoMatchingImpl { rejected, .. })
+                if rejected[0].reason == RejectionReason::TooManyArguments { max: 3, actual: 4 }
+        ));
+    }
+
+    #[test]
+    fn resolve_nullability() {
+        let (urn, registry) = registry();
+        let resolve = |name: &str, args: &[&str]| {
+            registry
+                .resolve_scalar_function(&urn, name, &types(args))
+                .map(|resolution| resolution.return_type)
+        };
+
+        // Declared output ignores argument nullability.
+        assert_eq!(resolve("is_null", &["i32"]), Ok(parse("boolean")));
+        assert_eq!(resolve("is_null", &["i32?"]), Ok(parse("boolean")));
+
+        // Discrete requires compatible argument nullability.
+        assert_eq!(resolve("strict", &["i32"]), Ok(parse("i32?")));
+        assert!(matches!(
+            resolve("strict", &["i32?"]),
+            Err(ResolutionError::NoMatchingImpl { .. })
+        ));
+
+        assert_eq!(
+            resolve("missing", &[]),
+            Err(ResolutionError::UnknownFunction {
+                name: "missing".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn resolve_type_variables() {
+        let (urn, registry) = registry();
+        let resolve = |name: &str, args: &[&str]| {
+            registry
+                .resolve_scalar_function(&urn, name, &types(args))
+                .map(|resolution| resolution.return_type)
+        };
+
+        assert_eq!(resolve("coalesce", &["i64?", "i64"]), Ok(parse("i64")));
+        assert_eq!(
+            resolve("round", &["DECIMAL?<10, 2>", "i32"]),
+            Ok(parse("DECIMAL?<10, 0>"))
+        );
+        assert_eq!(resolve("first", &["list<string?>"]), Ok(parse("string?")));
+
+        // The object form of an argument type is a named struct.
+        let point = ConcreteType::named_struct(
+            [("x", "i32"), ("y", "i32")]
+                .into_iter()
+                .map(|(name, type_str)| (name.to_string(), parse(type_str)))
+                .collect(),
+            false,
+        );
+        assert_eq!(
+            registry
+                .resolve_scalar_function(&urn, "norm", &[point])
+                .map(|resolution| resolution.return_type),
+            Ok(parse("i32"))
+        );
+
+        // Type derivations compute the return type from the bindings.
+        assert_eq!(
+            resolve("multiply", &["DECIMAL<10, 2>", "DECIMAL?<5, 1>"]),
+            Ok(parse("DECIMAL?<16, 3>"))
+        );
+        assert_eq!(
+            resolve("multiply", &["DECIMAL<30, 10>", "DECIMAL<20, 5>"]),
+            Ok(parse("DECIMAL<38, 6>"))
+        );
+
+        // All occurrences of a type variable bind to the same type.
+        assert!(matches!(
+            resolve("coalesce", &["i64", "i32"]),
+            Err(ResolutionError::NoMatchingImpl { rejected, .. })
+                if matches!(rejected[0].reason, RejectionReason::ArgumentType { index: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn resolve_type_variations() {
+        let (urn, registry) = registry();
+        let resolve = |name: &str, args: &[&str]| {
+            registry
+                .resolve_scalar_function(&urn, name, &types(args))
+                .map(|resolution| resolution.return_type)
+        };
+
+        assert_eq!(resolve("add", &["i32[dict]", "i32"]), Ok(parse("i32")));
+        assert!(resolve("add", &["i64[sorted]", "i64"]).is_err());
+        assert_eq!(
+            resolve("coalesce", &["i64[sorted]", "i64[sorted]"]),
+            Ok(parse("i64[sorted]"))
+        );
+
+        // Without the registry, all variations inherit the functions.
+        let add = registry.get_scalar_function(&urn, "add").unwrap();
+        assert!(add.resolve(&types(&["i64[sorted]", "i64"])).is_ok());
+    }
+
+    #[test]
+    fn argument_count() {
+        let parameters = types(&["string"]);
+        let variadic = VariadicBehavior {
+            min: None,
+            max: None,
+            parameter_consistency: Some(ParameterConsistency::Consistent),
+        };
+        assert_eq!(check_count(&parameters, Some(&variadic), 0), Ok(()));
+        assert_eq!(
+            check_count(&parameters, None, 2),
+            Err(RejectionReason::ArgumentCount {
+                expected: 1,
+                actual: 2
+            })
+        );
+    }
+
+    #[test]
+    fn unsupported_argument_type() {
+        assert_eq!(
+            declared_type(0, &RawType::String("unknown<i32>".to_string())),
+            Err(RejectionReason::UnsupportedArgumentType {
+                index: 0,
+                type_str: "unknown<i32>".to_string()
+            })
+        );
+    }
+}
@@ -5,23 +5,29 @@
 //! This module provides typed wrappers around scalar functions parsed from extension
 //! YAML files, validating constraints and resolving type strings to concrete types.
 
-use std::collections::HashMap;
+use indexmap::IndexMap;
 
 use crate::text::simple_extensions::{
-    NullabilityHandling as RawNullabilityHandling, Options as RawOptions,
-    ScalarFunction as RawScalarFunction, ScalarFunctionImplsItem as RawImpl, Type as RawType,
+    Arguments as RawArguments, Deterministic as RawDeterministic,
+    Implementation as RawImplementation, NullabilityHandling as RawNullabilityHandling,
+    Options as RawOptions, OptionsValue as RawOptionsValue, ReturnValue as RawReturnValue,
+    ScalarFunction as RawScalarFunction, ScalarFunctionImplsItem as RawImpl,
+    SessionDependent as RawSessionDependent, Type as RawType,
     VariadicBehavior as RawVariadicBehavior, VariadicBehaviorParameterConsistency,
 };
 
 use super::argument::{ArgumentsItem, ArgumentsItemError};
+use super::derivation::{DerivationError, TypeDerivation};
+use super::diagnostics::FunctionKind;
 use super::extensions::TypeContext;
-use super::type_ast::{TypeExpr, TypeParseError};
+use super::signature::{SignatureType, SignatureTypeKind};
+use super::type_ast::TypeParseError;
 use super::types::{ConcreteType, ExtensionTypeError};
 use crate::parse::Parse;
 use thiserror::Error;
 
 /// Errors that can occur when parsing scalar functions
-#[derive(Debug, Error)]
+#[derive(Debug, Error, PartialEq)]
 pub enum ScalarFunctionError {
     /// Scalar function has no implementations
     #[error("Scalar function '{name}' must have at least one implementation")]
@@ -54,9 +60,45 @@ pub enum ScalarFunctionError {
     /// Error parsing type expression
     #[error("Type parse error: {0}")]
     TypeParseError(#[from] TypeParseError),
+    /// Error parsing a type derivation
+    #[error("Type derivation error: {0}")]
+    TypeDerivationError(DerivationError),
     /// Feature not yet implemented
     #[error("Not yet implemented: {0}")]
     NotYetImplemented(String),
+    /// Feature not yet implemented, in an implementation of a function
+    #[error("Implementation {index} not yet implemented: {reason}")]
+    ImplNotYetImplemented {
+        /// The index of the implementation in the function
+        index: usize,
+        /// The feature that is not yet implemented
+        reason: String,
+    },
+}
+
+impl From<DerivationError> for ScalarFunctionError {
+    /// Errors in the result type of a derivation are reported as plain type
+    /// errors.
+    fn from(err: DerivationError) -> Self {
+        match err {
+            DerivationError::TypeParse(err) => err.into(),
+            DerivationError::Type(err) => err.into(),
+            err => ScalarFunctionError::TypeDerivationError(err),
+        }
+    }
+}
+
+impl ScalarFunctionError {
+    /// Attach the index of the implementation a
+    /// [`ScalarFunctionError::NotYetImplemented`] error occurred in.
+    pub(super) fn in_impl(self, index: usize) -> Self {
+        match self {
+            ScalarFunctionError::NotYetImplemented(reason) => {
+                ScalarFunctionError::ImplNotYetImplemented { index, reason }
+            }
+            err => err,
+        }
+    }
 }
 
 /// A validated scalar function definition with one or more implementations
@@ -68,29 +110,91 @@ pub struct ScalarFunction {
     pub description: Option<String>,
     /// Function implementations (overloads)
     pub impls: Vec<Impl>,
+    /// The `metadata` of the function declaration: free-form entries the
+    /// specification leaves to producers and consumers. For a function
+    /// declared more than once, the entries of all declarations are merged,
+    /// the first declaration winning on conflicting keys.
+    pub metadata: serde_json::Map<String, serde_json::Value>,
 }
 
 impl ScalarFunction {
     /// Parse a scalar function from raw YAML, resolving types with the provided context
+    ///
+    /// Implementations using features that are not yet supported are skipped
+    /// with a [`ParseWarning::SkippedImpl`](super::ParseWarning::SkippedImpl),
+    /// unless parsing strictly. Returns `None` if all implementations are
+    /// skipped.
     pub(super) fn from_raw(
         raw: RawScalarFunction,
         ctx: &mut TypeContext,
-    ) -> Result<Self, ScalarFunctionError> {
+    ) -> Result<Option<Self>, ScalarFunctionError> {
         if raw.impls.is_empty() {
             return Err(ScalarFunctionError::NoImplementations { name: raw.name });
         }
 
-        let impls = raw
-            .impls
-            .into_iter()
-            .map(|impl_| Impl::from_raw(impl_, ctx))
-            .collect::<Result<Vec<_>, _>>()?;
+        let mut impls = Vec::new();
+        for (index, impl_) in raw.impls.into_iter().enumerate() {
+            match Impl::from_raw(impl_, ctx) {
+                Ok(impl_) => impls.push(impl_),
+                Err(ScalarFunctionError::NotYetImplemented(reason)) if !ctx.is_strict() => {
+                    ctx.skip_impl(FunctionKind::Scalar, &raw.name, index, reason);
+                }
+                Err(err) => return Err(err.in_impl(index)),
+            }
+        }
 
-        Ok(ScalarFunction {
+        Ok((!impls.is_empty()).then(|| ScalarFunction {
             name: raw.name,
             description: raw.description,
             impls,
-        })
+            metadata: raw.metadata,
+        }))
+    }
+
+    /// Get the implementation with the given signature, i.e. the part of a
+    /// compound function name after the `:` (e.g. `i32_i32` for
+    /// `add:i32_i32`).
+    pub fn get_impl(&self, signature: &str) -> Option<&Impl> {
+        self.impls
+            .iter()
+            .find(|impl_| impl_.signature() == signature)
+    }
+
+    /// Get the implementation for a compound function name (e.g.
+    /// `add:i32_i32`).
+    ///
+    /// A plain function name (e.g. `add`) only resolves when this function
+    /// has exactly one implementation. Returns `None` if the name does not
+    /// refer to this function.
+    pub fn get_impl_by_compound_name(&self, compound_name: &str) -> Option<&Impl> {
+        match split_compound_name(compound_name) {
+            (name, _) if name != self.name => None,
+            (_, Some(signature)) => self.get_impl(signature),
+            (_, None) => match self.impls.as_slice() {
+                [impl_] => Some(impl_),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Split a compound function name (e.g. `add:i32_i32`) into the function name
+/// and the signature, if any.
+pub(crate) fn split_compound_name(compound_name: &str) -> (&str, Option<&str>) {
+    match compound_name.split_once(':') {
+        Some((name, signature)) => (name, Some(signature)),
+        None => (compound_name, None),
+    }
+}
+
+impl From<ScalarFunction> for RawScalarFunction {
+    fn from(function: ScalarFunction) -> Self {
+        RawScalarFunction {
+            name: function.name,
+            description: function.description,
+            metadata: function.metadata,
+            impls: function.impls.into_iter().map(Into::into).collect(),
+        }
     }
 }
 
@@ -99,6 +203,12 @@ impl ScalarFunction {
 pub struct Impl {
     /// Function arguments with types and optional names/descriptions
     pub args: Vec<ArgumentsItem>,
+    /// The parsed declared types of the value arguments, see
+    /// [`Impl::argument_types`].
+    ///
+    /// Filled in when parsing. Overload resolution parses the declared types
+    /// of value arguments without an entry from [`Impl::args`].
+    pub argument_types: Vec<SignatureType>,
     /// Configurable function options (e.g., overflow behavior, rounding modes)
     pub options: Options,
     /// Variadic argument behavior.
@@ -117,16 +227,20 @@ pub struct Impl {
     ///
     /// Defaults to [`NullabilityHandling::Mirror`] per the Substrait spec.
     pub nullability: NullabilityHandling,
-    /// Return type resolved to a concrete type
+    /// Declared return type
     ///
-    /// The raw YAML type string is parsed and validated. Only concrete types
-    /// (without type variables) are supported; functions with type variables
-    /// are skipped in this basic implementation.
-    pub return_type: ConcreteType,
+    /// The raw YAML type string is parsed and validated. The return type may
+    /// contain type variables (e.g. `any1`, `DECIMAL<P, S>`), which are bound
+    /// by the argument types of a call, and may be a multiline type
+    /// derivation computed from those bindings; see
+    /// [`ScalarFunction::resolve`]. The YAML object form of a return type is
+    /// a (non-nullable) named struct.
+    pub return_type: TypeDerivation,
     /// Language-specific implementation code (e.g., SQL, C++, Python)
     ///
-    /// Maps language identifiers to implementation source code snippets.
-    pub implementation: HashMap<String, String>,
+    /// Maps language identifiers to implementation source code snippets, in
+    /// declaration order.
+    pub implementation: IndexMap<String, String>,
 }
 
 impl Impl {
@@ -135,46 +249,18 @@ impl Impl {
         raw: RawImpl,
         ctx: &mut TypeContext,
     ) -> Result<Self, ScalarFunctionError> {
-        // Parse and validate the return type
-        let return_type = match raw.return_.0 {
-            RawType::String(s) => {
-                // Multiline strings indicate type derivation expressions
-                // See: https://github.com/substrait-io/substrait-rs/issues/449
-                if s.contains('\n') {
-                    return Err(ScalarFunctionError::NotYetImplemented(
-                        "Type derivation expressions - issue #449".to_string(),
-                    ));
-                }
-                let type_expr = TypeExpr::parse(&s)?;
-                type_expr.visit_references(&mut |name| ctx.linked(name));
-                match ConcreteType::try_from(type_expr) {
-                    Ok(concrete) => concrete,
-                    Err(ExtensionTypeError::InvalidAnyTypeVariable { .. })
-                    | Err(ExtensionTypeError::InvalidParameter(_))
-                    | Err(ExtensionTypeError::InvalidParameterKind { .. }) => {
-                        // Type has type/parameter variables (any1, L1, P, etc.) - not yet supported
-                        // See: https://github.com/substrait-io/substrait-rs/issues/452
-                        return Err(ScalarFunctionError::NotYetImplemented(
-                            "Type variables in function signatures - issue #452".to_string(),
-                        ));
-                    }
-                    Err(ExtensionTypeError::UnknownTypeName { name }) => {
-                        return Err(ScalarFunctionError::TypeError(
-                            ExtensionTypeError::UnknownTypeName { name },
-                        ));
-                    }
-                    Err(e) => return Err(ScalarFunctionError::TypeError(e)),
-                }
-            }
-            RawType::Object(_) => {
-                // Struct return types (YAML syntactic sugar) are not yet supported
-                // See: https://github.com/substrait-io/substrait-rs/issues/450
-                return Err(ScalarFunctionError::NotYetImplemented(
-                    "Struct return types - issue #450".to_string(),
-                ));
+        // Function types (e.g. `func<any1 -> any2>`), taken by functions with
+        // lambda arguments, are not supported yet.
+        let not_yet_implemented = |err: ScalarFunctionError| match err {
+            ScalarFunctionError::TypeError(ExtensionTypeError::FunctionType) => {
+                ScalarFunctionError::NotYetImplemented("function types (`func<...>`)".to_string())
             }
+            err => err,
         };
 
+        // Parse and validate the return type
+        let return_type = parse_return_type(raw.return_.0, ctx).map_err(not_yet_implemented)?;
+
         let variadic = raw.variadic.map(|v| v.try_into()).transpose()?;
 
         let args = match raw.args {
@@ -186,8 +272,40 @@ impl Impl {
             None => Vec::new(),
         };
 
+        // Parse the declared types of the value arguments once, for overload
+        // resolution.
+        let argument_types = args
+            .iter()
+            .filter_map(|arg| match arg {
+                ArgumentsItem::ValueArgument(arg) => Some(parse_argument_type(arg.value(), ctx)),
+                _ => None,
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(not_yet_implemented)?;
+
+        // Named type variables (e.g. `T`) in the return type must be bound by
+        // the arguments; otherwise the name refers to an unknown type.
+        if let Some(name) = return_type
+            .result()
+            .type_variables()
+            .into_iter()
+            .find(|name| {
+                !is_any_variable(name)
+                    && !argument_types
+                        .iter()
+                        .any(|argument_type| argument_type.type_variables().contains(name))
+            })
+        {
+            return Err(ScalarFunctionError::TypeError(
+                ExtensionTypeError::UnknownTypeName {
+                    name: name.to_string(),
+                },
+            ));
+        }
+
         Ok(Impl {
             args,
+            argument_types,
             options: raw.options.as_ref().map(Options::from).unwrap_or_default(),
             variadic,
             session_dependent: raw.session_dependent.map(|b| b.0).unwrap_or(false),
@@ -197,19 +315,141 @@ impl Impl {
                 .map(Into::into)
                 .unwrap_or(NullabilityHandling::Mirror),
             return_type,
-            implementation: raw
-                .implementation
-                .map(|i| i.0.into_iter().collect())
-                .unwrap_or_default(),
+            implementation: raw.implementation.map(|i| i.0).unwrap_or_default(),
         })
     }
 }
 
+impl From<Impl> for RawImpl {
+    fn from(impl_: Impl) -> Self {
+        let Impl {
+            args,
+            argument_types: _,
+            options,
+            variadic,
+            session_dependent,
+            deterministic,
+            nullability,
+            return_type,
+            implementation,
+        } = impl_;
+
+        // Defaults are omitted, as in hand-written extension files.
+        RawImpl {
+            args: (!args.is_empty())
+                .then(|| RawArguments(args.into_iter().map(Into::into).collect())),
+            options: (!options.0.is_empty()).then(|| options.into()),
+            variadic: variadic.map(Into::into),
+            session_dependent: session_dependent.then_some(RawSessionDependent(true)),
+            deterministic: (!deterministic).then_some(RawDeterministic(false)),
+            nullability: (nullability != NullabilityHandling::Mirror).then(|| nullability.into()),
+            return_: RawReturnValue(raw_return_type(return_type)),
+            implementation: (!implementation.is_empty())
+                .then_some(RawImplementation(implementation)),
+        }
+    }
+}
+
+/// Parse a declared return (or intermediate) type: a type string, which may be
+/// a multiline type derivation, or the YAML object form of a named struct.
+pub(super) fn parse_return_type(
+    raw: RawType,
+    ctx: &mut TypeContext,
+) -> Result<TypeDerivation, ScalarFunctionError> {
+    match raw {
+        RawType::String(s) => Ok(TypeDerivation::parse_with(&s, |result| {
+            ctx.parse_type(result)
+        })?),
+        RawType::Object(fields) => {
+            // The object form is a non-nullable named struct, parsed like the
+            // structure of a type definition.
+            let named_struct: ConcreteType = Parse::parse(RawType::Object(fields), ctx)?;
+            Ok(SignatureType::from(named_struct).into())
+        }
+    }
+}
+
+/// Parse the declared type of a value argument: a type string, or the YAML
+/// object form of a named struct.
+fn parse_argument_type(
+    raw: &RawType,
+    ctx: &mut TypeContext,
+) -> Result<SignatureType, ScalarFunctionError> {
+    match raw {
+        RawType::String(s) => Ok(SignatureType::try_from(ctx.parse_type(s)?)?),
+        RawType::Object(_) => {
+            let named_struct: ConcreteType = Parse::parse(raw.clone(), ctx)?;
+            Ok(named_struct.into())
+        }
+    }
+}
+
+/// Convert a return (or intermediate) type back to its raw form. Named structs
+/// that can be written in the YAML object form are written as such.
+pub(super) fn raw_return_type(return_type: TypeDerivation) -> RawType {
+    match return_type.result() {
+        SignatureType {
+            kind: SignatureTypeKind::NamedStruct { fields },
+            nullable: false,
+            variation: None,
+        } if return_type.assignments().is_empty() => RawType::Object(
+            fields
+                .iter()
+                .map(|(name, t)| (name.clone(), serde_json::Value::String(t.to_string())))
+                .collect(),
+        ),
+        _ => RawType::String(return_type.to_string()),
+    }
+}
+
+/// Returns whether a type variable name is an `anyN` variable.
+fn is_any_variable(name: &str) -> bool {
+    name.strip_prefix("any")
+        .is_some_and(|id| !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit()))
+}
+
+impl Impl {
+    /// Returns the declared types of the value arguments of this
+    /// implementation, in order.
+    ///
+    /// The types are parsed and validated when the implementation is parsed;
+    /// they may contain type variables, which are bound by overload
+    /// resolution.
+    pub fn argument_types(&self) -> &[SignatureType] {
+        &self.argument_types
+    }
+
+    /// Returns the signature of this implementation, as used in compound
+    /// function names: the short names of its arguments joined by `_` (e.g.
+    /// `i32_i32` for `add:i32_i32`).
+    ///
+    /// See [ArgumentsItem::shorthand].
+    pub fn signature(&self) -> String {
+        self.args
+            .iter()
+            .map(ArgumentsItem::shorthand)
+            .collect::<Vec<_>>()
+            .join("_")
+    }
+
+    /// Returns the compound name of this implementation of the function with
+    /// the given name (e.g. `add:i32_i32`).
+    ///
+    /// Producers should use compound names in function declarations, so that
+    /// consumers can resolve the exact overload.
+    pub fn compound_name(&self, name: &str) -> String {
+        format!("{name}:{}", self.signature())
+    }
+}
+
 /// Validated variadic behavior with min/max constraints
 #[derive(Clone, Debug, PartialEq)]
 pub struct VariadicBehavior {
     /// Minimum number of arguments
-    pub min: u32,
+    ///
+    /// `None` when not specified in the YAML, in which case there is no lower
+    /// bound.
+    pub min: Option<u32>,
     /// Maximum number of arguments (unlimited when None)
     pub max: Option<u32>,
     /// Whether all variadic parameters must have the same type.
@@ -253,6 +493,17 @@ impl From<VariadicBehaviorParameterConsistency> for ParameterConsistency {
     }
 }
 
+impl From<ParameterConsistency> for VariadicBehaviorParameterConsistency {
+    fn from(parameter_consistency: ParameterConsistency) -> Self {
+        match parameter_consistency {
+            ParameterConsistency::Consistent => VariadicBehaviorParameterConsistency::Consistent,
+            ParameterConsistency::Inconsistent => {
+                VariadicBehaviorParameterConsistency::Inconsistent
+            }
+        }
+    }
+}
+
 impl TryFrom<RawVariadicBehavior> for VariadicBehavior {
     type Error = ScalarFunctionError;
 
@@ -267,14 +518,10 @@ impl TryFrom<RawVariadicBehavior> for VariadicBehavior {
             Ok(value as u32)
         }
 
-        let min = raw
-            .min
-            .map(|v| parse_bound(v, "min"))
-            .transpose()?
-            .unwrap_or(0);
+        let min = raw.min.map(|v| parse_bound(v, "min")).transpose()?;
         let max = raw.max.map(|v| parse_bound(v, "max")).transpose()?;
 
-        if let Some(max_val) = max {
+        if let (Some(min), Some(max_val)) = (min, max) {
             if min > max_val {
                 return Err(ScalarFunctionError::VariadicMinGreaterThanMax { min, max: max_val });
             }
@@ -288,6 +535,16 @@ impl TryFrom<RawVariadicBehavior> for VariadicBehavior {
     }
 }
 
+impl From<VariadicBehavior> for RawVariadicBehavior {
+    fn from(variadic: VariadicBehavior) -> Self {
+        RawVariadicBehavior {
+            min: variadic.min.map(Into::into),
+            max: variadic.max.map(Into::into),
+            parameter_consistency: variadic.parameter_consistency.map(Into::into),
+        }
+    }
+}
+
 /// How a function handles null inputs and produces nullable outputs
 #[derive(Clone, Debug, PartialEq)]
 pub enum NullabilityHandling {
@@ -309,16 +566,71 @@ impl From<RawNullabilityHandling> for NullabilityHandling {
     }
 }
 
-/// Validated function options
+impl From<NullabilityHandling> for RawNullabilityHandling {
+    fn from(nullability: NullabilityHandling) -> Self {
+        match nullability {
+            NullabilityHandling::Mirror => RawNullabilityHandling::Mirror,
+            NullabilityHandling::DeclaredOutput => RawNullabilityHandling::DeclaredOutput,
+            NullabilityHandling::Discrete => RawNullabilityHandling::Discrete,
+        }
+    }
+}
+
+/// Validated function options, by name in declaration order
 #[derive(Clone, Debug, Default, PartialEq)]
-pub struct Options(pub HashMap<String, Vec<String>>);
+pub struct Options(pub IndexMap<String, FunctionOption>);
+
+/// A configurable option of a function implementation
+#[derive(Clone, Debug, PartialEq)]
+pub struct FunctionOption {
+    /// Human-readable description of the option
+    pub description: Option<String>,
+    /// The values the option can take
+    pub values: Vec<String>,
+}
 
 impl From<&RawOptions> for Options {
     fn from(raw: &RawOptions) -> Self {
         Options(
             raw.0
                 .iter()
-                .map(|(k, v)| (k.clone(), v.values.clone()))
+                .map(|(name, value)| {
+                    (
+                        name.clone(),
+                        FunctionOption {
+                            description: value.description.clone(),
+                            values: value.values.clone(),
+                        },
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+impl From<Options> for RawOptions {
+    fn from(options: Options) -> Self {
+        RawOptions(
+            options
+                .0
+                .into_iter()
+                .map(
+                    |(
+                        name,
+                        FunctionOption {
+                            description,
+                            values,
+                        },
+                    )| {
+                        (
+                            name,
+                            RawOptionsValue {
+                                description,
+                                values,
+                            },
+                        )
+                    },
+                )
                 .collect(),
         )
     }
@@ -360,7 +672,7 @@ mod tests {
             parameter_consistency: None,
         };
         let result = VariadicBehavior::try_from(raw).unwrap();
-        assert_eq!(result.min, 1);
+        assert_eq!(result.min, Some(1));
         assert_eq!(result.max, Some(5));
     }
 
@@ -372,7 +684,7 @@ mod tests {
             parameter_consistency: None,
         };
         let result = VariadicBehavior::try_from(raw).unwrap();
-        assert_eq!(result.min, 0);
+        assert_eq!(result.min, None);
         assert_eq!(result.max, None);
     }
 
@@ -420,22 +732,141 @@ mod tests {
         };
 
         let mut ctx = super::super::extensions::TypeContext::default();
-        let result = ScalarFunction::from_raw(raw, &mut ctx).unwrap();
+        let result = ScalarFunction::from_raw(raw, &mut ctx)
+            .unwrap()
+            .expect("not skipped");
 
         assert_eq!(result.name, "add");
         assert_eq!(result.description, Some("Addition function".to_string()));
         assert_eq!(result.impls.len(), 1);
 
-        // Verify return type is properly parsed to ConcreteType
-        use super::super::types::{BasicBuiltinType, ConcreteTypeKind};
+        // Verify return type is properly parsed to a concrete signature type
+        use super::super::types::{BasicBuiltinType, ConcreteType};
         let return_type = &result.impls[0].return_type;
-        assert!(!return_type.nullable, "i32 should not be nullable");
+        assert!(!return_type.result().nullable, "i32 should not be nullable");
+        assert!(return_type.assignments().is_empty());
+        assert_eq!(
+            return_type.result(),
+            &SignatureType::from(ConcreteType::builtin(BasicBuiltinType::I32, false))
+        );
+    }
+
+    #[test]
+    fn test_scalar_function_with_type_variables() {
+        use crate::text::simple_extensions::{
+            Arguments, ArgumentsItem as RawArgumentsItem, ReturnValue,
+            ScalarFunction as RawScalarFunction, ScalarFunctionImplsItem, Type,
+            ValueArg as RawValueArg,
+        };
+
+        let impl_ = |args: &[&str], return_type: &str| ScalarFunctionImplsItem {
+            args: Some(Arguments(
+                args.iter()
+                    .map(|arg| {
+                        RawArgumentsItem::ValueArg(RawValueArg {
+                            name: None,
+                            description: None,
+                            value: Type::String(arg.to_string()),
+                            constant: None,
+                        })
+                    })
+                    .collect(),
+            )),
+            options: None,
+            variadic: None,
+            session_dependent: None,
+            deterministic: None,
+            nullability: None,
+            return_: ReturnValue(Type::String(return_type.to_string())),
+            implementation: None,
+        };
+        let raw = |impls| RawScalarFunction {
+            name: "generic".to_string(),
+            description: None,
+            metadata: Default::default(),
+            impls,
+        };
+
+        let mut ctx = super::super::extensions::TypeContext::default();
+        let result = ScalarFunction::from_raw(
+            raw(vec![
+                impl_(&["any1", "any1"], "any1?"),
+                impl_(&["DECIMAL<P1, S1>"], "DECIMAL<38, S1>"),
+                impl_(&["list<T>"], "T"),
+            ]),
+            &mut ctx,
+        )
+        .unwrap()
+        .expect("not skipped");
+        let return_types = result
+            .impls
+            .iter()
+            .map(|impl_| impl_.return_type.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(return_types, ["any1?", "decimal<38, S1>", "T"]);
+        assert_eq!(result.impls[2].argument_types()[0].to_string(), "list<T>");
+
+        // Argument types are validated like return types.
+        let result = ScalarFunction::from_raw(raw(vec![impl_(&["unknown<i32>"], "i32")]), &mut ctx);
+        assert!(matches!(
+            result,
+            Err(ScalarFunctionError::TypeError(ExtensionTypeError::UnknownTypeName { name }))
+            if name == "unknown"
+        ));
+
+        // Named type variables must be bound by the arguments.
+        let result = ScalarFunction::from_raw(raw(vec![impl_(&["i32"], "T")]), &mut ctx);
         assert!(matches!(
-            &return_type.kind,
-            ConcreteTypeKind::Builtin(BasicBuiltinType::I32)
+            result,
+            Err(ScalarFunctionError::TypeError(ExtensionTypeError::UnknownTypeName { name }))
+            if name == "T"
         ));
     }
 
+    #[test]
+    fn test_struct_return_type() {
+        use super::super::types::BasicBuiltinType;
+        use super::super::{ExtensionFile, Registry};
+
+        let yaml = r#"
+%YAML 1.2
+---
+urn: extension:example.com:structs
+scalar_functions:
+  - name: split_point
+    impls:
+      - args:
+          - value: string
+        return:
+          x: fp64
+          y: fp64?
+"#;
+        let file = ExtensionFile::read_from_str(yaml).unwrap();
+        let urn = file.urn().clone();
+        let registry = Registry::new([file]).unwrap();
+        let impl_ = registry
+            .get_scalar_function_impl(&urn, "split_point:str")
+            .unwrap();
+
+        let mut fields = indexmap::IndexMap::new();
+        fields.insert(
+            "x".to_string(),
+            ConcreteType::builtin(BasicBuiltinType::Fp64, false),
+        );
+        fields.insert(
+            "y".to_string(),
+            ConcreteType::builtin(BasicBuiltinType::Fp64, true),
+        );
+        assert_eq!(
+            impl_.return_type.result(),
+            &SignatureType::from(ConcreteType::named_struct(fields, false))
+        );
+
+        // Named structs are written back in the object form.
+        let RawReturnValue(raw) = RawImpl::from(impl_.clone()).return_;
+        assert_eq!(serde_yaml::to_string(&raw).unwrap(), "x: fp64\ny: fp64?\n");
+    }
+
     #[test]
     fn test_options_conversion() {
         use crate::text::simple_extensions::{Options as RawOptions, OptionsValue};
@@ -446,7 +877,7 @@ mod tests {
             "overflow".to_string(),
             OptionsValue {
                 values: vec!["SILENT".to_string(), "ERROR".to_string()],
-                description: None,
+                description: Some("Behavior on overflow".to_string()),
             },
         );
 
@@ -454,9 +885,15 @@ mod tests {
         let options = Options::from(&raw);
 
         assert_eq!(options.0.len(), 1);
+        let overflow = options.0.get("overflow").unwrap();
+        assert_eq!(
+            overflow.values,
+            vec!["SILENT".to_string(), "ERROR".to_string()]
+        );
         assert_eq!(
-            options.0.get("overflow").unwrap(),
-            &vec!["SILENT".to_string(), "ERROR".to_string()]
+            overflow.description.as_deref(),
+            Some("Behavior on overflow")
         );
+        assert_eq!(RawOptions::from(options), raw);
     }
 }
@@ -0,0 +1,1066 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Types in function signatures, which may contain type variables.
+//!
+//! Function signatures use the same type syntax as concrete types, but can
+//! refer to type variables (`any1`, `T`) and integer parameter variables
+//! (`L1`, `P`, `S`), e.g. `DECIMAL<P1, S1>` or `list<any1>`. A
+//! [`SignatureType`] represents such a type, and [`TypeBindings`] unifies
+//! signature types with [`ConcreteType`]s, binding the variables, and
+//! instantiates signature types from the bound variables.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use indexmap::IndexMap;
+
+use super::type_ast::{TypeExpr, TypeExprParam};
+use super::types::{
+    BasicBuiltinType, ConcreteType, ConcreteTypeKind, ExtensionTypeError, TypeParameter,
+    option_type, parse_builtin, unaliased, write_separated,
+};
+use crate::urn::Urn;
+
+/// A type in a function signature, with nullability.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignatureType {
+    /// The type shape
+    pub kind: SignatureTypeKind,
+    /// Whether this type is nullable
+    pub nullable: bool,
+    /// The name of the type variation, if any. A signature type without a
+    /// variation matches all variations of a type.
+    pub variation: Option<String>,
+}
+
+/// The shape of a [`SignatureType`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum SignatureTypeKind {
+    /// Any type - `any`. Matches every type without binding it.
+    Any,
+    /// A type variable, e.g. `any1` or `T`. All occurrences of a type variable
+    /// in a signature bind to the same type.
+    Variable(String),
+    /// Built-in type, with parameters that may be variables, e.g.
+    /// `DECIMAL<P1, S1>`.
+    Builtin {
+        /// Lowercase name of the built-in type (e.g. `decimal`)
+        name: String,
+        /// Type parameters
+        parameters: Vec<SignatureParameter>,
+    },
+    /// Extension type, with parameters that may be variables.
+    Extension {
+        /// Extension type name
+        name: String,
+        /// The URN of the extension defining the type, if known. See
+        /// [`ConcreteTypeKind::Extension`].
+        urn: Option<Urn>,
+        /// Type parameters
+        parameters: Vec<SignatureParameter>,
+    },
+    /// List type with element type
+    List(Box<SignatureType>),
+    /// Map type with key and value types
+    Map {
+        /// Key type
+        key: Box<SignatureType>,
+        /// Value type
+        value: Box<SignatureType>,
+    },
+    /// Struct type (ordered fields without names)
+    Struct(Vec<SignatureType>),
+    /// Named struct type (ordered fields with names)
+    NamedStruct {
+        /// Ordered field names and types
+        fields: IndexMap<String, SignatureType>,
+    },
+}
+
+/// A parameter of a parameterized [`SignatureType`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum SignatureParameter {
+    /// Integer parameter, e.g. the `38` in `DECIMAL<38, S>`
+    Integer(i64),
+    /// Integer parameter variable, e.g. the `P` in `DECIMAL<P, S>`
+    IntegerVariable(String),
+    /// Type parameter
+    Type(SignatureType),
+    /// Boolean parameter of an extension type
+    Boolean(bool),
+    /// String parameter of an extension type
+    String(String),
+    /// Bare name parameter of an extension type, e.g. the `T` in `u!box<T>`.
+    ///
+    /// This matches either the enumeration option of the same name, or any
+    /// type, binding the name as a type variable.
+    Enum(String),
+    /// Null parameter of an extension type, for an omitted optional parameter
+    Null,
+}
+
+impl SignatureType {
+    /// Returns the names of the type variables in this type, in order of
+    /// appearance.
+    pub fn type_variables(&self) -> Vec<&str> {
+        let mut variables = Vec::new();
+        self.visit(&mut |kind| {
+            if let SignatureTypeKind::Variable(name) = kind {
+                variables.push(name.as_str());
+            }
+        });
+        variables
+    }
+
+    /// Visit all extension type references contained in this type, calling
+    /// `on_ext` for each encountered extension name.
+    pub fn visit_references<F>(&self, on_ext: &mut F)
+    where
+        F: FnMut(&str),
+    {
+        self.visit(&mut |kind| {
+            if let SignatureTypeKind::Extension { name, .. } = kind {
+                on_ext(name);
+            }
+        });
+    }
+
+    /// Returns whether this type contains no variables (and no `any`).
+    pub fn is_concrete(&self) -> bool {
+        let mut concrete = true;
+        self.visit(&mut |kind| match kind {
+            SignatureTypeKind::Any | SignatureTypeKind::Variable(_) => concrete = false,
+            SignatureTypeKind::Builtin { parameters, .. }
+            | SignatureTypeKind::Extension { parameters, .. } => {
+                concrete &= !parameters
+                    .iter()
+                    .any(|p| matches!(p, SignatureParameter::IntegerVariable(_)))
+            }
+            _ => {}
+        });
+        concrete
+    }
+
+    /// Qualify the extension types referenced in this type by the URN of the
+    /// extension defining them, see [`ConcreteType::qualify`].
+    pub(super) fn qualify(&mut self, type_urn: &impl Fn(&str) -> Option<Urn>) {
+        match &mut self.kind {
+            SignatureTypeKind::Builtin { parameters, .. } => {
+                qualify_parameters(parameters, type_urn)
+            }
+            SignatureTypeKind::Extension {
+                name,
+                urn,
+                parameters,
+            } => {
+                if urn.is_none() {
+                    *urn = type_urn(name);
+                    if urn.is_some() {
+                        *name = unaliased(name).to_string();
+                    }
+                }
+                qualify_parameters(parameters, type_urn);
+            }
+            SignatureTypeKind::List(element) => element.qualify(type_urn),
+            SignatureTypeKind::Map { key, value } => {
+                key.qualify(type_urn);
+                value.qualify(type_urn);
+            }
+            SignatureTypeKind::Struct(fields) => {
+                fields.iter_mut().for_each(|t| t.qualify(type_urn))
+            }
+            SignatureTypeKind::NamedStruct { fields } => {
+                fields.values_mut().for_each(|t| t.qualify(type_urn))
+            }
+            SignatureTypeKind::Any | SignatureTypeKind::Variable(_) => {}
+        }
+    }
+
+    /// Visit the kinds of this type and all nested types.
+    fn visit<'a>(&'a self, f: &mut impl FnMut(&'a SignatureTypeKind)) {
+        f(&self.kind);
+        match &self.kind {
+            SignatureTypeKind::Builtin { parameters, .. }
+            | SignatureTypeKind::Extension { parameters, .. } => {
+                for parameter in parameters {
+                    if let SignatureParameter::Type(t) = parameter {
+                        t.visit(f);
+                    }
+                }
+            }
+            SignatureTypeKind::List(element) => element.visit(f),
+            SignatureTypeKind::Map { key, value } => {
+                key.visit(f);
+                value.visit(f);
+            }
+            SignatureTypeKind::Struct(fields) => fields.iter().for_each(|t| t.visit(f)),
+            SignatureTypeKind::NamedStruct { fields } => fields.values().for_each(|t| t.visit(f)),
+            SignatureTypeKind::Any | SignatureTypeKind::Variable(_) => {}
+        }
+    }
+}
+
+/// Qualify the type parameters of a [`SignatureType`], see
+/// [`SignatureType::qualify`].
+fn qualify_parameters(
+    parameters: &mut [SignatureParameter],
+    type_urn: &impl Fn(&str) -> Option<Urn>,
+) {
+    for parameter in parameters {
+        if let SignatureParameter::Type(t) = parameter {
+            t.qualify(type_urn);
+        }
+    }
+}
+
+impl fmt::Display for SignatureParameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureParameter::Integer(i) => write!(f, "{i}"),
+            SignatureParameter::IntegerVariable(name) => f.write_str(name),
+            SignatureParameter::Type(t) => write!(f, "{t}"),
+            SignatureParameter::Boolean(b) => write!(f, "{b}"),
+            SignatureParameter::String(s) => write!(f, "'{s}'"),
+            SignatureParameter::Enum(name) => f.write_str(name),
+            SignatureParameter::Null => f.write_str("null"),
+        }
+    }
+}
+
+/// Write a type in the type string syntax, with the nullability marker and
+/// variation between the name and the parameters (e.g. `decimal?<P, S>` or
+/// `varchar[dict]<L1>`), so that the output can be parsed again.
+fn write_type(
+    f: &mut fmt::Formatter<'_>,
+    kind: &SignatureTypeKind,
+    nullable: bool,
+    variation: Option<&str>,
+) -> fmt::Result {
+    let nullable = match (nullable, variation) {
+        (true, Some(variation)) => format!("?[{variation}]"),
+        (false, Some(variation)) => format!("[{variation}]"),
+        (true, None) => "?".to_string(),
+        (false, None) => String::new(),
+    };
+    match kind {
+        SignatureTypeKind::Any => write!(f, "any{nullable}"),
+        SignatureTypeKind::Variable(name) => write!(f, "{name}{nullable}"),
+        SignatureTypeKind::Builtin { name, parameters } => {
+            write!(f, "{name}{nullable}")?;
+            write_separated(f, parameters.iter(), "<", ">", ", ")
+        }
+        SignatureTypeKind::Extension {
+            name, parameters, ..
+        } => {
+            write!(f, "u!{name}{nullable}")?;
+            write_separated(f, parameters.iter(), "<", ">", ", ")
+        }
+        SignatureTypeKind::List(element) => write!(f, "list{nullable}<{element}>"),
+        SignatureTypeKind::Map { key, value } => write!(f, "map{nullable}<{key}, {value}>"),
+        SignatureTypeKind::Struct(fields) => {
+            write!(f, "struct{nullable}")?;
+            write_separated(f, fields.iter(), "<", ">", ", ")
+        }
+        SignatureTypeKind::NamedStruct { fields } => {
+            let fields = fields.iter().map(|(name, t)| format!("{name}: {t}"));
+            write_separated(f, fields, "{", "}", ", ")?;
+            f.write_str(&nullable)
+        }
+    }
+}
+
+impl fmt::Display for SignatureTypeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_type(f, self, false, None)
+    }
+}
+
+impl fmt::Display for SignatureType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_type(f, &self.kind, self.nullable, self.variation.as_deref())
+    }
+}
+
+/// Returns the lowercase name and integer parameters of a built-in type.
+fn builtin_parts(builtin: &BasicBuiltinType) -> (&'static str, Vec<i64>) {
+    match *builtin {
+        BasicBuiltinType::Boolean => ("bool", vec![]),
+        BasicBuiltinType::I8 => ("i8", vec![]),
+        BasicBuiltinType::I16 => ("i16", vec![]),
+        BasicBuiltinType::I32 => ("i32", vec![]),
+        BasicBuiltinType::I64 => ("i64", vec![]),
+        BasicBuiltinType::Fp32 => ("fp32", vec![]),
+        BasicBuiltinType::Fp64 => ("fp64", vec![]),
+        BasicBuiltinType::String => ("string", vec![]),
+        BasicBuiltinType::Binary => ("binary", vec![]),
+        BasicBuiltinType::Timestamp => ("timestamp", vec![]),
+        BasicBuiltinType::TimestampTz => ("timestamp_tz", vec![]),
+        BasicBuiltinType::Date => ("date", vec![]),
+        BasicBuiltinType::Time => ("time", vec![]),
+        BasicBuiltinType::IntervalYear => ("interval_year", vec![]),
+        BasicBuiltinType::Uuid => ("uuid", vec![]),
+        BasicBuiltinType::FixedChar { length } => ("fixedchar", vec![length.into()]),
+        BasicBuiltinType::VarChar { length } => ("varchar", vec![length.into()]),
+        BasicBuiltinType::FixedBinary { length } => ("fixedbinary", vec![length.into()]),
+        BasicBuiltinType::Decimal { precision, scale } => {
+            ("decimal", vec![precision.into(), scale.into()])
+        }
+        BasicBuiltinType::PrecisionTime { precision } => ("precision_time", vec![precision.into()]),
+        BasicBuiltinType::PrecisionTimestamp { precision } => {
+            ("precision_timestamp", vec![precision.into()])
+        }
+        BasicBuiltinType::PrecisionTimestampTz { precision } => {
+            ("precision_timestamp_tz", vec![precision.into()])
+        }
+        BasicBuiltinType::IntervalDay { precision } => ("interval_day", vec![precision.into()]),
+        BasicBuiltinType::IntervalCompound { precision } => {
+            ("interval_compound", vec![precision.into()])
+        }
+    }
+}
+
+/// Returns the canonical lowercase name of a built-in type, as used by
+/// [`builtin_parts`].
+fn canonical_builtin_name(lower_name: &str) -> &str {
+    match lower_name {
+        "boolean" => "bool",
+        "precisiontime" => "precision_time",
+        other => other,
+    }
+}
+
+impl From<ConcreteType> for SignatureType {
+    fn from(concrete: ConcreteType) -> Self {
+        let parameter = |parameter: TypeParameter| match parameter {
+            TypeParameter::Integer(i) => SignatureParameter::Integer(i),
+            TypeParameter::Type(t) => SignatureParameter::Type(t.into()),
+            TypeParameter::Boolean(b) => SignatureParameter::Boolean(b),
+            TypeParameter::String(s) => SignatureParameter::String(s),
+            TypeParameter::Enum(e) => SignatureParameter::Enum(e),
+            TypeParameter::Null => SignatureParameter::Null,
+        };
+        let kind = match concrete.kind {
+            ConcreteTypeKind::Builtin(builtin) => {
+                let (name, parameters) = builtin_parts(&builtin);
+                SignatureTypeKind::Builtin {
+                    name: name.to_string(),
+                    parameters: parameters
+                        .into_iter()
+                        .map(SignatureParameter::Integer)
+                        .collect(),
+                }
+            }
+            ConcreteTypeKind::Extension {
+                name,
+                urn,
+                parameters,
+            } => SignatureTypeKind::Extension {
+                name,
+                urn,
+                parameters: parameters.into_iter().map(parameter).collect(),
+            },
+            ConcreteTypeKind::List(element) => SignatureTypeKind::List(Box::new((*element).into())),
+            ConcreteTypeKind::Map { key, value } => SignatureTypeKind::Map {
+                key: Box::new((*key).into()),
+                value: Box::new((*value).into()),
+            },
+            ConcreteTypeKind::Struct(fields) => {
+                SignatureTypeKind::Struct(fields.into_iter().map(Into::into).collect())
+            }
+            ConcreteTypeKind::NamedStruct { fields } => SignatureTypeKind::NamedStruct {
+                fields: fields
+                    .into_iter()
+                    .map(|(name, t)| (name, t.into()))
+                    .collect(),
+            },
+        };
+        SignatureType {
+            kind,
+            nullable: concrete.nullable,
+            variation: concrete.variation,
+        }
+    }
+}
+
+/// Convert a type expression parameter of a built-in type: bare names are
+/// integer parameter variables.
+fn builtin_parameter(
+    type_name: &str,
+    index: usize,
+    param: TypeExprParam<'_>,
+) -> Result<SignatureParameter, ExtensionTypeError> {
+    match param {
+        TypeExprParam::Integer(i) => Ok(SignatureParameter::Integer(i)),
+        TypeExprParam::Enum(name) => Ok(SignatureParameter::IntegerVariable(name.to_string())),
+        TypeExprParam::Type(t) => Ok(SignatureParameter::Type(t.try_into()?)),
+        TypeExprParam::Boolean(_) | TypeExprParam::String(_) | TypeExprParam::Null => {
+            Err(ExtensionTypeError::InvalidParameterKind {
+                type_name: type_name.to_string(),
+                index,
+                expected: "an integer",
+            })
+        }
+    }
+}
+
+/// Returns whether a bare name is a type variable.
+///
+/// Besides `any` and `anyN` (see [`TypeExpr::TypeVariable`]), type variables
+/// follow the naming convention of parameters: upper case letters and digits
+/// (e.g. `T` or `K1`). Other names are unknown types, such as misspelled
+/// built-in types or extension types without the `u!` prefix.
+fn is_type_variable_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Convert a type expression parameter of an extension type.
+fn extension_parameter(param: TypeExprParam<'_>) -> Result<SignatureParameter, ExtensionTypeError> {
+    Ok(match param {
+        TypeExprParam::Integer(i) => SignatureParameter::Integer(i),
+        TypeExprParam::Type(t) => SignatureParameter::Type(t.try_into()?),
+        TypeExprParam::Boolean(b) => SignatureParameter::Boolean(b),
+        TypeExprParam::String(s) => SignatureParameter::String(s.to_string()),
+        TypeExprParam::Enum(name) => SignatureParameter::Enum(name.to_string()),
+        TypeExprParam::Null => SignatureParameter::Null,
+    })
+}
+
+impl<'a> TryFrom<TypeExpr<'a>> for SignatureType {
+    type Error = ExtensionTypeError;
+
+    fn try_from(type_expr: TypeExpr<'a>) -> Result<Self, Self::Error> {
+        let (kind, nullable, variation) = match type_expr {
+            TypeExpr::TypeVariable(id, nullable) => (
+                SignatureTypeKind::Variable(format!("any{id}")),
+                nullable,
+                None,
+            ),
+            TypeExpr::UserDefined(name, params, nullable, variation) => (
+                SignatureTypeKind::Extension {
+                    name: name.to_string(),
+                    urn: None,
+                    parameters: params
+                        .into_iter()
+                        .map(extension_parameter)
+                        .collect::<Result<_, _>>()?,
+                },
+                nullable,
+                variation,
+            ),
+            TypeExpr::Simple(name, params, nullable, variation) => {
+                let lower = name.to_ascii_lowercase();
+                let kind = match lower.as_str() {
+                    "any" if params.is_empty() => SignatureTypeKind::Any,
+                    "list" | "map" | "struct" => {
+                        let mut types = params
+                            .into_iter()
+                            .enumerate()
+                            .map(|(index, param)| match param {
+                                TypeExprParam::Type(t) => SignatureType::try_from(t),
+                                TypeExprParam::Enum(name) if is_type_variable_name(name) => {
+                                    Ok(SignatureType {
+                                        kind: SignatureTypeKind::Variable(name.to_string()),
+                                        nullable: false,
+                                        variation: None,
+                                    })
+                                }
+                                TypeExprParam::Enum(name) => {
+                                    Err(ExtensionTypeError::UnknownTypeName {
+                                        name: name.to_string(),
+                                    })
+                                }
+                                _ => Err(ExtensionTypeError::InvalidParameterKind {
+                                    type_name: name.to_string(),
+                                    index,
+                                    expected: "a type",
+                                }),
+                            })
+                            .collect::<Result<Vec<_>, _>>()?;
+                        let expected = match lower.as_str() {
+                            "list" => 1,
+                            "map" => 2,
+                            _ => types.len(),
+                        };
+                        if types.len() != expected {
+                            return Err(ExtensionTypeError::InvalidParameterCount {
+                                type_name: name.to_string(),
+                                expected,
+                                actual: types.len(),
+                            });
+                        }
+                        match lower.as_str() {
+                            "list" => SignatureTypeKind::List(Box::new(types.remove(0))),
+                            "map" => {
+                                let value = types.pop().unwrap();
+                                let key = types.pop().unwrap();
+                                SignatureTypeKind::Map {
+                                    key: Box::new(key),
+                                    value: Box::new(value),
+                                }
+                            }
+                            _ => SignatureTypeKind::Struct(types),
+                        }
+                    }
+                    _ if BasicBuiltinType::is_name(&lower) => {
+                        let parameters = params
+                            .iter()
+                            .cloned()
+                            .enumerate()
+                            .map(|(index, param)| builtin_parameter(name, index, param))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        // Validate fully specified built-in types.
+                        if parameters
+                            .iter()
+                            .all(|p| matches!(p, SignatureParameter::Integer(_)))
+                        {
+                            parse_builtin(name, &lower, &params)?;
+                        }
+                        SignatureTypeKind::Builtin {
+                            name: canonical_builtin_name(&lower).to_string(),
+                            parameters,
+                        }
+                    }
+                    _ if params.is_empty() && is_type_variable_name(name) => {
+                        SignatureTypeKind::Variable(name.to_string())
+                    }
+                    _ => {
+                        return Err(ExtensionTypeError::UnknownTypeName {
+                            name: name.to_string(),
+                        });
+                    }
+                };
+                (kind, nullable, variation)
+            }
+            TypeExpr::Function(..) => return Err(ExtensionTypeError::FunctionType),
+        };
+        Ok(SignatureType {
+            kind,
+            nullable,
+            variation: variation.map(str::to_string),
+        })
+    }
+}
+
+/// Bindings of type variables and integer parameter variables, built by
+/// unifying [`SignatureType`]s with [`ConcreteType`]s.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TypeBindings {
+    /// Bound type variables. Types bound by top-level signature types are
+    /// stored without nullability, types bound by nested signature types
+    /// (e.g. the `any1` in `list<any1>`) keep it.
+    types: HashMap<String, ConcreteType>,
+    /// Type variables whose nullability is fixed by a nested binding
+    nested: HashSet<String>,
+    /// Bound integer parameter variables
+    integers: HashMap<String, i64>,
+}
+
+impl TypeBindings {
+    /// Returns the type bound to a type variable, if any.
+    pub fn get_type(&self, name: &str) -> Option<&ConcreteType> {
+        self.types.get(name)
+    }
+
+    /// Returns the value bound to an integer parameter variable, if any.
+    pub fn get_integer(&self, name: &str) -> Option<i64> {
+        self.integers.get(name).copied()
+    }
+
+    /// Bind an integer parameter variable, e.g. from a type derivation.
+    pub fn bind_integer(&mut self, name: impl Into<String>, value: i64) {
+        self.integers.insert(name.into(), value);
+    }
+
+    /// Keep only the bindings that another set of bindings binds to the same
+    /// value.
+    pub(crate) fn retain_common(&mut self, other: &TypeBindings) {
+        self.types
+            .retain(|name, bound| other.types.get(name) == Some(bound));
+        self.nested
+            .retain(|name| self.types.contains_key(name) && other.nested.contains(name));
+        self.integers
+            .retain(|name, value| other.integers.get(name) == Some(value));
+    }
+
+    /// Unify a signature type with a concrete type, binding the variables of
+    /// the signature type.
+    ///
+    /// The top-level nullability is not compared, as it depends on the
+    /// nullability handling of a function. A signature type without a type
+    /// variation matches all variations. Returns `false` if the types do not
+    /// match, or if a variable is already bound to a different value. The
+    /// bindings may be partially updated in that case.
+    pub fn unify(&mut self, pattern: &SignatureType, concrete: &ConcreteType) -> bool {
+        self.unify_type(pattern, concrete, false)
+    }
+
+    /// Unify a signature type with a concrete type, where `nested` tells
+    /// whether the signature type is part of another type.
+    fn unify_type(
+        &mut self,
+        pattern: &SignatureType,
+        concrete: &ConcreteType,
+        nested: bool,
+    ) -> bool {
+        if pattern
+            .variation
+            .as_ref()
+            .is_some_and(|variation| concrete.variation.as_ref() != Some(variation))
+        {
+            return false;
+        }
+        match (&pattern.kind, &concrete.kind) {
+            (SignatureTypeKind::Any, _) => true,
+            (SignatureTypeKind::Variable(name), _) => self.bind_type(name, concrete, nested),
+            (
+                SignatureTypeKind::Builtin { name, parameters },
+                ConcreteTypeKind::Builtin(builtin),
+            ) => {
+                let (builtin_name, values) = builtin_parts(builtin);
+                name == builtin_name
+                    && parameters.len() == values.len()
+                    && parameters
+                        .iter()
+                        .zip(values)
+                        .all(|(parameter, value)| self.unify_integer(parameter, value))
+            }
+            (
+                SignatureTypeKind::Extension {
+                    name,
+                    urn,
+                    parameters,
+                },
+                ConcreteTypeKind::Extension {
+                    name: concrete_name,
+                    urn: concrete_urn,
+                    parameters: concrete_parameters,
+                },
+            ) => {
+                // Types are compared by URN when both are qualified.
+                name == concrete_name
+                    && (urn.is_none() || concrete_urn.is_none() || urn == concrete_urn)
+                    && parameters.len() == concrete_parameters.len()
+                    && parameters.iter().zip(concrete_parameters).all(
+                        |(parameter, concrete)| match (parameter, concrete) {
+                            (SignatureParameter::Type(pattern), TypeParameter::Type(concrete)) => {
+                                self.unify_nested(pattern, concrete)
+                            }
+                            (parameter, TypeParameter::Integer(value)) => {
+                                self.unify_integer(parameter, *value)
+                            }
+                            (SignatureParameter::Enum(name), TypeParameter::Enum(option)) => {
+                                name == option
+                            }
+                            (SignatureParameter::Enum(name), TypeParameter::Type(concrete))
+                                if is_type_variable_name(name) =>
+                            {
+                                self.bind_type(name, concrete, true)
+                            }
+                            (SignatureParameter::Type(pattern), TypeParameter::Enum(option)) => {
+                                option_type(option)
+                                    .is_some_and(|t| SignatureType::from(t) == *pattern)
+                            }
+                            (SignatureParameter::Boolean(b), TypeParameter::Boolean(value)) => {
+                                b == value
+                            }
+                            (SignatureParameter::String(s), TypeParameter::String(value)) => {
+                                s == value
+                            }
+                            (SignatureParameter::Null, TypeParameter::Null) => true,
+                            _ => false,
+                        },
+                    )
+            }
+            (SignatureTypeKind::List(pattern), ConcreteTypeKind::List(concrete)) => {
+                self.unify_nested(pattern, concrete)
+            }
+            (
+                SignatureTypeKind::Map { key, value },
+                ConcreteTypeKind::Map {
+                    key: concrete_key,
+                    value: concrete_value,
+                },
+            ) => self.unify_nested(key, concrete_key) && self.unify_nested(value, concrete_value),
+            (SignatureTypeKind::Struct(fields), ConcreteTypeKind::Struct(concrete_fields)) => {
+                fields.len() == concrete_fields.len()
+                    && fields
+                        .iter()
+                        .zip(concrete_fields)
+                        .all(|(pattern, concrete)| self.unify_nested(pattern, concrete))
+            }
+            (
+                SignatureTypeKind::NamedStruct { fields },
+                ConcreteTypeKind::NamedStruct {
+                    fields: concrete_fields,
+                },
+            ) => {
+                fields.len() == concrete_fields.len()
+                    && fields.iter().zip(concrete_fields).all(
+                        |((name, pattern), (concrete_name, concrete))| {
+                            name == concrete_name && self.unify_nested(pattern, concrete)
+                        },
+                    )
+            }
+            _ => false,
+        }
+    }
+
+    /// Unify a nested signature type, where nullability must match unless the
+    /// signature type is a variable.
+    fn unify_nested(&mut self, pattern: &SignatureType, concrete: &ConcreteType) -> bool {
+        let nullability = matches!(
+            pattern.kind,
+            SignatureTypeKind::Any | SignatureTypeKind::Variable(_)
+        ) || pattern.nullable == concrete.nullable;
+        nullability && self.unify_type(pattern, concrete, true)
+    }
+
+    /// Bind a type variable, or check the type bound to it.
+    ///
+    /// The nullability of top-level types depends on the nullability
+    /// handling of a function, so it is only bound by nested types. Once
+    /// bound, the nullability must match for nested types.
+    fn bind_type(&mut self, name: &str, concrete: &ConcreteType, nested: bool) -> bool {
+        let Some(bound) = self.types.get_mut(name) else {
+            let nullable = nested && concrete.nullable;
+            self.types.insert(
+                name.to_string(),
+                ConcreteType {
+                    nullable,
+                    ..concrete.clone()
+                },
+            );
+            if nested {
+                self.nested.insert(name.to_string());
+            }
+            return true;
+        };
+        if bound.kind != concrete.kind || bound.variation != concrete.variation {
+            return false;
+        }
+        if !nested {
+            return true;
+        }
+        if self.nested.contains(name) {
+            return bound.nullable == concrete.nullable;
+        }
+        bound.nullable = concrete.nullable;
+        self.nested.insert(name.to_string());
+        true
+    }
+
+    /// Unify an integer parameter with a value.
+    fn unify_integer(&mut self, parameter: &SignatureParameter, value: i64) -> bool {
+        match parameter {
+            SignatureParameter::Integer(i) => *i == value,
+            SignatureParameter::IntegerVariable(name) => {
+                *self.integers.entry(name.clone()).or_insert(value) == value
+            }
+            _ => false,
+        }
+    }
+
+    /// Instantiate a signature type, replacing the variables with their bound
+    /// values.
+    ///
+    /// A top-level variable takes the nullability of the signature type, a
+    /// nested variable is also nullable if its bound type is.
+    pub fn instantiate(&self, pattern: &SignatureType) -> Result<ConcreteType, BindingError> {
+        self.instantiate_type(pattern, false)
+    }
+
+    /// Instantiate a signature type, where `nested` tells whether the
+    /// signature type is part of another type.
+    fn instantiate_type(
+        &self,
+        pattern: &SignatureType,
+        nested: bool,
+    ) -> Result<ConcreteType, BindingError> {
+        let nullable = pattern.nullable;
+        let kind = match &pattern.kind {
+            SignatureTypeKind::Any => return Err(BindingError::UnboundVariable("any".to_string())),
+            SignatureTypeKind::Variable(name) => {
+                let bound = self
+                    .types
+                    .get(name)
+                    .ok_or_else(|| BindingError::UnboundVariable(name.clone()))?;
+                return Ok(ConcreteType {
+                    nullable: nullable || (nested && bound.nullable),
+                    variation: pattern
+                        .variation
+                        .clone()
+                        .or_else(|| bound.variation.clone()),
+                    ..bound.clone()
+                });
+            }
+            SignatureTypeKind::Builtin { name, parameters } => {
+                let parameters = parameters
+                    .iter()
+                    .map(|parameter| {
+                        self.instantiate_integer(parameter)
+                            .map(TypeExprParam::Integer)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let builtin = parse_builtin(name, name, &parameters)?
+                    .ok_or_else(|| ExtensionTypeError::UnknownTypeName { name: name.clone() })?;
+                ConcreteTypeKind::Builtin(builtin)
+            }
+            SignatureTypeKind::Extension {
+                name,
+                urn,
+                parameters,
+            } => ConcreteTypeKind::Extension {
+                name: name.clone(),
+                urn: urn.clone(),
+                parameters: parameters
+                    .iter()
+                    .map(|parameter| match parameter {
+                        SignatureParameter::Type(t) => {
+                            self.instantiate_type(t, true).map(TypeParameter::Type)
+                        }
+                        SignatureParameter::Enum(name) => Ok(match self.types.get(name) {
+                            Some(bound) => TypeParameter::Type(bound.clone()),
+                            None => TypeParameter::Enum(name.clone()),
+                        }),
+                        SignatureParameter::Boolean(b) => Ok(TypeParameter::Boolean(*b)),
+                        SignatureParameter::String(s) => Ok(TypeParameter::String(s.clone())),
+                        SignatureParameter::Null => Ok(TypeParameter::Null),
+                        integer => self
+                            .instantiate_integer(integer)
+                            .map(TypeParameter::Integer),
+                    })
+                    .collect::<Result<_, _>>()?,
+            },
+            SignatureTypeKind::List(element) => {
+                ConcreteTypeKind::List(Box::new(self.instantiate_type(element, true)?))
+            }
+            SignatureTypeKind::Map { key, value } => ConcreteTypeKind::Map {
+                key: Box::new(self.instantiate_type(key, true)?),
+                value: Box::new(self.instantiate_type(value, true)?),
+            },
+            SignatureTypeKind::Struct(fields) => ConcreteTypeKind::Struct(
+                fields
+                    .iter()
+                    .map(|t| self.instantiate_type(t, true))
+                    .collect::<Result<_, _>>()?,
+            ),
+            SignatureTypeKind::NamedStruct { fields } => ConcreteTypeKind::NamedStruct {
+                fields: fields
+                    .iter()
+                    .map(|(name, t)| Ok((name.clone(), self.instantiate_type(t, true)?)))
+                    .collect::<Result<_, BindingError>>()?,
+            },
+        };
+        Ok(ConcreteType {
+            kind,
+            nullable,
+            variation: pattern.variation.clone(),
+        })
+    }
+
+    /// Instantiate an integer parameter.
+    fn instantiate_integer(&self, parameter: &SignatureParameter) -> Result<i64, BindingError> {
+        match parameter {
+            SignatureParameter::Integer(i) => Ok(*i),
+            SignatureParameter::IntegerVariable(name) => self
+                .integers
+                .get(name)
+                .copied()
+                .ok_or_else(|| BindingError::UnboundVariable(name.clone())),
+            SignatureParameter::Type(t) => Err(BindingError::UnexpectedType(t.to_string())),
+            other => Err(BindingError::UnexpectedParameter(other.to_string())),
+        }
+    }
+}
+
+/// Errors that can occur when instantiating a [`SignatureType`].
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum BindingError {
+    /// A variable is not bound.
+    #[error("unbound variable `{0}`")]
+    UnboundVariable(String),
+    /// A type was used where an integer parameter was expected.
+    #[error("expected an integer parameter, got type `{0}`")]
+    UnexpectedType(String),
+    /// A non-integer literal was used where an integer parameter was expected.
+    #[error("expected an integer parameter, got `{0}`")]
+    UnexpectedParameter(String),
+    /// The instantiated type is invalid.
+    #[error("invalid type: {0}")]
+    InvalidType(#[from] ExtensionTypeError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signature(type_str: &str) -> SignatureType {
+        SignatureType::try_from(TypeExpr::parse(type_str).unwrap()).unwrap()
+    }
+
+    fn concrete(type_str: &str) -> ConcreteType {
+        ConcreteType::try_from(TypeExpr::parse(type_str).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn parse_signature_types() {
+        let cases = vec![
+            ("i32", "i32"),
+            ("boolean?", "bool?"),
+            ("any", "any"),
+            ("any1?", "any1?"),
+            ("T", "T"),
+            ("DECIMAL<P1,S1>", "decimal<P1, S1>"),
+            ("DECIMAL<38, S>", "decimal<38, S>"),
+            ("VARCHAR<L1>", "varchar<L1>"),
+            ("list<any1>", "list<any1>"),
+            ("map<string, T?>", "map<string, T?>"),
+            ("DECIMAL?<P, 2>", "decimal?<P, 2>"),
+            ("u!geometry", "u!geometry"),
+            ("LIST?<i32>", "list?<i32>"),
+            ("i32[dict]?", "i32?[dict]"),
+            ("VARCHAR[dict]<L1>", "varchar[dict]<L1>"),
+        ];
+        for (type_str, expected) in cases {
+            assert_eq!(signature(type_str).to_string(), expected, "{type_str}");
+        }
+
+        assert!(signature("DECIMAL<10, 2>").is_concrete());
+        assert!(!signature("DECIMAL<P, 2>").is_concrete());
+        assert_eq!(
+            signature("map<any1, list<T>>").type_variables(),
+            ["any1", "T"]
+        );
+    }
+
+    #[test]
+    fn parse_signature_type_errors() {
+        assert!(SignatureType::try_from(TypeExpr::parse("DECIMAL<39, 2>").unwrap()).is_err());
+        assert!(SignatureType::try_from(TypeExpr::parse("list<i32, i32>").unwrap()).is_err());
+        assert!(SignatureType::try_from(TypeExpr::parse("unknown<i32>").unwrap()).is_err());
+
+        // Misspelled types and unprefixed extension types are not variables
+        for name in ["strng", "point", "list<point>", "map<i32, point>"] {
+            assert!(
+                SignatureType::try_from(TypeExpr::parse(name).unwrap()).is_err(),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn unify_and_instantiate() {
+        let mut bindings = TypeBindings::default();
+        assert!(bindings.unify(&signature("DECIMAL<P1, S1>"), &concrete("DECIMAL?<10, 2>")));
+        assert!(bindings.unify(&signature("any1"), &concrete("i64?")));
+        assert!(bindings.unify(&signature("list<any1>"), &concrete("list<i64>")));
+        assert_eq!(bindings.get_integer("P1"), Some(10));
+        assert_eq!(bindings.get_type("any1"), Some(&concrete("i64")));
+
+        assert_eq!(
+            bindings.instantiate(&signature("DECIMAL?<38, S1>")),
+            Ok(concrete("DECIMAL?<38, 2>"))
+        );
+        assert_eq!(
+            bindings.instantiate(&signature("map<any1, VARCHAR<P1>>")),
+            Ok(concrete("map<i64, VARCHAR<10>>"))
+        );
+        assert_eq!(
+            bindings.instantiate(&signature("any2")),
+            Err(BindingError::UnboundVariable("any2".to_string()))
+        );
+    }
+
+    #[test]
+    fn unify_conflicts() {
+        let mut bindings = TypeBindings::default();
+        assert!(bindings.unify(&signature("any1"), &concrete("i32")));
+        assert!(!bindings.unify(&signature("any1"), &concrete("i64")));
+
+        let mut bindings = TypeBindings::default();
+        assert!(bindings.unify(&signature("DECIMAL<P, S>"), &concrete("DECIMAL<10, 2>")));
+        assert!(!bindings.unify(&signature("DECIMAL<P, S>"), &concrete("DECIMAL<12, 2>")));
+
+        let mut bindings = TypeBindings::default();
+        assert!(!bindings.unify(&signature("list<i32>"), &concrete("list<i32?>")));
+        assert!(bindings.unify(&signature("list<any1>"), &concrete("list<i32?>")));
+        assert!(!bindings.unify(&signature("i32"), &concrete("i64")));
+
+        // Signature types without a variation match all variations.
+        let mut bindings = TypeBindings::default();
+        assert!(bindings.unify(&signature("i32"), &concrete("i32[dict]")));
+        assert!(bindings.unify(&signature("i32[dict]"), &concrete("i32[dict]")));
+        assert!(!bindings.unify(&signature("i32[dict]"), &concrete("i32")));
+        assert!(bindings.unify(&signature("any1"), &concrete("i32?[dict]")));
+        assert_eq!(
+            bindings.instantiate(&signature("any1?")),
+            Ok(concrete("i32?[dict]"))
+        );
+    }
+
+    #[test]
+    fn unify_nested_nullability() {
+        let mut bindings = TypeBindings::default();
+        assert!(bindings.unify(&signature("list<any1>"), &concrete("list<i32?>")));
+        assert_eq!(bindings.get_type("any1"), Some(&concrete("i32?")));
+        assert_eq!(
+            bindings.instantiate(&signature("list<any1>")),
+            Ok(concrete("list<i32?>"))
+        );
+        assert_eq!(
+            bindings.instantiate(&signature("any1")),
+            Ok(concrete("i32"))
+        );
+        assert!(!bindings.unify(&signature("list<any1>"), &concrete("list<i32>")));
+
+        // Top-level bindings take the nullability of later nested bindings.
+        let mut bindings = TypeBindings::default();
+        assert!(bindings.unify(&signature("any1"), &concrete("i32")));
+        assert!(bindings.unify(&signature("list<any1>"), &concrete("list<i32?>")));
+        assert!(bindings.unify(&signature("any1"), &concrete("i32")));
+        assert_eq!(
+            bindings.instantiate(&signature("map<any1, any1>")),
+            Ok(concrete("map<i32?, i32?>"))
+        );
+    }
+
+    #[test]
+    fn unify_literal_parameters() {
+        let mut bindings = TypeBindings::default();
+        let pattern = signature("u!tagged<true, 'utf8', MODE_A>");
+        assert!(bindings.unify(&pattern, &concrete("u!tagged<true, 'utf8', MODE_A>")));
+        assert!(!bindings.unify(&pattern, &concrete("u!tagged<false, 'utf8', MODE_A>")));
+        assert!(!bindings.unify(&pattern, &concrete("u!tagged<true, 'utf16', MODE_A>")));
+        assert!(!bindings.unify(&pattern, &concrete("u!tagged<true, 'utf8', MODE_B>")));
+
+        // Bare names also bind type parameters.
+        let mut bindings = TypeBindings::default();
+        assert!(bindings.unify(&signature("u!box<T>"), &concrete("u!box<i32?>")));
+        assert_eq!(
+            bindings.instantiate(&signature("list<T?>")),
+            Ok(concrete("list<i32?>"))
+        );
+        assert_eq!(
+            bindings.instantiate(&signature("u!box<T, null>")),
+            Ok(concrete("u!box<i32?, null>"))
+        );
+
+        // Only variable names bind types.
+        let mut bindings = TypeBindings::default();
+        assert!(!bindings.unify(&signature("u!box<mode>"), &concrete("u!box<i32>")));
+
+        // Enumeration options spelled as builtin types match these types.
+        let option = |option: &str| {
+            ConcreteType::extension_with_params(
+                "unit".to_string(),
+                vec![TypeParameter::Enum(option.to_string())],
+                false,
+            )
+        };
+        assert!(bindings.unify(&signature("u!unit<DATE>"), &option("DATE")));
+        assert!(!bindings.unify(&signature("u!unit<DATE>"), &option("TIME")));
+    }
+}
@@ -5,6 +5,8 @@
 //! This module provides syntactic parsing of Substrait type strings into an AST.
 //! It does NOT validate that type names are valid builtins or that extension types exist -
 //! semantic validation occurs later when converting to [`ConcreteType`](super::types::ConcreteType).
+//! Only bare identifiers in parameter lists are classified by name, as either
+//! types or enumeration values.
 //!
 //! This module is based on the official substrait type grammar defined
 //! [here](https://github.com/substrait-io/substrait/blob/5f031b69ed211e1ec307be3db7989d64c65d33a2/grammar/SubstraitType.g4).
@@ -20,6 +22,8 @@
 //
 // Therefore, the grammar is manually implemented.
 
+use super::types::BasicBuiltinType;
+
 /// A parsed type expression from a type string, with lifetime tied to the original string.
 ///
 /// This represents the syntactic structure only - type names are not validated.
@@ -28,16 +32,21 @@
 pub enum TypeExpr<'a> {
     /// A type without the `u!` prefix (expected to be a builtin like `i32`, `List`, etc.)
     ///
-    /// Contains: (name, parameters, nullable)
-    Simple(&'a str, Vec<TypeExprParam<'a>>, bool),
+    /// Contains: (name, parameters, nullable, type variation)
+    Simple(&'a str, Vec<TypeExprParam<'a>>, bool, Option<&'a str>),
     /// A user-defined extension type with the `u!` prefix (e.g., `u!geometry`)
     ///
-    /// Contains: (name without `u!` prefix, parameters, nullable)
-    UserDefined(&'a str, Vec<TypeExprParam<'a>>, bool),
+    /// Contains: (name without `u!` prefix, parameters, nullable, type variation)
+    UserDefined(&'a str, Vec<TypeExprParam<'a>>, bool, Option<&'a str>),
     /// Type variable (e.g., `any1`, `any2`)
     ///
     /// Contains: (variable id, nullable)
     TypeVariable(u32, bool),
+    /// A function type, the type of lambda arguments (e.g. `func<any1 -> any2>`
+    /// or `func<(any1, any2) -> any1>`)
+    ///
+    /// Contains: (argument types, result type, nullable)
+    Function(Vec<TypeExpr<'a>>, Box<TypeExpr<'a>>, bool),
 }
 
 /// A parsed parameter to a parameterized type
@@ -47,18 +56,41 @@ pub enum TypeExprParam<'a> {
     Type(TypeExpr<'a>),
     /// An integer literal parameter
     Integer(i64),
+    /// A boolean literal parameter (`true` or `false`)
+    Boolean(bool),
+    /// A string literal parameter, in single or double quotes (e.g. `'utf8'`)
+    ///
+    /// Contains the string without quotes.
+    String(&'a str),
+    /// An enumeration parameter: a bare identifier that does not name a
+    /// built-in type (e.g. `MODE_A`).
+    ///
+    /// In function signatures, these also name parameter variables (e.g. the
+    /// `P` in `DECIMAL<P, S>`).
+    Enum(&'a str),
+    /// A null parameter (`null`), for an omitted optional parameter
+    Null,
 }
 
 #[derive(Debug, PartialEq, thiserror::Error)]
 pub enum TypeParseError {
     #[error("missing closing angle bracket in parameter list: {0}")]
     ExpectedClosingAngleBracket(String),
-    #[error("Type variation syntax is not supported: {0}")]
-    UnsupportedVariation(String),
+    #[error("invalid type variation syntax: {0}")]
+    InvalidVariation(String),
+    #[error("unterminated string literal in parameter list: {0}")]
+    UnterminatedString(String),
+    #[error("invalid function type syntax: {0}")]
+    InvalidFunctionType(String),
 }
 
 impl<'a> TypeExpr<'a> {
     /// Parse a type string into a [`TypeExpr`].
+    ///
+    /// A type variation is written in square brackets after the type name and
+    /// nullability marker, before any parameters (e.g. `i32?[dict_encoded]`
+    /// or `VARCHAR[dict_encoded]<10>`); `i32[dict_encoded]?` is accepted as
+    /// well.
     pub fn parse(type_str: &'a str) -> Result<Self, TypeParseError> {
         // Handle type variables like any1, any2, etc.
         if let Some(suffix) = type_str.strip_prefix("any") {
@@ -77,28 +109,57 @@ impl<'a> TypeExpr<'a> {
             None => (false, type_str),
         };
 
-        let (name_and_nullable, params): (&'a str, Vec<TypeExprParam<'a>>) =
-            match rest.split_once('<') {
-                Some((n, p)) => match p.strip_suffix('>') {
-                    Some(p) => (n, parse_params(p)?),
-                    None => return Err(TypeParseError::ExpectedClosingAngleBracket(p.to_string())),
-                },
-                None => (rest, vec![]),
-            };
-
-        if name_and_nullable.contains('[') || name_and_nullable.contains(']') {
-            return Err(TypeParseError::UnsupportedVariation(type_str.to_string()));
-        }
+        let (name_and_suffix, params): (&'a str, Option<&'a str>) = match rest.split_once('<') {
+            Some((n, p)) => match p.strip_suffix('>') {
+                Some(p) => (n, Some(p)),
+                None => return Err(TypeParseError::ExpectedClosingAngleBracket(p.to_string())),
+            },
+            None => (rest, None),
+        };
 
-        let (name, nullable) = match name_and_nullable.strip_suffix('?') {
+        let strip_nullable = |name: &'a str| match name.strip_suffix('?') {
             Some(name) => (name, true),
-            None => (name_and_nullable, false),
+            None => (name, false),
+        };
+        let invalid_variation = || TypeParseError::InvalidVariation(type_str.to_string());
+
+        let (name, nullable, variation) = match name_and_suffix.split_once('[') {
+            Some((name_and_nullable, rest)) => {
+                let (variation, after) = rest.split_once(']').ok_or_else(invalid_variation)?;
+                if variation.is_empty() || variation.contains(['[', ']']) {
+                    return Err(invalid_variation());
+                }
+                let (name, nullable) = strip_nullable(name_and_nullable);
+                // The nullability marker may also follow the variation.
+                let nullable = match after {
+                    "" => nullable,
+                    "?" if !nullable => true,
+                    _ => return Err(invalid_variation()),
+                };
+                (name, nullable, Some(variation))
+            }
+            None if name_and_suffix.contains(']') => return Err(invalid_variation()),
+            None => {
+                let (name, nullable) = strip_nullable(name_and_suffix);
+                (name, nullable, None)
+            }
         };
 
+        match params {
+            Some(params) if !user_defined && name.eq_ignore_ascii_case("func") => {
+                if variation.is_some() {
+                    return Err(invalid_variation());
+                }
+                return parse_function(params, nullable);
+            }
+            _ => {}
+        }
+        let params = params.map(parse_params).transpose()?.unwrap_or_default();
+
         if user_defined {
-            Ok(TypeExpr::UserDefined(name, params, nullable))
+            Ok(TypeExpr::UserDefined(name, params, nullable, variation))
         } else {
-            Ok(TypeExpr::Simple(name, params, nullable))
+            Ok(TypeExpr::Simple(name, params, nullable, variation))
         }
     }
 
@@ -109,7 +170,7 @@ impl<'a> TypeExpr<'a> {
         F: FnMut(&str),
     {
         match self {
-            TypeExpr::UserDefined(name, params, _) => {
+            TypeExpr::UserDefined(name, params, ..) => {
                 on_ext(name);
                 for p in params {
                     if let TypeExprParam::Type(t) = p {
@@ -117,7 +178,7 @@ impl<'a> TypeExpr<'a> {
                     }
                 }
             }
-            TypeExpr::Simple(_name, params, _) => {
+            TypeExpr::Simple(_name, params, ..) => {
                 for p in params {
                     if let TypeExprParam::Type(t) = p {
                         t.visit_references(on_ext);
@@ -125,19 +186,73 @@ impl<'a> TypeExpr<'a> {
                 }
             }
             TypeExpr::TypeVariable(..) => {}
+            TypeExpr::Function(arguments, result, _) => {
+                for argument in arguments {
+                    argument.visit_references(on_ext);
+                }
+                result.visit_references(on_ext);
+            }
+        }
+    }
+}
+
+/// Parse the parameters of a function type: the argument types, optionally in
+/// parentheses, and the result type, separated by `->` (e.g. `any1 -> any2`).
+fn parse_function<'a>(s: &'a str, nullable: bool) -> Result<TypeExpr<'a>, TypeParseError> {
+    let invalid = || TypeParseError::InvalidFunctionType(s.to_string());
+    let arrow = top_level_arrow(s).ok_or_else(invalid)?;
+    let (arguments, result) = (s[..arrow].trim(), s[arrow + 2..].trim());
+    let arguments = arguments
+        .strip_prefix('(')
+        .and_then(|arguments| arguments.strip_suffix(')'))
+        .unwrap_or(arguments);
+
+    let arguments = parse_params(arguments)?
+        .into_iter()
+        .map(|param| match param {
+            TypeExprParam::Type(t) => Ok(t),
+            // Bare names of arguments are type variables (e.g. `T`).
+            TypeExprParam::Enum(name) => Ok(TypeExpr::Simple(name, vec![], false, None)),
+            _ => Err(invalid()),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if arguments.is_empty() || result.is_empty() {
+        return Err(invalid());
+    }
+    let result = TypeExpr::parse(result)?;
+
+    Ok(TypeExpr::Function(arguments, Box::new(result), nullable))
+}
+
+/// Returns the position of the first `->` of `s` outside of parameter lists.
+fn top_level_arrow(s: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '-' if s[i + 1..].starts_with('>') && depth == 0 => return Some(i),
+            '<' => depth += 1,
+            '>' if !s[..i].ends_with('-') => depth -= 1,
+            _ => {}
         }
     }
+    None
 }
 
 fn parse_params<'a>(s: &'a str) -> Result<Vec<TypeExprParam<'a>>, TypeParseError> {
     let mut result = Vec::new();
     let mut start = 0;
     let mut depth = 0;
+    let mut quote = None;
 
     for (i, c) in s.char_indices() {
         match c {
+            // Delimiters within string literals are ignored.
+            '\'' | '"' if quote.is_none() => quote = Some(c),
+            _ if quote == Some(c) => quote = None,
+            _ if quote.is_some() => {}
             '<' => depth += 1,
-            '>' => depth -= 1,
+            // The `>` of a function type arrow (`->`) is not a delimiter.
+            '>' if !s[..i].ends_with('-') => depth -= 1,
             ',' if depth == 0 => {
                 result.push(parse_param(s[start..i].trim())?);
                 start = i + 1;
@@ -149,6 +264,9 @@ fn parse_params<'a>(s: &'a str) -> Result<Vec<TypeExprParam<'a>>, TypeParseError
     if depth != 0 {
         return Err(TypeParseError::ExpectedClosingAngleBracket(s.to_string()));
     }
+    if quote.is_some() {
+        return Err(TypeParseError::UnterminatedString(s.to_string()));
+    }
 
     if start < s.len() {
         result.push(parse_param(s[start..].trim())?);
@@ -161,7 +279,35 @@ fn parse_param<'a>(s: &'a str) -> Result<TypeExprParam<'a>, TypeParseError> {
     if let Ok(i) = s.parse::<i64>() {
         return Ok(TypeExprParam::Integer(i));
     }
-    Ok(TypeExprParam::Type(TypeExpr::parse(s)?))
+    match s {
+        "true" => return Ok(TypeExprParam::Boolean(true)),
+        "false" => return Ok(TypeExprParam::Boolean(false)),
+        "null" => return Ok(TypeExprParam::Null),
+        _ => {}
+    }
+    if let Some(string) = ['\'', '"']
+        .into_iter()
+        .find_map(|quote| s.strip_prefix(quote)?.strip_suffix(quote))
+    {
+        return Ok(TypeExprParam::String(string));
+    }
+
+    match TypeExpr::parse(s)? {
+        TypeExpr::Simple(name, params, false, None) if params.is_empty() && !is_type_name(name) => {
+            Ok(TypeExprParam::Enum(name))
+        }
+        t => Ok(TypeExprParam::Type(t)),
+    }
+}
+
+/// Returns whether a bare name (without parameters) names a type rather than
+/// an enumeration value.
+fn is_type_name(name: &str) -> bool {
+    BasicBuiltinType::is_name(name)
+        || matches!(
+            name.to_ascii_lowercase().as_str(),
+            "any" | "list" | "map" | "struct"
+        )
 }
 
 #[cfg(test)]
@@ -175,16 +321,19 @@ mod tests {
     #[test]
     fn test_simple_types() {
         let cases = vec![
-            ("i32", TypeExpr::Simple("i32", vec![], false)),
-            ("i32?", TypeExpr::Simple("i32", vec![], true)),
-            ("MAP", TypeExpr::Simple("MAP", vec![], false)),
-            ("timestamp", TypeExpr::Simple("timestamp", vec![], false)),
+            ("i32", TypeExpr::Simple("i32", vec![], false, None)),
+            ("i32?", TypeExpr::Simple("i32", vec![], true, None)),
+            ("MAP", TypeExpr::Simple("MAP", vec![], false, None)),
+            (
+                "timestamp",
+                TypeExpr::Simple("timestamp", vec![], false, None),
+            ),
             (
                 "timestamp_tz?",
-                TypeExpr::Simple("timestamp_tz", vec![], true),
+                TypeExpr::Simple("timestamp_tz", vec![], true, None),
             ),
-            ("time", TypeExpr::Simple("time", vec![], false)),
-            ("any", TypeExpr::Simple("any", vec![], false)),
+            ("time", TypeExpr::Simple("time", vec![], false, None)),
+            ("any", TypeExpr::Simple("any", vec![], false, None)),
         ];
 
         for (expr, expected) in cases {
@@ -216,17 +365,19 @@ mod tests {
                 TypeExpr::UserDefined(
                     "geo",
                     vec![
-                        TypeExprParam::Type(TypeExpr::Simple("i32", vec![], true)),
+                        TypeExprParam::Type(TypeExpr::Simple("i32", vec![], true, None)),
                         TypeExprParam::Type(TypeExpr::Simple(
                             "point",
                             vec![
-                                TypeExprParam::Type(TypeExpr::Simple("i32", vec![], false)),
-                                TypeExprParam::Type(TypeExpr::Simple("i32", vec![], false)),
+                                TypeExprParam::Type(TypeExpr::Simple("i32", vec![], false, None)),
+                                TypeExprParam::Type(TypeExpr::Simple("i32", vec![], false, None)),
                             ],
                             false,
+                            None,
                         )),
                     ],
                     true,
+                    None,
                 ),
             ),
             (
@@ -234,10 +385,11 @@ mod tests {
                 TypeExpr::Simple(
                     "Map",
                     vec![
-                        TypeExprParam::Type(TypeExpr::Simple("i32", vec![], false)),
-                        TypeExprParam::Type(TypeExpr::Simple("string", vec![], false)),
+                        TypeExprParam::Type(TypeExpr::Simple("i32", vec![], false, None)),
+                        TypeExprParam::Type(TypeExpr::Simple("string", vec![], false, None)),
                     ],
                     true,
+                    None,
                 ),
             ),
         ];
@@ -246,6 +398,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_literal_parameters() {
+        assert_eq!(
+            parse("u!mytype<true, 'utf8', MODE_A, null, \"a, b<c>\", i32>"),
+            TypeExpr::UserDefined(
+                "mytype",
+                vec![
+                    TypeExprParam::Boolean(true),
+                    TypeExprParam::String("utf8"),
+                    TypeExprParam::Enum("MODE_A"),
+                    TypeExprParam::Null,
+                    TypeExprParam::String("a, b<c>"),
+                    TypeExprParam::Type(TypeExpr::Simple("i32", vec![], false, None)),
+                ],
+                false,
+                None,
+            )
+        );
+
+        assert_eq!(
+            TypeExpr::parse("u!mytype<'utf8>"),
+            Err(TypeParseError::UnterminatedString("'utf8".to_string()))
+        );
+    }
+
     #[test]
     fn test_visit_references_builtin_case_insensitive() {
         let cases = vec![
@@ -263,14 +440,99 @@ mod tests {
     }
 
     #[test]
-    fn test_variation_not_supported() {
-        let cases = vec!["i32[1]", "Foo?[1]", "u!bar[2]"];
+    fn test_variations() {
+        let cases = vec![
+            (
+                "i32[dict_encoded]",
+                TypeExpr::Simple("i32", vec![], false, Some("dict_encoded")),
+            ),
+            ("Foo?[1]", TypeExpr::Simple("Foo", vec![], true, Some("1"))),
+            ("i32[1]?", TypeExpr::Simple("i32", vec![], true, Some("1"))),
+            (
+                "u!bar[2]",
+                TypeExpr::UserDefined("bar", vec![], false, Some("2")),
+            ),
+            (
+                "VARCHAR?[dict]<10>",
+                TypeExpr::Simple(
+                    "VARCHAR",
+                    vec![TypeExprParam::Integer(10)],
+                    true,
+                    Some("dict"),
+                ),
+            ),
+        ];
+
+        for (expr, expected) in cases {
+            assert_eq!(parse(expr), expected, "unexpected parse for {expr}");
+        }
+    }
+
+    #[test]
+    fn test_invalid_variations() {
+        let cases = vec!["i32[]", "i32[dict", "i32]", "i32?[dict]?", "i32[dict]x"];
 
         for expr in cases {
             match TypeExpr::parse(expr) {
-                Err(TypeParseError::UnsupportedVariation(s)) => assert_eq!(s, expr),
-                other => panic!("expected UnsupportedVariation for {expr}, got {other:?}"),
+                Err(TypeParseError::InvalidVariation(s)) => assert_eq!(s, expr),
+                other => panic!("expected InvalidVariation for {expr}, got {other:?}"),
             }
         }
     }
+
+    #[test]
+    fn test_function_types() {
+        let any = |id| TypeExpr::TypeVariable(id, false);
+        let cases = [
+            (
+                "func<any1 -> any2>",
+                TypeExpr::Function(vec![any(1)], Box::new(any(2)), false),
+            ),
+            (
+                "FUNC?<any1, any1 -> any1>",
+                TypeExpr::Function(vec![any(1), any(1)], Box::new(any(1)), true),
+            ),
+            (
+                "func<(any1, i32) -> list<any1>>",
+                TypeExpr::Function(
+                    vec![any(1), TypeExpr::Simple("i32", vec![], false, None)],
+                    Box::new(TypeExpr::Simple(
+                        "list",
+                        vec![TypeExprParam::Type(any(1))],
+                        false,
+                        None,
+                    )),
+                    false,
+                ),
+            ),
+        ];
+        for (expr, expected) in cases {
+            assert_eq!(parse(expr), expected, "unexpected parse for {expr}");
+        }
+
+        // Function types nest in parameter lists.
+        assert_eq!(
+            parse("list<func<any1 -> any2>>"),
+            TypeExpr::Simple(
+                "list",
+                vec![TypeExprParam::Type(TypeExpr::Function(
+                    vec![any(1)],
+                    Box::new(any(2)),
+                    false
+                ))],
+                false,
+                None,
+            )
+        );
+
+        for expr in ["func<any1>", "func< -> any1>", "func<any1 -> >"] {
+            assert!(
+                matches!(
+                    TypeExpr::parse(expr),
+                    Err(TypeParseError::InvalidFunctionType(_))
+                ),
+                "expected InvalidFunctionType for {expr}"
+            );
+        }
+    }
 }
@@ -0,0 +1,172 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Type variation definitions: [`TypeVariation`].
+//!
+//! A type variation is a different physical representation of a parent type,
+//! e.g. a dictionary-encoded `i32`. Types refer to variations by name, in
+//! square brackets (e.g. `i32[dict_encoded]`).
+
+use super::extensions::TypeContext;
+use super::types::{ConcreteType, CustomType, ExtensionTypeError};
+use crate::parse::Parse;
+use crate::text::simple_extensions::{
+    SimpleExtensionsTypeVariationsItem as RawTypeVariation,
+    SimpleExtensionsTypeVariationsItemFunctions as RawVariationFunctions,
+};
+
+/// A validated type variation definition
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypeVariation {
+    /// Variation name
+    pub name: String,
+    /// The type this is a variation of
+    pub parent: ConcreteType,
+    /// Whether functions of the parent type apply to this variation.
+    ///
+    /// Defaults to [`VariationFunctions::Inherits`] per the Substrait spec.
+    pub functions: VariationFunctions,
+    /// Human-readable description
+    pub description: Option<String>,
+    /// The `metadata` of the type variation declaration, not interpreted by
+    /// this crate.
+    pub metadata: serde_json::Map<String, serde_json::Value>,
+}
+
+/// How functions of the parent type apply to a [`TypeVariation`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VariationFunctions {
+    /// Functions that accept the parent type also accept the variation
+    Inherits,
+    /// Only functions that explicitly declare the variation accept it
+    Separate,
+}
+
+impl From<RawVariationFunctions> for VariationFunctions {
+    fn from(raw: RawVariationFunctions) -> Self {
+        match raw {
+            RawVariationFunctions::Inherits => VariationFunctions::Inherits,
+            RawVariationFunctions::Separate => VariationFunctions::Separate,
+        }
+    }
+}
+
+impl From<VariationFunctions> for RawVariationFunctions {
+    fn from(functions: VariationFunctions) -> Self {
+        match functions {
+            VariationFunctions::Inherits => RawVariationFunctions::Inherits,
+            VariationFunctions::Separate => RawVariationFunctions::Separate,
+        }
+    }
+}
+
+impl Parse<TypeContext> for RawTypeVariation {
+    type Parsed = TypeVariation;
+    type Error = ExtensionTypeError;
+
+    fn parse(self, ctx: &mut TypeContext) -> Result<Self::Parsed, Self::Error> {
+        let RawTypeVariation {
+            description,
+            functions,
+            metadata,
+            name,
+            parent,
+        } = self;
+        CustomType::validate_name(&name)?;
+
+        // Parse the parent with context, so referenced extension types are
+        // recorded as linked
+        let parent = Parse::parse(parent, ctx)?;
+
+        Ok(TypeVariation {
+            name,
+            parent,
+            functions: functions
+                .map(Into::into)
+                .unwrap_or(VariationFunctions::Inherits),
+            description,
+            metadata,
+        })
+    }
+}
+
+impl From<TypeVariation> for RawTypeVariation {
+    fn from(variation: TypeVariation) -> Self {
+        RawTypeVariation {
+            name: variation.name,
+            parent: variation.parent.into(),
+            functions: (variation.functions != VariationFunctions::Inherits)
+                .then(|| variation.functions.into()),
+            description: variation.description,
+            metadata: variation.metadata,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::text::simple_extensions::{
+        ExtensionFile, SimpleExtensionsError, types::BasicBuiltinType,
+    };
+
+    const YAML: &str = r#"
+%YAML 1.2
+---
+urn: extension:example.com:variations
+types:
+  - name: point
+    structure:
+      x: i32
+      y: i32
+type_variations:
+  - name: dict_encoded
+    parent: i32
+    description: Dictionary-encoded integers.
+  - name: run_length
+    parent: u!point
+    functions: SEPARATE
+"#;
+
+    #[test]
+    fn parse_type_variations() {
+        let file = ExtensionFile::read_from_str(YAML).unwrap();
+
+        let dict = file.extension().get_type_variation("dict_encoded").unwrap();
+        assert_eq!(
+            dict.parent,
+            ConcreteType::builtin(BasicBuiltinType::I32, false)
+        );
+        assert_eq!(dict.functions, VariationFunctions::Inherits);
+
+        let run_length = file.extension().get_type_variation("run_length").unwrap();
+        assert_eq!(
+            run_length.parent,
+            ConcreteType::extension("point".into(), false)
+        );
+        assert_eq!(run_length.functions, VariationFunctions::Separate);
+    }
+
+    #[test]
+    fn round_trip() {
+        let file = ExtensionFile::read_from_str(YAML.replace("u!point", "i64")).unwrap();
+        let raw = file.to_raw();
+        assert_eq!(raw.type_variations.len(), 2);
+
+        let parsed = ExtensionFile::create(raw).unwrap();
+        for name in ["dict_encoded", "run_length"] {
+            assert_eq!(
+                parsed.extension().get_type_variation(name),
+                file.extension().get_type_variation(name)
+            );
+        }
+    }
+
+    #[test]
+    fn unresolved_parent() {
+        let yaml = YAML.replace("parent: u!point", "parent: u!line");
+        assert!(matches!(
+            ExtensionFile::read_from_str(yaml),
+            Err(SimpleExtensionsError::UnresolvedTypeReference { type_name, .. }) if type_name == "line"
+        ));
+    }
+}
@@ -10,6 +10,7 @@ use super::argument::{
     EnumOptions as ParsedEnumOptions, EnumOptionsError as ParsedEnumOptionsError,
 };
 use super::extensions::TypeContext;
+use super::signature::SignatureType;
 use super::type_ast::TypeExprParam;
 use crate::parse::Parse;
 use crate::parse::text::simple_extensions::type_ast::TypeParseError;
@@ -17,6 +18,7 @@ use crate::text::simple_extensions::{
     EnumOptions as RawEnumOptions, SimpleExtensionsTypesItem, Type as RawType, TypeParamDefs,
     TypeParamDefsItem, TypeParamDefsItemType,
 };
+use crate::urn::Urn;
 use indexmap::IndexMap;
 use serde_json::{Map, Value};
 use std::convert::TryFrom;
@@ -24,11 +26,17 @@ use std::fmt;
 use std::ops::RangeInclusive;
 use thiserror::Error;
 
+/// Returns the name of an extension type without the alias of a dependency,
+/// e.g. `point` for `geo.point`.
+pub(super) fn unaliased(name: &str) -> &str {
+    name.split_once('.').map_or(name, |(_, name)| name)
+}
+
 /// Write a sequence of items separated by a separator, with a start and end
 /// delimiter.
 ///
 /// Start and end are only included in the output if there is at least one item.
-fn write_separated<I, T>(
+pub(super) fn write_separated<I, T>(
     f: &mut fmt::Formatter<'_>,
     iter: I,
     start: &str,
@@ -127,12 +135,12 @@ pub enum BasicBuiltinType {
         /// Sub-second precision digits (0-12: seconds to picoseconds)
         precision: i32,
     },
-    /// Timestamp with sub-second precision: `PRECISIONTIMESTAMP<P>`
+    /// Timestamp with sub-second precision: `PRECISION_TIMESTAMP<P>`
     PrecisionTimestamp {
         /// Sub-second precision digits (0-12: seconds to picoseconds)
         precision: i32,
     },
-    /// Timezone-aware timestamp with precision: `PRECISIONTIMESTAMPTZ<P>`
+    /// Timezone-aware timestamp with precision: `PRECISION_TIMESTAMP_TZ<P>`
     PrecisionTimestampTz {
         /// Sub-second precision digits (0-12: seconds to picoseconds)
         precision: i32,
@@ -170,47 +178,54 @@ impl BasicBuiltinType {
     }
 }
 
-impl fmt::Display for BasicBuiltinType {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            BasicBuiltinType::Boolean => f.write_str("bool"),
-            BasicBuiltinType::I8 => f.write_str("i8"),
-            BasicBuiltinType::I16 => f.write_str("i16"),
-            BasicBuiltinType::I32 => f.write_str("i32"),
-            BasicBuiltinType::I64 => f.write_str("i64"),
-            BasicBuiltinType::Fp32 => f.write_str("fp32"),
-            BasicBuiltinType::Fp64 => f.write_str("fp64"),
-            BasicBuiltinType::String => f.write_str("string"),
-            BasicBuiltinType::Binary => f.write_str("binary"),
-            BasicBuiltinType::Timestamp => f.write_str("timestamp"),
-            BasicBuiltinType::TimestampTz => f.write_str("timestamp_tz"),
-            BasicBuiltinType::Date => f.write_str("date"),
-            BasicBuiltinType::Time => f.write_str("time"),
-            BasicBuiltinType::IntervalYear => f.write_str("interval_year"),
-            BasicBuiltinType::Uuid => f.write_str("uuid"),
-            BasicBuiltinType::FixedChar { length } => write!(f, "FIXEDCHAR<{length}>"),
-            BasicBuiltinType::VarChar { length } => write!(f, "VARCHAR<{length}>"),
-            BasicBuiltinType::FixedBinary { length } => write!(f, "FIXEDBINARY<{length}>"),
-            BasicBuiltinType::Decimal { precision, scale } => {
-                write!(f, "DECIMAL<{precision}, {scale}>")
-            }
-            BasicBuiltinType::PrecisionTime { precision } => {
-                write!(f, "PRECISIONTIME<{precision}>")
-            }
+impl BasicBuiltinType {
+    /// Returns the name of the type, as displayed, and its parameters.
+    fn display_parts(&self) -> (&'static str, Vec<i32>) {
+        match *self {
+            BasicBuiltinType::Boolean => ("bool", vec![]),
+            BasicBuiltinType::I8 => ("i8", vec![]),
+            BasicBuiltinType::I16 => ("i16", vec![]),
+            BasicBuiltinType::I32 => ("i32", vec![]),
+            BasicBuiltinType::I64 => ("i64", vec![]),
+            BasicBuiltinType::Fp32 => ("fp32", vec![]),
+            BasicBuiltinType::Fp64 => ("fp64", vec![]),
+            BasicBuiltinType::String => ("string", vec![]),
+            BasicBuiltinType::Binary => ("binary", vec![]),
+            BasicBuiltinType::Timestamp => ("timestamp", vec![]),
+            BasicBuiltinType::TimestampTz => ("timestamp_tz", vec![]),
+            BasicBuiltinType::Date => ("date", vec![]),
+            BasicBuiltinType::Time => ("time", vec![]),
+            BasicBuiltinType::IntervalYear => ("interval_year", vec![]),
+            BasicBuiltinType::Uuid => ("uuid", vec![]),
+            BasicBuiltinType::FixedChar { length } => ("FIXEDCHAR", vec![length]),
+            BasicBuiltinType::VarChar { length } => ("VARCHAR", vec![length]),
+            BasicBuiltinType::FixedBinary { length } => ("FIXEDBINARY", vec![length]),
+            BasicBuiltinType::Decimal { precision, scale } => ("DECIMAL", vec![precision, scale]),
+            BasicBuiltinType::PrecisionTime { precision } => ("PRECISIONTIME", vec![precision]),
             BasicBuiltinType::PrecisionTimestamp { precision } => {
-                write!(f, "PRECISIONTIMESTAMP<{precision}>")
+                ("PRECISION_TIMESTAMP", vec![precision])
             }
             BasicBuiltinType::PrecisionTimestampTz { precision } => {
-                write!(f, "PRECISIONTIMESTAMPTZ<{precision}>")
+                ("PRECISION_TIMESTAMP_TZ", vec![precision])
             }
-            BasicBuiltinType::IntervalDay { precision } => write!(f, "INTERVAL_DAY<{precision}>"),
+            BasicBuiltinType::IntervalDay { precision } => ("INTERVAL_DAY", vec![precision]),
             BasicBuiltinType::IntervalCompound { precision } => {
-                write!(f, "INTERVAL_COMPOUND<{precision}>")
+                ("INTERVAL_COMPOUND", vec![precision])
             }
         }
     }
 }
 
+/// Builtin types are displayed with the type names of the specification, e.g.
+/// `PRECISION_TIMESTAMP<6>` and `PRECISION_TIMESTAMP_TZ<6>`.
+impl fmt::Display for BasicBuiltinType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, parameters) = self.display_parts();
+        f.write_str(name)?;
+        write_separated(f, parameters, "<", ">", ", ")
+    }
+}
+
 /// A parameter, used in parameterized types
 #[derive(Clone, Debug, PartialEq)]
 pub enum TypeParameter {
@@ -218,8 +233,29 @@ pub enum TypeParameter {
     Integer(i64),
     /// Type parameter (nested type)
     Type(ConcreteType),
-    // TODO: Add support for other type parameters, as described in
-    // https://github.com/substrait-io/substrait/blob/35101020d961eda48f8dd1aafbc794c9e5cac077/proto/substrait/type.proto#L250-L265
+    /// Boolean parameter
+    Boolean(bool),
+    /// String parameter
+    String(String),
+    /// Enumeration parameter (one of the options of the parameter definition)
+    Enum(String),
+    /// Null parameter, for an omitted optional parameter
+    Null,
+}
+
+impl TypeParameter {
+    /// The parameter as a JSON value, as checked by
+    /// `TypeParam::is_valid_value`. Types are represented by their type
+    /// string.
+    pub fn to_value(&self) -> Value {
+        match self {
+            TypeParameter::Integer(i) => Value::from(*i),
+            TypeParameter::Type(t) => Value::String(t.to_string()),
+            TypeParameter::Boolean(b) => Value::Bool(*b),
+            TypeParameter::String(s) | TypeParameter::Enum(s) => Value::String(s.clone()),
+            TypeParameter::Null => Value::Null,
+        }
+    }
 }
 
 impl fmt::Display for TypeParameter {
@@ -227,10 +263,24 @@ impl fmt::Display for TypeParameter {
         match self {
             TypeParameter::Integer(i) => write!(f, "{i}"),
             TypeParameter::Type(t) => write!(f, "{t}"),
+            TypeParameter::Boolean(b) => write!(f, "{b}"),
+            TypeParameter::String(s) => write!(f, "'{s}'"),
+            TypeParameter::Enum(e) => f.write_str(e),
+            TypeParameter::Null => f.write_str("null"),
         }
     }
 }
 
+/// Returns the builtin type an enumeration option is spelled as, if any (e.g.
+/// `DATE`).
+///
+/// Bare names of builtin types in parameter lists are parsed as types, so a
+/// type parameter may stand for such an option.
+pub(super) fn option_type(option: &str) -> Option<ConcreteType> {
+    primitive_builtin(&option.to_ascii_lowercase())
+        .map(|builtin| ConcreteType::builtin(builtin, false))
+}
+
 /// Parse a primitive (no type parameters) builtin type name
 fn primitive_builtin(lower_name: &str) -> Option<BasicBuiltinType> {
     match lower_name {
@@ -370,21 +420,28 @@ pub struct TypeParam {
     pub param_type: ParameterConstraint,
     /// Human-readable description
     pub description: Option<String>,
+    /// Whether the parameter may be omitted, by passing `null`
+    pub optional: bool,
 }
 
 impl TypeParam {
-    /// Create a new type parameter
+    /// Create a new (required) type parameter
     pub fn new(name: String, param_type: ParameterConstraint, description: Option<String>) -> Self {
         Self {
             name,
             param_type,
             description,
+            optional: false,
         }
     }
 
-    /// Check if a parameter value is valid
+    /// Check if a parameter value is valid. `null` is only valid for optional
+    /// parameters.
     pub fn is_valid_value(&self, value: &Value) -> bool {
-        self.param_type.is_valid_value(value)
+        match value {
+            Value::Null => self.optional,
+            value => self.param_type.is_valid_value(value),
+        }
     }
 }
 
@@ -400,6 +457,7 @@ impl TryFrom<TypeParamDefsItem> for TypeParam {
             name,
             param_type,
             description: item.description,
+            optional: item.optional.unwrap_or(false),
         })
     }
 }
@@ -484,12 +542,26 @@ pub enum ExtensionTypeError {
         /// Description of the expected range or type
         expected: RangeInclusive<i32>,
     },
+    /// Type parameter does not satisfy the parameter definition of an
+    /// extension type
+    #[error("Type '{type_name}' parameter '{parameter_name}' does not accept {value}")]
+    InvalidParameterForDefinition {
+        /// The extension type name
+        type_name: String,
+        /// The name of the parameter definition
+        parameter_name: String,
+        /// The provided parameter
+        value: String,
+    },
     /// Structure representation cannot be nullable
     #[error("Structure representation cannot be nullable: {type_string}")]
     StructureCannotBeNullable {
         /// The type string that was nullable
         type_string: String,
     },
+    /// Function types (e.g. `func<any1 -> any2>`) are not supported yet
+    #[error("Function types (`func<...>`) are not yet supported")]
+    FunctionType,
     /// Error parsing type
     #[error("Error parsing type: {0}")]
     ParseType(#[from] TypeParseError),
@@ -530,6 +602,9 @@ pub struct CustomType {
     pub variadic: Option<bool>,
     /// Human-readable description
     pub description: Option<String>,
+    /// The `metadata` of the type declaration, kept to write the type back,
+    /// see [`ExtensionFile::to_raw`](super::ExtensionFile::to_raw).
+    pub metadata: serde_json::Map<String, serde_json::Value>,
 }
 
 impl CustomType {
@@ -556,6 +631,69 @@ impl CustomType {
         Ok(())
     }
 
+    /// Check the parameters of a reference to this type (e.g. the `10` in
+    /// `u!mytype<10>`) against the parameter definitions of this type.
+    ///
+    /// Optional parameters may be omitted at the end, and the last parameter
+    /// definition of a variadic type may be repeated.
+    pub fn validate_parameters(
+        &self,
+        parameters: &[TypeParameter],
+    ) -> Result<(), ExtensionTypeError> {
+        let required = self
+            .parameters
+            .iter()
+            .rposition(|param| !param.optional)
+            .map_or(0, |index| index + 1);
+        let variadic = self.variadic == Some(true) && !self.parameters.is_empty();
+        if parameters.len() < required || (!variadic && parameters.len() > self.parameters.len()) {
+            return Err(ExtensionTypeError::InvalidParameterCount {
+                type_name: self.name.clone(),
+                expected: if parameters.len() < required {
+                    required
+                } else {
+                    self.parameters.len()
+                },
+                actual: parameters.len(),
+            });
+        }
+
+        for (index, parameter) in parameters.iter().enumerate() {
+            let definition = &self.parameters[index.min(self.parameters.len() - 1)];
+            let option = match (&definition.param_type, parameter) {
+                (ParameterConstraint::Enumeration { options }, TypeParameter::Type(t)) => options
+                    .iter()
+                    .find(|option| option_type(option).as_ref() == Some(t))
+                    .map(|option| TypeParameter::Enum(option.clone())),
+                _ => None,
+            };
+            let parameter = option.as_ref().unwrap_or(parameter);
+            let kind_matches = matches!(
+                (&definition.param_type, parameter),
+                (ParameterConstraint::DataType, TypeParameter::Type(_))
+                    | (
+                        ParameterConstraint::Integer { .. },
+                        TypeParameter::Integer(_)
+                    )
+                    | (
+                        ParameterConstraint::Enumeration { .. },
+                        TypeParameter::Enum(_)
+                    )
+                    | (ParameterConstraint::Boolean, TypeParameter::Boolean(_))
+                    | (ParameterConstraint::String, TypeParameter::String(_))
+                    | (_, TypeParameter::Null)
+            );
+            if !kind_matches || !definition.is_valid_value(&parameter.to_value()) {
+                return Err(ExtensionTypeError::InvalidParameterForDefinition {
+                    type_name: self.name.clone(),
+                    parameter_name: definition.name.clone(),
+                    value: parameter.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
     /// Create a new custom type with validation
     pub fn new(
         name: String,
@@ -572,6 +710,7 @@ impl CustomType {
             structure,
             variadic,
             description,
+            metadata: Default::default(),
         })
     }
 }
@@ -595,10 +734,7 @@ impl From<CustomType> for SimpleExtensionsTypesItem {
                             min,
                             max,
                             options: param.param_type.raw_options(),
-                            // TODO: add this to TypeParamDefsItem parsing, and
-                            // follow it through here. I'm not entirely sure
-                            // when/if it is used.
-                            optional: None,
+                            optional: param.optional.then_some(true),
                         }
                     })
                     .collect(),
@@ -611,7 +747,7 @@ impl From<CustomType> for SimpleExtensionsTypesItem {
         SimpleExtensionsTypesItem {
             name: value.name,
             description: value.description,
-            metadata: Default::default(),
+            metadata: value.metadata,
             parameters,
             structure,
             variadic: value.variadic,
@@ -659,6 +795,7 @@ impl Parse<TypeContext> for SimpleExtensionsTypesItem {
             structure,
             variadic: self.variadic,
             description: self.description,
+            metadata: self.metadata,
         })
     }
 }
@@ -670,9 +807,7 @@ impl Parse<TypeContext> for RawType {
     fn parse(self, ctx: &mut TypeContext) -> Result<Self::Parsed, Self::Error> {
         match self {
             RawType::String(type_string) => {
-                let parsed_type = TypeExpr::parse(&type_string)?;
-                let mut link = |name: &str| ctx.linked(name);
-                parsed_type.visit_references(&mut link);
+                let parsed_type = ctx.parse_type(&type_string)?;
                 let concrete = ConcreteType::try_from(parsed_type)?;
                 Ok(concrete)
             }
@@ -693,9 +828,7 @@ impl Parse<TypeContext> for RawType {
                         }
                     };
 
-                    let parsed_field_type = TypeExpr::parse(&type_string)?;
-                    let mut link = |name: &str| ctx.linked(name);
-                    parsed_field_type.visit_references(&mut link);
+                    let parsed_field_type = ctx.parse_type(&type_string)?;
                     let field_concrete_type = ConcreteType::try_from(parsed_field_type)?;
 
                     if fields
@@ -706,10 +839,7 @@ impl Parse<TypeContext> for RawType {
                     }
                 }
 
-                Ok(ConcreteType {
-                    kind: ConcreteTypeKind::NamedStruct { fields },
-                    nullable: false,
-                })
+                Ok(ConcreteType::named_struct(fields, false))
             }
         }
     }
@@ -725,7 +855,7 @@ pub struct InvalidTypeName(String);
 /// This is almost a complete type, but is missing nullability information. It must be
 /// wrapped in a [`ConcreteType`] to form a complete type with nullable/non-nullable annotation.
 ///
-/// Note that this is a recursive type - other than the [BuiltinType]s, the other variants can
+/// Note that this is a recursive type - other than the [BasicBuiltinType]s, the other variants can
 /// have type parameters that are themselves [ConcreteType]s.
 #[derive(Clone, Debug, PartialEq)]
 pub enum ConcreteTypeKind {
@@ -735,6 +865,13 @@ pub enum ConcreteTypeKind {
     Extension {
         /// Extension type name
         name: String,
+        /// The URN of the extension defining the type, if known.
+        ///
+        /// Types of plans are qualified by URN, as are types of function
+        /// signatures resolved through a [`Registry`](super::Registry). The
+        /// name of a qualified type is the name it is defined with, without
+        /// the alias of a dependency (`point` for `u!geo.point`).
+        urn: Option<Urn>,
         /// Type parameters
         parameters: Vec<TypeParameter>,
     },
@@ -757,35 +894,67 @@ pub enum ConcreteTypeKind {
     },
 }
 
+/// Write a type in the type string syntax, with the nullability marker and
+/// variation between the name and the parameters (e.g. `DECIMAL?[v]<10, 2>`
+/// or `u!point?`), so that the output can be parsed again.
+fn write_type(
+    f: &mut fmt::Formatter<'_>,
+    kind: &ConcreteTypeKind,
+    nullable: bool,
+    variation: Option<&str>,
+) -> fmt::Result {
+    let nullable = match (nullable, variation) {
+        (true, Some(variation)) => format!("?[{variation}]"),
+        (false, Some(variation)) => format!("[{variation}]"),
+        (true, None) => "?".to_string(),
+        (false, None) => String::new(),
+    };
+    match kind {
+        ConcreteTypeKind::Builtin(builtin) => {
+            let (name, parameters) = builtin.display_parts();
+            write!(f, "{name}{nullable}")?;
+            write_separated(f, parameters, "<", ">", ", ")
+        }
+        ConcreteTypeKind::Extension {
+            name, parameters, ..
+        } => {
+            write!(f, "u!{name}{nullable}")?;
+            write_separated(f, parameters.iter(), "<", ">", ", ")
+        }
+        ConcreteTypeKind::List(element) => write!(f, "list{nullable}<{element}>"),
+        ConcreteTypeKind::Map { key, value } => write!(f, "map{nullable}<{key}, {value}>"),
+        ConcreteTypeKind::Struct(fields) => {
+            write!(f, "struct{nullable}")?;
+            write_separated(f, fields.iter(), "<", ">", ", ")
+        }
+        ConcreteTypeKind::NamedStruct { fields } => {
+            let fields = fields.iter().map(|(k, v)| KeyValueDisplay(k, v, ": "));
+            write_separated(f, fields, "{", "}", ", ")?;
+            f.write_str(&nullable)
+        }
+    }
+}
+
 impl fmt::Display for ConcreteTypeKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            ConcreteTypeKind::Builtin(b) => write!(f, "{b}"),
-            ConcreteTypeKind::Extension { name, parameters } => {
-                write!(f, "{name}")?;
-                write_separated(f, parameters.iter(), "<", ">", ", ")
-            }
-            ConcreteTypeKind::List(elem) => write!(f, "list<{elem}>"),
-            ConcreteTypeKind::Map { key, value } => write!(f, "map<{key}, {value}>"),
-            ConcreteTypeKind::Struct(types) => {
-                write_separated(f, types.iter(), "struct<", ">", ", ")
-            }
-            ConcreteTypeKind::NamedStruct { fields } => {
-                let kvs = fields.iter().map(|(k, v)| KeyValueDisplay(k, v, ": "));
-
-                write_separated(f, kvs, "{", "}", ", ")
-            }
-        }
+        write_type(f, self, false, None)
     }
 }
 
 /// A concrete, fully-resolved type instance with nullability.
+///
+/// The constructors ([`ConcreteType::builtin`], [`ConcreteType::list`], ...)
+/// create types with the system-preferred variation, use
+/// [`ConcreteType::with_variation`] to set another variation.
 #[derive(Clone, Debug, PartialEq)]
 pub struct ConcreteType {
     /// The resolved type shape
     pub kind: ConcreteTypeKind,
     /// Whether this type is nullable
     pub nullable: bool,
+    /// The name of the type variation, if any (e.g. `dict_encoded` for
+    /// `i32[dict_encoded]`). `None` is the system-preferred variation.
+    pub variation: Option<String>,
 }
 
 impl ConcreteType {
@@ -794,6 +963,7 @@ impl ConcreteType {
         ConcreteType {
             kind: ConcreteTypeKind::Builtin(builtin_type),
             nullable,
+            variation: None,
         }
     }
 
@@ -802,9 +972,11 @@ impl ConcreteType {
         ConcreteType {
             kind: ConcreteTypeKind::Extension {
                 name,
+                urn: None,
                 parameters: Vec::new(),
             },
             nullable,
+            variation: None,
         }
     }
 
@@ -815,8 +987,13 @@ impl ConcreteType {
         nullable: bool,
     ) -> ConcreteType {
         ConcreteType {
-            kind: ConcreteTypeKind::Extension { name, parameters },
+            kind: ConcreteTypeKind::Extension {
+                name,
+                urn: None,
+                parameters,
+            },
             nullable,
+            variation: None,
         }
     }
 
@@ -825,6 +1002,7 @@ impl ConcreteType {
         ConcreteType {
             kind: ConcreteTypeKind::List(Box::new(element_type)),
             nullable,
+            variation: None,
         }
     }
 
@@ -833,6 +1011,7 @@ impl ConcreteType {
         ConcreteType {
             kind: ConcreteTypeKind::Struct(field_types),
             nullable,
+            variation: None,
         }
     }
 
@@ -844,6 +1023,7 @@ impl ConcreteType {
                 value: Box::new(value_type),
             },
             nullable,
+            variation: None,
         }
     }
 
@@ -852,6 +1032,82 @@ impl ConcreteType {
         ConcreteType {
             kind: ConcreteTypeKind::NamedStruct { fields },
             nullable,
+            variation: None,
+        }
+    }
+
+    /// Set the type variation of this type (e.g. `dict_encoded`)
+    pub fn with_variation(self, variation: impl Into<String>) -> ConcreteType {
+        ConcreteType {
+            variation: Some(variation.into()),
+            ..self
+        }
+    }
+
+    /// Visit all extension types referenced in this type (including this type
+    /// itself), calling `on_ext` with the name and parameters of each.
+    pub fn visit_extensions<F>(&self, on_ext: &mut F)
+    where
+        F: FnMut(&str, &[TypeParameter]),
+    {
+        match &self.kind {
+            ConcreteTypeKind::Builtin(_) => {}
+            ConcreteTypeKind::Extension {
+                name, parameters, ..
+            } => {
+                on_ext(name, parameters);
+                for parameter in parameters {
+                    if let TypeParameter::Type(t) = parameter {
+                        t.visit_extensions(on_ext);
+                    }
+                }
+            }
+            ConcreteTypeKind::List(element) => element.visit_extensions(on_ext),
+            ConcreteTypeKind::Map { key, value } => {
+                key.visit_extensions(on_ext);
+                value.visit_extensions(on_ext);
+            }
+            ConcreteTypeKind::Struct(fields) => {
+                fields.iter().for_each(|t| t.visit_extensions(on_ext))
+            }
+            ConcreteTypeKind::NamedStruct { fields } => {
+                fields.values().for_each(|t| t.visit_extensions(on_ext))
+            }
+        }
+    }
+
+    /// Qualify the extension types referenced in this type by the URN of the
+    /// extension defining them, as returned by `type_urn` for the name of the
+    /// type as written. Types that are already qualified are kept.
+    pub fn qualify(&mut self, type_urn: &impl Fn(&str) -> Option<Urn>) {
+        match &mut self.kind {
+            ConcreteTypeKind::Builtin(_) => {}
+            ConcreteTypeKind::Extension {
+                name,
+                urn,
+                parameters,
+            } => {
+                if urn.is_none() {
+                    *urn = type_urn(name);
+                    if urn.is_some() {
+                        *name = unaliased(name).to_string();
+                    }
+                }
+                for parameter in parameters {
+                    if let TypeParameter::Type(t) = parameter {
+                        t.qualify(type_urn);
+                    }
+                }
+            }
+            ConcreteTypeKind::List(element) => element.qualify(type_urn),
+            ConcreteTypeKind::Map { key, value } => {
+                key.qualify(type_urn);
+                value.qualify(type_urn);
+            }
+            ConcreteTypeKind::Struct(fields) => fields.iter_mut().for_each(|t| t.qualify(type_urn)),
+            ConcreteTypeKind::NamedStruct { fields } => {
+                fields.values_mut().for_each(|t| t.qualify(type_urn))
+            }
         }
     }
 
@@ -871,26 +1127,25 @@ impl ConcreteType {
 
 impl fmt::Display for ConcreteType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.kind)?;
-        if self.nullable {
-            write!(f, "?")?;
-        }
-        Ok(())
+        write_type(f, &self.kind, self.nullable, self.variation.as_deref())
     }
 }
 
 impl From<ConcreteType> for RawType {
     fn from(val: ConcreteType) -> Self {
+        // Types are written as signature types, which keep the `u!` prefix of
+        // extension types.
+        let type_string = |ty: ConcreteType| SignatureType::from(ty).to_string();
         match val.kind {
             ConcreteTypeKind::NamedStruct { fields } => {
                 let map = Map::from_iter(
                     fields
                         .into_iter()
-                        .map(|(name, ty)| (name, serde_json::Value::String(ty.to_string()))),
+                        .map(|(name, ty)| (name, serde_json::Value::String(type_string(ty)))),
                 );
                 RawType::Object(map)
             }
-            _ => RawType::String(val.to_string()),
+            _ => RawType::String(type_string(val)),
         }
     }
 }
@@ -969,7 +1224,11 @@ fn expect_type_argument<'a>(
 ) -> Result<ConcreteType, ExtensionTypeError> {
     match param {
         TypeExprParam::Type(t) => ConcreteType::try_from(t),
-        TypeExprParam::Integer(_) => Err(ExtensionTypeError::InvalidParameterKind {
+        // Bare names that are not types are unknown types here
+        TypeExprParam::Enum(name) => Err(ExtensionTypeError::UnknownTypeName {
+            name: name.to_string(),
+        }),
+        _ => Err(ExtensionTypeError::InvalidParameterKind {
             type_name: type_name.to_string(),
             index,
             expected: "a type",
@@ -984,14 +1243,37 @@ impl<'a> TryFrom<TypeExprParam<'a>> for TypeParameter {
         Ok(match param {
             TypeExprParam::Integer(v) => TypeParameter::Integer(v),
             TypeExprParam::Type(t) => TypeParameter::Type(ConcreteType::try_from(t)?),
+            TypeExprParam::Boolean(b) => TypeParameter::Boolean(b),
+            TypeExprParam::String(s) => TypeParameter::String(s.to_string()),
+            TypeExprParam::Enum(e) => TypeParameter::Enum(e.to_string()),
+            TypeExprParam::Null => TypeParameter::Null,
         })
     }
 }
 
+/// Create a built-in type from its lowercase name and integer parameters,
+/// validating the parameters as when parsing a type string (e.g. precision and
+/// scale of `decimal`).
+pub(crate) fn builtin_from_parts(
+    lower_name: &str,
+    params: &[i64],
+) -> Result<BasicBuiltinType, ExtensionTypeError> {
+    let params = params
+        .iter()
+        .copied()
+        .map(TypeExprParam::Integer)
+        .collect::<Vec<_>>();
+    parse_builtin(lower_name, lower_name, &params)?.ok_or_else(|| {
+        ExtensionTypeError::UnknownTypeName {
+            name: lower_name.to_string(),
+        }
+    })
+}
+
 /// Parse a builtin type. Returns an `ExtensionTypeError` if the type name is
 /// matched, but parameters are incorrect; returns `Some(None)` if the type is
 /// not known.
-fn parse_builtin<'a>(
+pub(super) fn parse_builtin<'a>(
     display_name: &str,
     lower_name: &str,
     params: &[TypeExprParam<'a>],
@@ -1053,64 +1335,77 @@ fn parse_builtin<'a>(
     }
 }
 
+/// Convert a type without the `u!` prefix: a builtin, list, map or struct
+/// type.
+fn simple_type<'a>(
+    name: &'a str,
+    params: Vec<TypeExprParam<'a>>,
+    nullable: bool,
+) -> Result<ConcreteType, ExtensionTypeError> {
+    let lower = name.to_ascii_lowercase();
+
+    match lower.as_str() {
+        "list" => {
+            expect_param_len(name, &params, 1)?;
+            let element = expect_type_argument(name, 0, params.into_iter().next().unwrap())?;
+            return Ok(ConcreteType::list(element, nullable));
+        }
+        "map" => {
+            expect_param_len(name, &params, 2)?;
+            let mut iter = params.into_iter();
+            let key = expect_type_argument(name, 0, iter.next().unwrap())?;
+            let value = expect_type_argument(name, 1, iter.next().unwrap())?;
+            return Ok(ConcreteType::map(key, value, nullable));
+        }
+        "struct" => {
+            let field_types = params
+                .into_iter()
+                .enumerate()
+                .map(|(idx, param)| expect_type_argument(name, idx, param))
+                .collect::<Result<Vec<_>, _>>()?;
+            return Ok(ConcreteType::r#struct(field_types, nullable));
+        }
+        _ => {}
+    }
+
+    if let Some(builtin) = parse_builtin(name, lower.as_str(), &params)? {
+        return Ok(ConcreteType::builtin(builtin, nullable));
+    }
+
+    // Simple types that aren't builtins are unknown
+    // Extension types MUST use the u! prefix
+    Err(ExtensionTypeError::UnknownTypeName {
+        name: name.to_string(),
+    })
+}
+
 impl<'a> TryFrom<TypeExpr<'a>> for ConcreteType {
     type Error = ExtensionTypeError;
 
     fn try_from(parsed_type: TypeExpr<'a>) -> Result<Self, Self::Error> {
-        match parsed_type {
-            TypeExpr::Simple(name, params, nullable) => {
-                let lower = name.to_ascii_lowercase();
-
-                match lower.as_str() {
-                    "list" => {
-                        expect_param_len(name, &params, 1)?;
-                        let element =
-                            expect_type_argument(name, 0, params.into_iter().next().unwrap())?;
-                        return Ok(ConcreteType::list(element, nullable));
-                    }
-                    "map" => {
-                        expect_param_len(name, &params, 2)?;
-                        let mut iter = params.into_iter();
-                        let key = expect_type_argument(name, 0, iter.next().unwrap())?;
-                        let value = expect_type_argument(name, 1, iter.next().unwrap())?;
-                        return Ok(ConcreteType::map(key, value, nullable));
-                    }
-                    "struct" => {
-                        let field_types = params
-                            .into_iter()
-                            .enumerate()
-                            .map(|(idx, param)| expect_type_argument(name, idx, param))
-                            .collect::<Result<Vec<_>, _>>()?;
-                        return Ok(ConcreteType::r#struct(field_types, nullable));
-                    }
-                    _ => {}
-                }
-
-                if let Some(builtin) = parse_builtin(name, lower.as_str(), &params)? {
-                    return Ok(ConcreteType::builtin(builtin, nullable));
-                }
-
-                // Simple types that aren't builtins are unknown
-                // Extension types MUST use the u! prefix
-                Err(ExtensionTypeError::UnknownTypeName {
-                    name: name.to_string(),
-                })
+        let (concrete, variation) = match parsed_type {
+            TypeExpr::Simple(name, params, nullable, variation) => {
+                (simple_type(name, params, nullable)?, variation)
             }
-            TypeExpr::UserDefined(name, params, nullable) => {
+            TypeExpr::UserDefined(name, params, nullable, variation) => {
                 let parameters = params
                     .into_iter()
                     .map(TypeParameter::try_from)
                     .collect::<Result<Vec<_>, _>>()?;
-                Ok(ConcreteType::extension_with_params(
-                    name.to_string(),
-                    parameters,
-                    nullable,
-                ))
+                (
+                    ConcreteType::extension_with_params(name.to_string(), parameters, nullable),
+                    variation,
+                )
             }
             TypeExpr::TypeVariable(id, nullability) => {
-                Err(ExtensionTypeError::InvalidAnyTypeVariable { id, nullability })
+                return Err(ExtensionTypeError::InvalidAnyTypeVariable { id, nullability });
             }
-        }
+            TypeExpr::Function(..) => return Err(ExtensionTypeError::FunctionType),
+        };
+        Ok(ConcreteType {
+            variation: variation.map(str::to_string),
+            ..concrete
+        })
     }
 }
 
@@ -1118,12 +1413,12 @@ impl<'a> TryFrom<TypeExpr<'a>> for ConcreteType {
 mod tests {
     use super::super::extensions::TypeContext;
     use super::*;
-    use crate::parse::text::simple_extensions::TypeExpr;
     use crate::parse::text::simple_extensions::argument::EnumOptions as ParsedEnumOptions;
+    use crate::parse::text::simple_extensions::{ExtensionFile, SimpleExtensionsError, TypeExpr};
     use crate::text::simple_extensions;
     use std::iter::FromIterator;
 
-    /// Create a [ConcreteType] from a [BuiltinType]
+    /// Create a [ConcreteType] from a [BasicBuiltinType]
     fn concretize(builtin: BasicBuiltinType) -> ConcreteType {
         ConcreteType::builtin(builtin, false)
     }
@@ -1404,6 +1699,107 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_literal_type_parameters() {
+        let parsed = parse_type("u!tagged<true, 'utf8', MODE_A, null>");
+        assert_eq!(
+            parsed,
+            extension(
+                "tagged",
+                vec![
+                    TypeParameter::Boolean(true),
+                    TypeParameter::String("utf8".to_string()),
+                    TypeParameter::Enum("MODE_A".to_string()),
+                    TypeParameter::Null,
+                ],
+                false,
+            )
+        );
+        assert_eq!(parsed.to_string(), "u!tagged<true, 'utf8', MODE_A, null>");
+
+        let yaml = r#"
+%YAML 1.2
+---
+urn: extension:example.com:literal_params
+types:
+  - name: tagged
+    parameters:
+      - name: flag
+        type: boolean
+      - name: encoding
+        type: string
+      - name: mode
+        type: enumeration
+        options: [MODE_A, MODE_B]
+      - name: label
+        type: string
+        optional: true
+  - name: unit
+    parameters:
+      - name: kind
+        type: enumeration
+        options: [DATE, TIME, DAYS]
+  - name: holder
+    structure:
+      value: STRUCTURE
+"#;
+        let read = |structure: &str| {
+            ExtensionFile::read_from_str(yaml.replace("STRUCTURE", structure))
+                .map(|_| ())
+                .map_err(|e| match e {
+                    SimpleExtensionsError::ExtensionTypeError(e) => e,
+                    e => panic!("unexpected error {e}"),
+                })
+        };
+
+        assert_eq!(read("u!tagged<true, 'utf8', MODE_A>"), Ok(()));
+        assert_eq!(read("u!tagged<false, 'utf8', MODE_B, null>"), Ok(()));
+        assert_eq!(read("u!tagged<false, 'a', MODE_B, 'b'>"), Ok(()));
+        // Options spelled as builtin types are parsed as types.
+        assert_eq!(read("u!unit<DATE>"), Ok(()));
+        assert_eq!(read("u!unit<DAYS>"), Ok(()));
+        assert_eq!(
+            read("u!unit<i32>"),
+            Err(ExtensionTypeError::InvalidParameterForDefinition {
+                type_name: "unit".to_string(),
+                parameter_name: "kind".to_string(),
+                value: "i32".to_string(),
+            })
+        );
+        assert_eq!(
+            read("u!tagged<true, 'utf8', MODE_C>"),
+            Err(ExtensionTypeError::InvalidParameterForDefinition {
+                type_name: "tagged".to_string(),
+                parameter_name: "mode".to_string(),
+                value: "MODE_C".to_string(),
+            })
+        );
+        assert_eq!(
+            read("u!tagged<'true', 'utf8', MODE_A>"),
+            Err(ExtensionTypeError::InvalidParameterForDefinition {
+                type_name: "tagged".to_string(),
+                parameter_name: "flag".to_string(),
+                value: "'true'".to_string(),
+            })
+        );
+        assert_eq!(
+            read("u!tagged<true, null, MODE_A>"),
+            Err(ExtensionTypeError::InvalidParameterForDefinition {
+                type_name: "tagged".to_string(),
+                parameter_name: "encoding".to_string(),
+                value: "null".to_string(),
+            })
+        );
+        assert_eq!(
+            read("u!tagged<true>"),
+            Err(ExtensionTypeError::InvalidParameterCount {
+                type_name: "tagged".to_string(),
+                expected: 3,
+                actual: 1,
+            })
+        );
+    }
+
     #[test]
     fn test_parameter_type_validation() {
         let int_param = ParameterConstraint::Integer {
@@ -1454,7 +1850,12 @@ mod tests {
                 "Map<List<I32?>, Struct<string, list<i64?>>>",
                 "map<list<i32?>, struct<string, list<i64?>>>",
             ),
-            ("u!custom<i32>", "custom<i32>"),
+            ("u!custom<i32>", "u!custom<i32>"),
+            ("DECIMAL?<10,2>", "DECIMAL?<10, 2>"),
+            ("List?[compact]<i32>", "list?[compact]<i32>"),
+            ("u!custom?<i32>", "u!custom?<i32>"),
+            ("struct<>", "struct"),
+            ("PRECISION_TIMESTAMP?<3>", "PRECISION_TIMESTAMP?<3>"),
         ];
 
         for (input, expected) in cases {
@@ -1466,6 +1867,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_type_display_parse_round_trip() {
+        for canonical in [
+            "i32?[dict]",
+            "DECIMAL?<10, 2>",
+            "VARCHAR?[compact]<8>",
+            "list?<i32?>",
+            "map?[sorted]<string, list<i64?>>",
+            "struct?<i8, INTERVAL_DAY?<6>>",
+            "u!custom?[v]<i32?, 'utf8', MODE_A>",
+        ] {
+            let concrete = parse_type(canonical);
+            assert_eq!(concrete.to_string(), canonical);
+            assert_eq!(parse_type(&concrete.to_string()), concrete);
+        }
+    }
+
     /// Test that named struct field order preserves the structure order when
     /// round-tripping through RawType (Substrait #915).
     #[test]
@@ -0,0 +1,312 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Window function definitions with validated signatures and resolved types.
+//!
+//! Window function implementations have the properties of aggregate function
+//! implementations ([`AggregateImpl`]), plus the kind of window they are
+//! evaluated over.
+
+use crate::text::simple_extensions::{
+    AggregateFunctionImplsItem as RawAggregateImpl, WindowFunction as RawWindowFunction,
+    WindowFunctionImplsItem as RawWindowImpl, WindowFunctionImplsItemWindowType as RawWindowType,
+};
+
+use super::aggregate_functions::{AggregateFunctionError, AggregateImpl};
+use super::diagnostics::FunctionKind;
+use super::extensions::TypeContext;
+use super::scalar_functions::ScalarFunctionError;
+use thiserror::Error;
+
+/// Errors that can occur when parsing window functions
+#[derive(Debug, Error, PartialEq)]
+pub enum WindowFunctionError {
+    /// Window function has no implementations
+    #[error("Window function '{name}' must have at least one implementation")]
+    NoImplementations {
+        /// The function name
+        name: String,
+    },
+    /// Error parsing the properties shared with aggregate function
+    /// implementations
+    #[error(transparent)]
+    Aggregate(#[from] AggregateFunctionError),
+}
+
+impl WindowFunctionError {
+    /// Attach the index of the implementation an error occurred in, see
+    /// [`AggregateFunctionError::in_impl`].
+    fn in_impl(self, index: usize) -> Self {
+        match self {
+            WindowFunctionError::Aggregate(err) => {
+                WindowFunctionError::Aggregate(err.in_impl(index))
+            }
+            err => err,
+        }
+    }
+}
+
+/// A validated window function definition with one or more implementations
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowFunction {
+    /// Function name
+    pub name: String,
+    /// Human-readable description
+    pub description: Option<String>,
+    /// Function implementations (overloads)
+    pub impls: Vec<WindowImpl>,
+    /// The `metadata` of the window function declaration, merged across
+    /// repeated declarations like [`ScalarFunction::metadata`](super::ScalarFunction::metadata).
+    pub metadata: serde_json::Map<String, serde_json::Value>,
+}
+
+impl WindowFunction {
+    /// Parse a window function from raw YAML, resolving types with the
+    /// provided context
+    ///
+    /// Implementations using features that are not yet supported are skipped,
+    /// see [`ParseOptions::strict`](super::ParseOptions::strict). Returns
+    /// `None` if all implementations are skipped.
+    pub(super) fn from_raw(
+        raw: RawWindowFunction,
+        ctx: &mut TypeContext,
+    ) -> Result<Option<Self>, WindowFunctionError> {
+        if raw.impls.is_empty() {
+            return Err(WindowFunctionError::NoImplementations { name: raw.name });
+        }
+
+        let mut impls = Vec::new();
+        for (index, impl_) in raw.impls.into_iter().enumerate() {
+            match WindowImpl::from_raw(impl_, ctx) {
+                Ok(impl_) => impls.push(impl_),
+                Err(WindowFunctionError::Aggregate(AggregateFunctionError::Impl(
+                    ScalarFunctionError::NotYetImplemented(reason),
+                ))) if !ctx.is_strict() => {
+                    ctx.skip_impl(FunctionKind::Window, &raw.name, index, reason);
+                }
+                Err(err) => return Err(err.in_impl(index)),
+            }
+        }
+
+        Ok((!impls.is_empty()).then(|| WindowFunction {
+            name: raw.name,
+            description: raw.description,
+            impls,
+            metadata: raw.metadata,
+        }))
+    }
+
+    /// Get the implementation with the given signature, i.e. the part of a
+    /// compound function name after the `:` (e.g. `any_i32` for
+    /// `lead:any_i32`).
+    pub fn get_impl(&self, signature: &str) -> Option<&WindowImpl> {
+        self.impls
+            .iter()
+            .find(|impl_| impl_.aggregate.impl_.signature() == signature)
+    }
+}
+
+impl From<WindowFunction> for RawWindowFunction {
+    fn from(function: WindowFunction) -> Self {
+        RawWindowFunction {
+            name: function.name,
+            description: function.description,
+            metadata: function.metadata,
+            impls: function.impls.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// A single window function implementation (overload)
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowImpl {
+    /// Arguments, return type and other properties shared with aggregate
+    /// function implementations
+    pub aggregate: AggregateImpl,
+    /// The kind of window the function is evaluated over.
+    ///
+    /// Defaults to [`WindowType::Partition`] per the Substrait spec.
+    pub window_type: WindowType,
+}
+
+impl WindowImpl {
+    /// Parse an implementation from raw YAML, resolving types with the
+    /// provided context
+    pub(super) fn from_raw(
+        raw: RawWindowImpl,
+        ctx: &mut TypeContext,
+    ) -> Result<Self, WindowFunctionError> {
+        let RawWindowImpl {
+            args,
+            decomposable,
+            deterministic,
+            implementation,
+            intermediate,
+            maxset,
+            nullability,
+            options,
+            ordered,
+            return_,
+            session_dependent,
+            variadic,
+            window_type,
+        } = raw;
+
+        // The properties shared with aggregate functions are parsed as such.
+        let aggregate = AggregateImpl::from_raw(
+            RawAggregateImpl {
+                args,
+                decomposable,
+                deterministic,
+                implementation,
+                intermediate,
+                maxset,
+                nullability,
+                options,
+                ordered,
+                return_,
+                session_dependent,
+                variadic,
+            },
+            ctx,
+        )?;
+
+        Ok(WindowImpl {
+            aggregate,
+            window_type: window_type.map(Into::into).unwrap_or(WindowType::Partition),
+        })
+    }
+}
+
+impl From<WindowImpl> for RawWindowImpl {
+    fn from(window_impl: WindowImpl) -> Self {
+        let WindowImpl {
+            aggregate,
+            window_type,
+        } = window_impl;
+        let RawAggregateImpl {
+            args,
+            decomposable,
+            deterministic,
+            implementation,
+            intermediate,
+            maxset,
+            nullability,
+            options,
+            ordered,
+            return_,
+            session_dependent,
+            variadic,
+        } = aggregate.into();
+
+        RawWindowImpl {
+            args,
+            decomposable,
+            deterministic,
+            implementation,
+            intermediate,
+            maxset,
+            nullability,
+            options,
+            ordered,
+            return_,
+            session_dependent,
+            variadic,
+            window_type: (window_type != WindowType::Partition).then(|| window_type.into()),
+        }
+    }
+}
+
+/// The kind of window a window function is evaluated over
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WindowType {
+    /// The function is evaluated over a sliding window of records, and can
+    /// produce results while the input is streamed
+    Streaming,
+    /// The function is evaluated over a whole partition of records
+    Partition,
+}
+
+impl From<RawWindowType> for WindowType {
+    fn from(raw: RawWindowType) -> Self {
+        match raw {
+            RawWindowType::Streaming => WindowType::Streaming,
+            RawWindowType::Partition => WindowType::Partition,
+        }
+    }
+}
+
+impl From<WindowType> for RawWindowType {
+    fn from(window_type: WindowType) -> Self {
+        match window_type {
+            WindowType::Streaming => RawWindowType::Streaming,
+            WindowType::Partition => RawWindowType::Partition,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::text::simple_extensions::{ExtensionFile, Registry};
+
+    const YAML: &str = r#"
+%YAML 1.2
+---
+urn: extension:example.com:windows
+window_functions:
+  - name: rank
+    description: The rank of the current row.
+    impls:
+      - args: []
+        nullability: DECLARED_OUTPUT
+        decomposable: NONE
+        return: i64?
+        window_type: PARTITION
+  - name: lead
+    impls:
+      - args:
+          - value: any1
+          - value: i32
+        nullability: DECLARED_OUTPUT
+        return: any1?
+        window_type: STREAMING
+"#;
+
+    #[test]
+    fn parse_window_functions() {
+        let file = ExtensionFile::read_from_str(YAML).unwrap();
+        let urn = file.urn().clone();
+        let mut names = file
+            .extension()
+            .window_functions()
+            .map(|function| function.name.as_str())
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, ["lead", "rank"]);
+
+        let registry = Registry::new([file]).unwrap();
+        let rank = registry.get_window_function(&urn, "rank").unwrap();
+        assert_eq!(rank.impls[0].window_type, WindowType::Partition);
+        assert!(rank.impls[0].aggregate.impl_.args.is_empty());
+
+        let lead = registry.get_window_function(&urn, "lead").unwrap();
+        let lead_impl = lead.get_impl("any_i32").unwrap();
+        assert_eq!(lead_impl.window_type, WindowType::Streaming);
+        assert_eq!(lead_impl.aggregate.impl_.return_type.to_string(), "any1?");
+
+        assert!(registry.get_window_function(&urn, "lag").is_none());
+    }
+
+    #[test]
+    fn round_trip() {
+        let file = ExtensionFile::read_from_str(YAML).unwrap();
+        let yaml = serde_yaml::to_string(&file.to_raw()).unwrap();
+        let parsed = ExtensionFile::read_from_str(yaml).unwrap();
+        for name in ["rank", "lead"] {
+            assert_eq!(
+                parsed.extension().get_window_function(name),
+                file.extension().get_window_function(name)
+            );
+        }
+    }
+}